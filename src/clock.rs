@@ -0,0 +1,158 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::vectors::Vector3i;
+
+//Ship time that passes each turn, every simulation steps by this much
+pub const SECONDS_PER_TURN: f32 = 1.0;
+
+//Elapsed game time, advanced once per tick by the clock system
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+pub struct GameClock {
+    pub turn: u64,
+    pub seconds: f32,
+}
+
+impl GameClock {
+    pub fn new() -> Self {
+        Self {
+            turn: 0,
+            seconds: 0.0,
+        }
+    }
+
+    pub fn advance(&mut self) {
+        self.turn += 1;
+        self.seconds += SECONDS_PER_TURN;
+    }
+
+    //Ship time as days since departure and a 24 hour clock
+    pub fn ship_time(&self) -> String {
+        let total = self.seconds as u64;
+        let days = total / 86400;
+        let hours = (total % 86400) / 3600;
+        let minutes = (total % 3600) / 60;
+        let seconds = total % 60;
+
+        format!(
+            "Day {} {:02}:{:02}:{:02}",
+            days + 1,
+            hours,
+            minutes,
+            seconds
+        )
+    }
+}
+
+//Something that happens to the world at a set time, targets are given by position so
+//pending events survive saving
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum ScheduledEvent {
+    //Writes to the game log
+    Message(String),
+    //Closes any open door at the position
+    CloseDoor(Vector3i),
+    //Turns the power switches at the position on or off
+    SetSwitch { position: Vector3i, on: bool },
+    //The hazard director's next emergency
+    Hazard,
+    //Something strikes the hull at the position
+    Impact { position: Vector3i, damage: f32 },
+    //The generator at the position has spun up and can deliver power
+    SpinUp(Vector3i),
+    //The delay timer at the position switches its output
+    TimerElapsed { position: Vector3i, on: bool },
+}
+
+impl ScheduledEvent {
+    //Events the clock system carries out itself, the rest wait for the system they belong to
+    pub fn is_world_event(&self) -> bool {
+        matches!(
+            self,
+            ScheduledEvent::Message(_)
+                | ScheduledEvent::CloseDoor(_)
+                | ScheduledEvent::SetSwitch { .. }
+        )
+    }
+}
+
+//Events waiting for their turn, keyed by the turn they fire on
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+pub struct EventScheduler {
+    pub events: BTreeMap<u64, Vec<ScheduledEvent>>,
+}
+
+impl EventScheduler {
+    pub fn new() -> Self {
+        Self {
+            events: BTreeMap::new(),
+        }
+    }
+
+    //Fires on the given turn, or on the next tick if that has already passed
+    pub fn schedule_at_turn(&mut self, turn: u64, event: ScheduledEvent) {
+        self.events.entry(turn).or_default().push(event);
+    }
+
+    pub fn schedule_in_turns(&mut self, clock: &GameClock, turns: u64, event: ScheduledEvent) {
+        self.schedule_at_turn(clock.turn + turns, event);
+    }
+
+    //Rounded up to whole turns, an event never fires early
+    pub fn schedule_in_seconds(&mut self, clock: &GameClock, seconds: f32, event: ScheduledEvent) {
+        let turns = (seconds.max(0.0) / SECONDS_PER_TURN).ceil() as u64;
+        self.schedule_at_turn(clock.turn + turns, event);
+    }
+
+    //Removes and returns everything due on or before the turn, oldest first
+    pub fn take_due(&mut self, turn: u64) -> Vec<ScheduledEvent> {
+        let later = self.events.split_off(&(turn + 1));
+        let due = std::mem::replace(&mut self.events, later);
+
+        due.into_values().flatten().collect()
+    }
+
+    //Like take_due, but only the events the filter picks, everything else stays scheduled
+    pub fn take_due_where<F: Fn(&ScheduledEvent) -> bool>(
+        &mut self,
+        turn: u64,
+        filter: F,
+    ) -> Vec<ScheduledEvent> {
+        let mut taken = Vec::new();
+
+        for (_, events) in self.events.range_mut(..=turn) {
+            let (picked, kept) = events.drain(..).partition(|event| filter(event));
+            *events = kept;
+            taken.extend(picked);
+        }
+        self.events.retain(|_, events| !events.is_empty());
+
+        taken
+    }
+
+    //Turn the event is next due on, if it is scheduled at all
+    pub fn turn_of(&self, event: &ScheduledEvent) -> Option<u64> {
+        self.events
+            .iter()
+            .find(|(_, events)| events.contains(event))
+            .map(|(turn, _)| *turn)
+    }
+
+    //Drops every pending copy of the event
+    pub fn cancel(&mut self, event: &ScheduledEvent) {
+        for events in self.events.values_mut() {
+            events.retain(|pending| pending != event);
+        }
+        self.events.retain(|_, events| !events.is_empty());
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.values().map(|events| events.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
@@ -8,6 +8,8 @@ use specs_derive::*;
 
 pub const R: f32 =  8.31446261815324;
 pub const K: f32 = 273.15;
+//Mean molar mass of breathable air, g/mol
+pub const AIR_MOLAR_MASS: f32 = 28.97;
 
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Hash, Debug, Copy)]
@@ -15,6 +17,112 @@ pub enum Gas {
     Oxygen,
     Nitrogen,
     CarbonDioxide,
+    CarbonMonoxide,
+    Hydrogen,
+    WaterVapour,
+    //Heavy, sweet smelling gas given off by the things growing in the dark
+    Miasma,
+}
+
+pub struct GasProperties {
+    //g/mol
+    pub molar_mass: f32,
+    //Molar heat capacity at constant volume, J/(mol K)
+    pub heat_capacity: f32,
+    //0.0 is inert, 1.0 burns as readily as hydrogen
+    pub flammability: f32,
+    //Partial pressure in Pa above which breathing the gas does harm
+    pub toxicity_threshold: Option<f32>,
+}
+
+impl Gas {
+    pub const ALL: [Gas; 7] = [
+        Gas::Oxygen,
+        Gas::Nitrogen,
+        Gas::CarbonDioxide,
+        Gas::CarbonMonoxide,
+        Gas::Hydrogen,
+        Gas::WaterVapour,
+        Gas::Miasma,
+    ];
+
+    pub fn properties(&self) -> GasProperties {
+        match self {
+            Gas::Oxygen => GasProperties {
+                molar_mass: 32.0,
+                heat_capacity: 21.0,
+                flammability: 0.0,
+                toxicity_threshold: Some(50000.0),
+            },
+            Gas::Nitrogen => GasProperties {
+                molar_mass: 28.0,
+                heat_capacity: 20.8,
+                flammability: 0.0,
+                toxicity_threshold: None,
+            },
+            Gas::CarbonDioxide => GasProperties {
+                molar_mass: 44.0,
+                heat_capacity: 28.9,
+                flammability: 0.0,
+                toxicity_threshold: Some(5000.0),
+            },
+            Gas::CarbonMonoxide => GasProperties {
+                molar_mass: 28.0,
+                heat_capacity: 20.8,
+                flammability: 0.4,
+                toxicity_threshold: Some(100.0),
+            },
+            Gas::Hydrogen => GasProperties {
+                molar_mass: 2.016,
+                heat_capacity: 20.4,
+                flammability: 1.0,
+                toxicity_threshold: None,
+            },
+            Gas::WaterVapour => GasProperties {
+                molar_mass: 18.0,
+                heat_capacity: 25.3,
+                flammability: 0.0,
+                toxicity_threshold: None,
+            },
+            Gas::Miasma => GasProperties {
+                molar_mass: 120.0,
+                heat_capacity: 35.0,
+                flammability: 0.2,
+                toxicity_threshold: Some(10.0),
+            },
+        }
+    }
+
+    //What the gas turns into when burnt with oxygen
+    pub fn combustion_product(&self) -> Option<Gas> {
+        match self {
+            Gas::Hydrogen => Some(Gas::WaterVapour),
+            Gas::CarbonMonoxide => Some(Gas::CarbonDioxide),
+            //Burns dirty
+            Gas::Miasma => Some(Gas::CarbonMonoxide),
+            _ => None,
+        }
+    }
+
+    //Relative diffusion speed by Graham's law, light gases are capped at the speed of air
+    pub fn diffusion_rate(&self) -> f32 {
+        (AIR_MOLAR_MASS / self.properties().molar_mass)
+            .sqrt()
+            .min(1.0)
+    }
+
+    //Name for messages, Display gives the variant name
+    pub fn readable_name(&self) -> &'static str {
+        match self {
+            Gas::Oxygen => "oxygen",
+            Gas::Nitrogen => "nitrogen",
+            Gas::CarbonDioxide => "carbon dioxide",
+            Gas::CarbonMonoxide => "carbon monoxide",
+            Gas::Hydrogen => "hydrogen",
+            Gas::WaterVapour => "water vapour",
+            Gas::Miasma => "miasma",
+        }
+    }
 }
 
 impl Display for Gas {
@@ -24,6 +132,10 @@ impl Display for Gas {
             Gas::Oxygen => gas_name = "Oxygen",
             Gas::Nitrogen => gas_name = "Nitrogen",
             Gas::CarbonDioxide => gas_name = "CarbonDioxide",
+            Gas::CarbonMonoxide => gas_name = "CarbonMonoxide",
+            Gas::Hydrogen => gas_name = "Hydrogen",
+            Gas::WaterVapour => gas_name = "WaterVapour",
+            Gas::Miasma => gas_name = "Miasma",
         }
         write!(f, "{}", gas_name)
     }
@@ -90,8 +202,13 @@ impl Atmosphere {
     }
     pub fn update_gas(&mut self, gasses: &HashMap<Gas, f32>, incoming_temperature: f32) {
         for (gas, delta_mols) in gasses.iter() {
-            //Recalculate temperature
-            self.temperature = ((self.get_total_mols() * self.temperature) + (delta_mols * incoming_temperature)) / (self.get_total_mols() + delta_mols);
+            //Recalculate temperature, weighted by how much heat each side holds
+            let current_capacity = self.get_heat_capacity();
+            let incoming_capacity = delta_mols * gas.properties().heat_capacity;
+
+            if current_capacity + incoming_capacity > 0.0 {
+                self.temperature = ((current_capacity * self.temperature) + (incoming_capacity * incoming_temperature)) / (current_capacity + incoming_capacity);
+            }
 
             //Apply new gas
             if let Some(current_mols) = self.gasses.get(&gas) {
@@ -228,6 +345,36 @@ impl Atmosphere {
         self.update_gas(&delta_gasses, self.temperature);
         self.dirty = true;
     }
+    //Total heat capacity of the gas in the tile, J/K
+    pub fn get_heat_capacity(&self) -> f32 {
+        self.gasses.iter().map(|(gas, mols)| mols * gas.properties().heat_capacity).sum()
+    }
+    pub fn add_heat(&mut self, joules: f32) {
+        let heat_capacity = self.get_heat_capacity();
+
+        if heat_capacity > 0.0 {
+            self.update_temperature(joules / heat_capacity);
+        }
+    }
+    pub fn get_partial_pressure(&self, gas: Gas) -> f32 {
+        if self.get_total_mols() == 0.0 {
+            return 0.0;
+        }
+        self.get_gas_ratio(gas) * self.pressure
+    }
+    //Gasses whose partial pressure is over their toxicity threshold
+    pub fn get_toxic_gasses(&self) -> Vec<Gas> {
+        let mut toxic_gasses: Vec<Gas> = self.gasses.keys()
+            .filter(|gas| match gas.properties().toxicity_threshold {
+                Some(threshold) => self.get_partial_pressure(**gas) > threshold,
+                None => false,
+            })
+            .cloned()
+            .collect();
+
+        toxic_gasses.sort_by_key(|gas| gas.to_string());
+        toxic_gasses
+    }
     pub fn update_temperature(&mut self, delta_t: f32) {
         //Apply new temperature
         self.temperature += delta_t;
@@ -247,7 +394,11 @@ impl Atmosphere {
         self.dirty = true;
     }
     pub fn recalculate_temperature(&mut self) {
-        self.temperature = self.pressure / (self.get_total_mols() * R);
+        //An empty tile has no temperature to derive, keep the last one instead of dividing by zero
+        let total_mols = self.get_total_mols();
+        if total_mols > 0.0 {
+            self.temperature = self.pressure / (total_mols * R);
+        }
         self.dirty = true;
     }
     pub fn get_gas_ratio(&self, gas: Gas) -> f32 {
@@ -8,55 +8,238 @@ use specs_derive::*;
 use super::atmospherics::Atmosphere;
 use super::atmospherics::Gas;
 
+//Sea level pressure in pascal, the ideal ratios of a breather are given at this pressure
+pub const ONE_ATMOSPHERE: f32 = 101325.0;
+//Blood levels move this far toward the levels in the lungs every breath
+const BLOOD_EXCHANGE_RATE: f32 = 0.05;
+
 #[derive(Component, Default, Serialize, Deserialize, Clone)]
 pub struct Breather {
     //Gas, ideal mols, needed ratio to reach ideal
     pub in_gasses: HashMap<Gas, (f32, f32)>,
+    //Mols breathed out when every in gas is at its ideal
     pub out_gasses: HashMap<Gas, f32>,
-    pub trigger_breath: bool,
     pub temperature: f32,
+    //Toxic gasses in the last breath, so choking is only reported when it starts
+    #[serde(default)]
+    pub toxic_exposure: Vec<Gas>,
+}
+
+//What a single breath took in
+pub struct BreathResult {
+    //0.0 is nothing usable, 1.0 is every in gas at its ideal
+    pub satisfaction: f32,
+    pub toxic_gasses: Vec<Gas>,
+    pub carbon_dioxide_pressure: f32,
 }
 
 impl Breather {
     pub fn new_humanlike() -> Self {
-        let in_gasses = HashMap::new();
-        //in_gasses.insert(Gas::Oxygen, (0.004125723, 0.21));
-        //in_gasses.insert(Gas::Nitrogen, (0.015324114, 0.78));
+        //Sped up a few hundred times over a real person so a sealed room runs out within a shift
+        let mut in_gasses = HashMap::new();
+        in_gasses.insert(Gas::Oxygen, (0.05, 0.21));
 
         let mut out_gasses = HashMap::new();
-        //out_gasses.insert(Gas::CarbonDioxide, 0.000785852);
-        out_gasses.insert(Gas::CarbonDioxide, 10.0);
-        //out_gasses.insert(Gas::Oxygen, 0.003143408);
-        //out_gasses.insert(Gas::Nitrogen, 0.015324114);
+        out_gasses.insert(Gas::CarbonDioxide, 0.04);
 
         Self {
             in_gasses,
             out_gasses,
-            trigger_breath: false,
             temperature: 288.15,
+            toxic_exposure: Vec::new(),
         }
     }
-    pub fn breath(&mut self, atmosphere: &mut Atmosphere) {
+    pub fn breath(&mut self, atmosphere: &mut Atmosphere) -> BreathResult {
+        let toxic_gasses = atmosphere.get_toxic_gasses();
+        let carbon_dioxide_pressure = atmosphere.get_partial_pressure(Gas::CarbonDioxide);
+
         let mut gas_changes = Vec::new();
+        let mut satisfaction: f32 = 1.0;
+
+        for (gas, (consumption, ideal_ratio)) in self.in_gasses.iter() {
+            let ideal_pressure = ideal_ratio * ONE_ATMOSPHERE;
+            let gas_satisfaction = if ideal_pressure > 0.0 {
+                (atmosphere.get_partial_pressure(*gas) / ideal_pressure).min(1.0)
+            } else {
+                1.0
+            };
+            let available = atmosphere.gasses.get(gas).cloned().unwrap_or(0.0);
+
+            gas_changes.push((*gas, (consumption * gas_satisfaction).min(available)));
+            satisfaction = satisfaction.min(gas_satisfaction);
+        }
 
-        for (atmosphere_gas, _) in atmosphere
-            .gasses
-            .iter()
-            .filter(|(gas, _)| self.in_gasses.contains_key(gas))
-        {
-            if let Some((consumption, ideal_ratio)) = self.in_gasses.get(atmosphere_gas) {
-                let amount =
-                    consumption * (ideal_ratio * atmosphere.get_gas_ratio(*atmosphere_gas));
-                gas_changes.push((*atmosphere_gas, amount));
-            }
-        }
-        // Then, apply all the changes
         for (gas, amount) in gas_changes {
             atmosphere.remove_single_gas(gas, amount);
         }
 
-        //TODO: Make the output gasses dependent on the linked input gas
-        atmosphere.update_gas(&self.out_gasses, self.temperature);
+        //Only what was actually burnt comes back out
+        let out_gasses: HashMap<Gas, f32> = self
+            .out_gasses
+            .iter()
+            .map(|(gas, mols)| (*gas, mols * satisfaction))
+            .filter(|(_, mols)| *mols > 0.0)
+            .collect();
+
+        atmosphere.update_gas(&out_gasses, self.temperature);
         atmosphere.dirty = true;
+
+        BreathResult {
+            satisfaction,
+            toxic_gasses,
+            carbon_dioxide_pressure,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Default)]
+pub enum VitalState {
+    #[default]
+    Healthy,
+    Dizzy,
+    Unconscious,
+    Dead,
+}
+
+//Blood gas levels of a breather
+#[derive(Component, Serialize, Deserialize, Clone, Debug)]
+pub struct Vitals {
+    //Oxygen saturation, 1.0 is healthy
+    pub blood_oxygen: f32,
+    //Carbon dioxide relative to the level that starts to hurt, 0.0 is healthy
+    pub blood_carbon_dioxide: f32,
+    pub state: VitalState,
+}
+
+impl Default for Vitals {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Vitals {
+    pub fn new() -> Self {
+        Self {
+            blood_oxygen: 1.0,
+            blood_carbon_dioxide: 0.0,
+            state: VitalState::Healthy,
+        }
+    }
+
+    pub fn update(&mut self, breath: &BreathResult) {
+        let carbon_dioxide_threshold = Gas::CarbonDioxide
+            .properties()
+            .toxicity_threshold
+            .unwrap_or(ONE_ATMOSPHERE);
+        let carbon_dioxide_level = breath.carbon_dioxide_pressure / carbon_dioxide_threshold;
+
+        self.blood_oxygen += (breath.satisfaction - self.blood_oxygen) * BLOOD_EXCHANGE_RATE;
+        self.blood_carbon_dioxide +=
+            (carbon_dioxide_level - self.blood_carbon_dioxide) * BLOOD_EXCHANGE_RATE;
+    }
+
+    //The worst of the two blood levels, nobody comes back from dead
+    pub fn assess(&self) -> VitalState {
+        if self.state == VitalState::Dead {
+            return VitalState::Dead;
+        }
+
+        let oxygen_state = if self.blood_oxygen < 0.25 {
+            VitalState::Dead
+        } else if self.blood_oxygen < 0.5 {
+            VitalState::Unconscious
+        } else if self.blood_oxygen < 0.75 {
+            VitalState::Dizzy
+        } else {
+            VitalState::Healthy
+        };
+
+        let carbon_dioxide_state = if self.blood_carbon_dioxide >= 4.0 {
+            VitalState::Dead
+        } else if self.blood_carbon_dioxide >= 2.0 {
+            VitalState::Unconscious
+        } else if self.blood_carbon_dioxide >= 1.0 {
+            VitalState::Dizzy
+        } else {
+            VitalState::Healthy
+        };
+
+        oxygen_state.max(carbon_dioxide_state)
+    }
+
+    pub fn is_incapacitated(&self) -> bool {
+        self.state >= VitalState::Unconscious
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum BodyTemperatureState {
+    #[default]
+    Normal,
+    Cold,
+    Hypothermic,
+    Hot,
+    Hyperthermic,
+}
+
+//Core temperature of a warm blooded body, kept up by metabolism and lost to the air around it
+#[derive(Component, Serialize, Deserialize, Clone, Debug)]
+pub struct Thermoregulation {
+    pub temperature: f32,
+    pub target_temperature: f32,
+    //J/K
+    pub heat_capacity: f32,
+    //W/K lost to the air at one atmosphere
+    pub conductance: f32,
+    //W produced at rest
+    pub metabolic_heat: f32,
+    pub state: BodyTemperatureState,
+}
+
+impl Thermoregulation {
+    pub fn new_humanlike() -> Self {
+        Self {
+            temperature: 310.15,
+            target_temperature: 310.15,
+            heat_capacity: 20000.0,
+            conductance: 4.5,
+            metabolic_heat: 100.0,
+            state: BodyTemperatureState::Normal,
+        }
+    }
+
+    //Shivering when cold, up to half again the resting heat
+    pub fn metabolism(&self) -> f32 {
+        let shiver = ((self.target_temperature - self.temperature) / 2.0).clamp(0.0, 0.5);
+        self.metabolic_heat * (1.0 + shiver)
+    }
+
+    //Blood vessels close up when cold and sweat opens the body up when hot
+    pub fn effective_conductance(&self) -> f32 {
+        let regulation = (1.0 + (self.temperature - self.target_temperature)).clamp(0.6, 3.0);
+        self.conductance * regulation
+    }
+
+    pub fn assess(&self) -> BodyTemperatureState {
+        let delta = self.temperature - self.target_temperature;
+
+        if delta <= -5.0 {
+            BodyTemperatureState::Hypothermic
+        } else if delta <= -2.0 {
+            BodyTemperatureState::Cold
+        } else if delta >= 4.0 {
+            BodyTemperatureState::Hyperthermic
+        } else if delta >= 1.5 {
+            BodyTemperatureState::Hot
+        } else {
+            BodyTemperatureState::Normal
+        }
+    }
+
+    pub fn is_harmful(&self) -> bool {
+        matches!(
+            self.state,
+            BodyTemperatureState::Hypothermic | BodyTemperatureState::Hyperthermic
+        )
     }
 }
@@ -1,5 +1,7 @@
 use std::collections::HashSet;
 
+use crate::clock::{EventScheduler, GameClock};
+use crate::hazards::HazardDirector;
 use crate::map;
 use crate::vectors::Vector3i;
 use rltk::{RGB, RGBA};
@@ -275,6 +277,24 @@ pub struct SerializeThis;
 #[derive(Component, Default, Serialize, Deserialize, Clone)]
 pub struct SerializationHelper {
     pub map: map::Map,
+    pub clock: GameClock,
+    pub scheduler: EventScheduler,
+    pub hazard_director: HazardDirector,
+}
+
+//Turns a closing door without its own AutoClose waits before trying again when someone stands in the doorway
+pub const DOOR_CLOSE_TURNS: u64 = 20;
+
+//Swings a door shut again the given number of turns after it was opened by hand
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct AutoClose {
+    pub turns: u64,
+}
+
+impl AutoClose {
+    pub fn new(turns: u64) -> Self {
+        Self { turns }
+    }
 }
 
 #[derive(Component, Default, ConvertSaveload, Clone)]
@@ -399,6 +419,26 @@ impl InContainer {
     }
 }
 
+#[derive(Component, Default, Serialize, Deserialize, Clone)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Self { current: max, max }
+    }
+
+    pub fn damage(&mut self, amount: f32) {
+        self.current = (self.current - amount).max(0.0);
+    }
+}
+
+//On a character while a breach drags them along, so the pull is only announced once
+#[derive(Component, Default, Serialize, Deserialize, Clone)]
+pub struct Dragged {}
+
 #[derive(Component, Default, Serialize, Deserialize, Clone)]
 pub struct Installed {}
 
@@ -0,0 +1,138 @@
+use std::ops::Deref;
+
+use serde::{Deserialize, Serialize};
+use specs::{prelude::*, storage::MaskedStorage};
+use specs_derive::*;
+
+use crate::{
+    entities::crew::CrewAi,
+    map::{structure::Material, zones::AtmosphereZones},
+    vectors::{utils::get_cardinal_neighbours_with_z, Vector3i},
+    Map, Viewshed,
+};
+
+//Turns it takes to take a section or a door apart
+pub const DECONSTRUCT_COST: f32 = 8.0;
+
+//Carried stock that can be built with, one item goes into one section
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct ConstructionMaterial {
+    pub material: Material,
+}
+
+impl ConstructionMaterial {
+    pub fn new(material: Material) -> Self {
+        Self { material }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Construction {
+    Hull,
+    GlassHull,
+    Door,
+}
+
+impl Construction {
+    pub const ALL: [Construction; 3] = [
+        Construction::Hull,
+        Construction::GlassHull,
+        Construction::Door,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Construction::Hull => "Hull section",
+            Construction::GlassHull => "Glass hull section",
+            Construction::Door => "Door",
+        }
+    }
+
+    pub fn material(&self) -> Material {
+        match self {
+            Construction::Hull | Construction::Door => Material::Steel,
+            Construction::GlassHull => Material::Glass,
+        }
+    }
+
+    //Tile the construction turns into, doors are entities instead
+    pub fn blueprint(&self) -> Option<&'static str> {
+        match self {
+            Construction::Hull => Some("hull"),
+            Construction::GlassHull => Some("glass_hull"),
+            Construction::Door => None,
+        }
+    }
+
+    pub fn cost(&self) -> f32 {
+        match self {
+            Construction::Hull => 10.0,
+            Construction::GlassHull => 8.0,
+            Construction::Door => 6.0,
+        }
+    }
+}
+
+//What taking something of this material apart gives back
+pub fn salvage_item(material: Material) -> Option<&'static str> {
+    match material {
+        Material::Steel => Some("steel_plating"),
+        Material::Glass => Some("glass_pane"),
+        Material::None | Material::Debris => None,
+    }
+}
+
+//Makes everything that breathes, sees or walks through the changed tile take another look
+pub fn refresh_around<D>(
+    position: Vector3i,
+    map: &mut Map,
+    zones: &mut AtmosphereZones,
+    positions: &Storage<Vector3i, D>,
+    viewsheds: &mut WriteStorage<Viewshed>,
+    crew_ais: &mut WriteStorage<CrewAi>,
+) where
+    D: Deref<Target = MaskedStorage<Vector3i>>,
+{
+    for neighbour in get_cardinal_neighbours_with_z(position)
+        .into_iter()
+        .chain([position])
+    {
+        if let Some(tile) = map.tiles.get_mut(&neighbour) {
+            tile.atmosphere.dirty = true;
+        }
+    }
+    zones.dirty = true;
+
+    for (viewshed, viewer_position) in (viewsheds, positions).join() {
+        if position.distance_to_int(*viewer_position) <= viewshed.view_distance as i32 {
+            viewshed.dirty = true;
+        }
+    }
+
+    //Paths through the tile may be blocked or shorter now, walk to the same place again
+    for crew_ai in crew_ais.join() {
+        if crew_ai.path.contains(&position) {
+            if let Some(destination) = crew_ai.path.last().copied() {
+                crew_ai.path = vec![destination];
+            }
+        }
+    }
+}
+
+//The same for changes made through the world, such as doors spawned after the systems ran
+pub fn refresh_world_around(ecs: &mut World, position: Vector3i) {
+    let mut map = ecs.fetch_mut::<Map>();
+    let mut zones = ecs.fetch_mut::<AtmosphereZones>();
+    let positions = ecs.write_storage::<Vector3i>();
+    let mut viewsheds = ecs.write_storage::<Viewshed>();
+    let mut crew_ais = ecs.write_storage::<CrewAi>();
+
+    refresh_around(
+        position,
+        &mut map,
+        &mut zones,
+        &positions,
+        &mut viewsheds,
+        &mut crew_ais,
+    );
+}
@@ -0,0 +1,71 @@
+use serde::Deserialize;
+use serde::Serialize;
+use specs::prelude::*;
+use specs_derive::*;
+
+use crate::vectors::Vector3i;
+
+//What a crew member is currently trying to get done, in order of urgency
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum CrewGoal {
+    //Get out of bad air
+    Breathe,
+    //Shut an open door onto a breach
+    CloseDoor,
+    //Turn a control panel that has been switched off back on
+    ResetBreaker,
+    //Pick up something left lying around
+    FetchItem,
+}
+
+impl CrewGoal {
+    pub fn description(&self) -> &'static str {
+        match self {
+            CrewGoal::Breathe => "heads for breathable air",
+            CrewGoal::CloseDoor => "goes to seal a breach",
+            CrewGoal::ResetBreaker => "goes to reset a breaker",
+            CrewGoal::FetchItem => "goes to fetch an item",
+        }
+    }
+}
+
+//Drives an NPC through the same intents the player uses
+#[derive(Component, Serialize, Deserialize, Clone, Default)]
+pub struct CrewAi {
+    pub goal: Option<CrewGoal>,
+    //Position of the thing the goal is about
+    pub target: Option<Vector3i>,
+    //Remaining steps, not including the current position
+    pub path: Vec<Vector3i>,
+    //Turns spent trying to reach the next step of the path
+    pub stuck_turns: u32,
+    //Turns to wait before looking for another goal
+    pub idle_turns: u32,
+}
+
+impl CrewAi {
+    pub fn new() -> Self {
+        Self {
+            goal: None,
+            target: None,
+            path: Vec::new(),
+            stuck_turns: 0,
+            idle_turns: 0,
+        }
+    }
+
+    pub fn set_goal(&mut self, goal: CrewGoal, target: Vector3i, path: Vec<Vector3i>) {
+        self.goal = Some(goal);
+        self.target = Some(target);
+        self.path = path;
+        self.stuck_turns = 0;
+    }
+
+    pub fn clear_goal(&mut self, idle_turns: u32) {
+        self.goal = None;
+        self.target = None;
+        self.path.clear();
+        self.stuck_turns = 0;
+        self.idle_turns = idle_turns;
+    }
+}
@@ -0,0 +1,38 @@
+use serde::Deserialize;
+use serde::Serialize;
+use specs::prelude::*;
+use specs_derive::*;
+
+//A prop or item that burns once its tile is hot enough
+#[derive(Component, Default, Serialize, Deserialize, Clone)]
+pub struct Flammable {
+    //Mols of oxygen the prop can burn through before it is consumed
+    pub fuel: f32,
+    pub ignition_temperature: f32,
+}
+
+impl Flammable {
+    pub fn new(fuel: f32, ignition_temperature: f32) -> Self {
+        Self {
+            fuel,
+            ignition_temperature,
+        }
+    }
+}
+
+//Marks a burning tile, the entity carries the light and glyph of the fire
+#[derive(Component, Default, Serialize, Deserialize, Clone)]
+pub struct Fire {
+    //0.0 is burnt out, 1.0 is burning as fast as the tile allows
+    pub intensity: f32,
+}
+
+impl Fire {
+    pub fn new() -> Self {
+        Self { intensity: 1.0 }
+    }
+}
+
+//On anything with health standing in a fire, so catching fire is only announced once
+#[derive(Component, Default, Serialize, Deserialize, Clone)]
+pub struct Burning {}
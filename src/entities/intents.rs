@@ -1,4 +1,29 @@
-use crate::vectors::Vector3i;
+use crate::clock::{EventScheduler, GameClock, ScheduledEvent, SECONDS_PER_TURN};
+use crate::entities::construction::{
+    refresh_around, refresh_world_around, salvage_item, Construction, ConstructionMaterial,
+    DECONSTRUCT_COST,
+};
+use crate::entities::components::AutoClose;
+use crate::entities::crew::CrewAi;
+use crate::entities::logic::{DelayTimer, Sensor, Signal};
+use crate::entities::network::Terminal;
+use crate::entities::power_components::{
+    Battery, Broken, ControlPanel, Fuel, Generator, GeneratorFuel,
+};
+use crate::gamelog::GameLog;
+use crate::graphics::char_to_glyph;
+use crate::map::{structure::Material, tile_blueprints, zones::AtmosphereZones};
+use crate::raws::spawn_named;
+use crate::spawner;
+use crate::states::RunState;
+use crate::systems::event_system::{check_entity_blocking, queue_intent, InteractionInformation};
+use crate::systems::structural_system::replace_tile;
+use crate::vectors::{utils, Vector3i};
+use crate::{
+    update_camera_position, Blocker, Camera, Container, Door, Illuminant, InContainer, Installed,
+    Item, Map, Name, Photometry, PowerNode, PowerSwitch, Viewshed,
+};
+use rltk::RGB;
 use serde::Deserialize;
 use serde::Serialize;
 use specs::error::NoError;
@@ -6,12 +31,71 @@ use specs::prelude::*;
 use specs::saveload::{ConvertSaveload, Marker};
 use specs_derive::*;
 
+//Every interactable component with its storage in the intent context, handed to the given macro
+//so the interaction menu and the interact intent go through the same list
+macro_rules! with_interactables {
+    ($callback:ident) => {
+        $callback!(
+            power_switches: $crate::entities::power_components::PowerSwitch,
+            doors: $crate::entities::components::Door,
+            batteries: $crate::entities::power_components::Battery,
+            generators: $crate::entities::power_components::Generator,
+            terminals: $crate::entities::network::Terminal,
+            signals: $crate::entities::logic::Signal,
+            sensors: $crate::entities::logic::Sensor,
+            delay_timers: $crate::entities::logic::DelayTimer
+        )
+    };
+}
+pub(crate) use with_interactables;
+
+//Everything an intent may touch when it executes, fetched once per run of the event system
+#[derive(SystemData)]
+pub struct IntentContext<'a> {
+    pub entities: Entities<'a>,
+    pub map: WriteExpect<'a, Map>,
+    pub game_log: WriteExpect<'a, GameLog>,
+    pub player: ReadExpect<'a, Entity>,
+    pub player_position: WriteExpect<'a, Vector3i>,
+    pub positions: WriteStorage<'a, Vector3i>,
+    pub names: ReadStorage<'a, Name>,
+    pub power_switches: WriteStorage<'a, PowerSwitch>,
+    pub power_nodes: WriteStorage<'a, PowerNode>,
+    pub breakers: ReadStorage<'a, ControlPanel>,
+    pub doors: WriteStorage<'a, Door>,
+    pub auto_closes: ReadStorage<'a, AutoClose>,
+    pub viewsheds: WriteStorage<'a, Viewshed>,
+    pub photometria: WriteStorage<'a, Photometry>,
+    pub illuminants: WriteStorage<'a, Illuminant>,
+    pub blockers: ReadStorage<'a, Blocker>,
+    pub cameras: ReadStorage<'a, Camera>,
+    pub containers: WriteStorage<'a, Container>,
+    pub in_container: WriteStorage<'a, InContainer>,
+    pub items: ReadStorage<'a, Item>,
+    pub zones: WriteExpect<'a, AtmosphereZones>,
+    pub lazy_update: Read<'a, LazyUpdate>,
+    pub construction_materials: ReadStorage<'a, ConstructionMaterial>,
+    pub installed: ReadStorage<'a, Installed>,
+    pub crew_ais: WriteStorage<'a, CrewAi>,
+    pub batteries: WriteStorage<'a, Battery>,
+    pub generators: WriteStorage<'a, Generator>,
+    pub fuels: ReadStorage<'a, Fuel>,
+    pub terminals: WriteStorage<'a, Terminal>,
+    pub signals: WriteStorage<'a, Signal>,
+    pub sensors: WriteStorage<'a, Sensor>,
+    pub delay_timers: WriteStorage<'a, DelayTimer>,
+    pub broken: WriteStorage<'a, Broken>,
+    pub clock: ReadExpect<'a, GameClock>,
+    pub scheduler: WriteExpect<'a, EventScheduler>,
+    //Intents that need the game to switch state, such as opening an inventory, set it here
+    pub run_state: WriteExpect<'a, RunState>,
+}
+
 #[derive(Component, Clone)]
 pub struct MoveIntent {
     pub current_position: Vector3i,
     pub delta: Vector3i,
-    pub cost: f32,
-    pub remaining_cost: f32,
+    pub progress: IntentProgress,
 }
 
 impl MoveIntent {
@@ -31,23 +115,66 @@ impl MoveIntent {
         Self {
             current_position,
             delta,
-            cost,
-            remaining_cost: cost,
+            progress: IntentProgress::new(cost),
         }
     }
 }
 
 impl Intent for MoveIntent {
-    fn get_cost(&self) -> f32 {
-        self.cost
+    fn intent_progress(&self) -> &IntentProgress {
+        &self.progress
     }
-    fn get_remaining_cost(&self) -> f32 {
-        self.remaining_cost
+    fn intent_progress_mut(&mut self) -> &mut IntentProgress {
+        &mut self.progress
     }
-    fn update_remaining_cost(&mut self, delta: f32) {
-        self.remaining_cost += delta;
+    fn describe(&self) -> String {
+        "Moving".to_string()
+    }
+    fn execute(&mut self, entity: Entity, context: &mut IntentContext) {
+        let target_position = self.current_position + self.delta;
+
+        //TODO: Add exceptions here for if a player might need to move through solid tiles
+        match context.map.tiles.get(&target_position) {
+            Some(tile) if tile.passable => {}
+            _ => return,
+        }
+
+        //If the movement is diagonal, blocks of four entites must be checked since the player passes through all four
+        if check_entity_blocking(
+            &context.blockers,
+            &context.positions,
+            self.current_position,
+            target_position,
+        ) {
+            return;
+        }
+
+        //Update position
+        if let Some(new_position) = context.positions.get_mut(entity) {
+            *new_position = target_position;
+        }
+
+        if let Some(viewshed) = context.viewsheds.get_mut(entity) {
+            viewshed.dirty = true;
+        }
+
+        if let Some(photometry) = context.photometria.get_mut(entity) {
+            photometry.dirty = true;
+        }
+
+        if let Some(illuminant) = context.illuminants.get_mut(entity) {
+            illuminant.dirty = true;
+        }
+
+        //Only the player drags the camera and position tracker along
+        if entity == *context.player {
+            context.game_log.entries.push(target_position.to_string());
+
+            *context.player_position = target_position;
+
+            update_camera_position(self.delta, &context.cameras, &mut context.positions);
+        }
     }
-    fn execute(&mut self) {}
 }
 
 #[derive(Component, Debug, ConvertSaveload, Clone)]
@@ -72,8 +199,7 @@ pub struct InteractIntent {
     pub target: Entity,
     pub interaction_id: u32,
     pub interaction_description: String,
-    pub cost: f32,
-    pub remaining_cost: f32,
+    pub progress: IntentProgress,
 }
 
 impl InteractIntent {
@@ -89,23 +215,88 @@ impl InteractIntent {
             target,
             interaction_id,
             interaction_description,
-            cost,
-            remaining_cost: cost,
+            progress: IntentProgress::new(cost),
         }
     }
 }
 
 impl Intent for InteractIntent {
-    fn get_cost(&self) -> f32 {
-        self.cost
+    fn intent_progress(&self) -> &IntentProgress {
+        &self.progress
     }
-    fn get_remaining_cost(&self) -> f32 {
-        self.remaining_cost
+    fn intent_progress_mut(&mut self) -> &mut IntentProgress {
+        &mut self.progress
     }
-    fn update_remaining_cost(&mut self, delta: f32) {
-        self.remaining_cost += delta;
+    fn describe(&self) -> String {
+        self.interaction_description.clone()
+    }
+    fn from_interaction(
+        initiator: Entity,
+        target: Entity,
+        interaction: &InteractionInformation,
+    ) -> Option<Self> {
+        Some(InteractIntent::new(
+            initiator,
+            target,
+            interaction.id,
+            interaction.description.clone(),
+            interaction.cost,
+        ))
+    }
+    fn execute(&mut self, _entity: Entity, context: &mut IntentContext) {
+        macro_rules! interact_with {
+            ( $( $storage:ident: $typ:ty ),* ) => {
+                $(
+                    if let Some(component) = context.$storage.get_mut(self.target) {
+                        if component.interaction_id == self.interaction_id {
+                            component.interact();
+
+                            if let Some(name) = context.names.get(self.target) {
+                                context.game_log.entries.push(format!("{}: {}", name.name, self.interaction_description));
+                            } else {
+                                context.game_log.entries.push("Invalid intent".to_string());
+                            }
+                        }
+                    }
+                )*
+            };
+        }
+
+        with_interactables!(interact_with);
+
+        //A door that closes by itself swings shut again after a while
+        if let (Some(door), Some(auto_close), Some(position)) = (
+            context.doors.get(self.target),
+            context.auto_closes.get(self.target),
+            context.positions.get(self.target),
+        ) {
+            let close = ScheduledEvent::CloseDoor(*position);
+            context.scheduler.cancel(&close);
+
+            if door.open {
+                context
+                    .scheduler
+                    .schedule_in_turns(&context.clock, auto_close.turns, close);
+            }
+        }
+
+        //If the interactable is powered, rebuild power state
+        if let Some(power_node) = context.power_nodes.get_mut(self.target) {
+            power_node.dirty = true;
+        }
+
+        //If the interactable is a breaker box, rebuild power state of all connected wires
+        if context.breakers.get(self.target).is_some() {
+            if let Some(breaker_position) = context.positions.get(self.target).cloned() {
+                for (power_node, _) in (&mut context.power_nodes, &context.positions)
+                    .join()
+                    .filter(|(_, position)| **position == breaker_position)
+                {
+                    power_node.dirty = true;
+                }
+            }
+        }
     }
-    fn execute(&mut self) {}
 }
 
 #[allow(dead_code)]
@@ -117,23 +308,97 @@ pub trait Interactable {
     fn get_cost(&self) -> f32;
 }
 
+//Asks the event system to stop whatever multi-turn action the entity is in the middle of
+#[derive(Component, Clone)]
+pub struct Interrupt {
+    pub reason: String,
+}
+
+impl Interrupt {
+    pub fn new(reason: String) -> Self {
+        Self { reason }
+    }
+}
+
+//How much of an intent's cost is left to pay, shared by every intent
+#[derive(Clone, Copy)]
+pub struct IntentProgress {
+    pub cost: f32,
+    pub remaining_cost: f32,
+    //Interrupted and waiting to be continued
+    pub paused: bool,
+}
+
+impl IntentProgress {
+    pub fn new(cost: f32) -> Self {
+        Self {
+            cost,
+            remaining_cost: cost,
+            paused: false,
+        }
+    }
+}
+
+//An action that takes some time to carry out, the event system pays its cost a turn at a time
+//and executes it once fully paid
 #[allow(dead_code)]
 pub trait Intent {
-    fn get_cost(&self) -> f32;
-    fn get_remaining_cost(&self) -> f32;
-    fn update_remaining_cost(&mut self, delta: f32);
-    //fn interaction_id(&self) -> usize;
-    //fn interaction_description(&self) -> String;
-    //fn state_description(&self) -> String;
-    fn execute(&mut self);
+    fn intent_progress(&self) -> &IntentProgress;
+    fn intent_progress_mut(&mut self) -> &mut IntentProgress;
+    fn get_cost(&self) -> f32 {
+        self.intent_progress().cost
+    }
+    fn get_remaining_cost(&self) -> f32 {
+        self.intent_progress().remaining_cost
+    }
+    fn update_remaining_cost(&mut self, delta: f32) {
+        self.intent_progress_mut().remaining_cost += delta;
+    }
+    fn is_paused(&self) -> bool {
+        self.intent_progress().paused
+    }
+    fn set_paused(&mut self, paused: bool) {
+        self.intent_progress_mut().paused = paused;
+    }
+    //Short name of the action for the interface
+    fn describe(&self) -> String;
+    //Share of the cost already paid, from 0.0 to 1.0
+    fn get_progress(&self) -> f32 {
+        if self.get_cost() <= 0.0 {
+            return 1.0;
+        }
+        (1.0 - self.get_remaining_cost() / self.get_cost()).clamp(0.0, 1.0)
+    }
+    //Checked every turn before the cost is paid, an invalid intent is dropped without executing
+    fn is_valid(&self, _entity: Entity, _context: &mut IntentContext) -> bool {
+        true
+    }
+    fn execute(&mut self, entity: Entity, context: &mut IntentContext);
+    //What the interaction menus offer to do to the target through this intent
+    fn interactions(_ecs: &World, _target: Entity) -> Vec<InteractionInformation>
+    where
+        Self: Sized,
+    {
+        Vec::new()
+    }
+    //The intent for an interaction picked from a menu, none if it is never offered there
+    fn from_interaction(
+        _initiator: Entity,
+        _target: Entity,
+        _interaction: &InteractionInformation,
+    ) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        None
+    }
 }
 
 #[derive(Component, Clone)]
 pub struct PickUpIntent {
     pub initiator: Entity,
     pub target: Entity,
-    pub cost: f32,
-    pub remaining_cost: f32,
+    pub progress: IntentProgress,
 }
 
 impl PickUpIntent {
@@ -141,23 +406,87 @@ impl PickUpIntent {
         PickUpIntent {
             initiator,
             target,
-            cost,
-            remaining_cost: cost,
+            progress: IntentProgress::new(cost),
         }
     }
 }
 
 impl Intent for PickUpIntent {
-    fn get_cost(&self) -> f32 {
-        self.cost
+    fn intent_progress(&self) -> &IntentProgress {
+        &self.progress
     }
-    fn get_remaining_cost(&self) -> f32 {
-        self.remaining_cost
+    fn intent_progress_mut(&mut self) -> &mut IntentProgress {
+        &mut self.progress
     }
-    fn update_remaining_cost(&mut self, delta: f32) {
-        self.remaining_cost += delta;
+    fn describe(&self) -> String {
+        "Picking up".to_string()
+    }
+    fn interactions(ecs: &World, target: Entity) -> Vec<InteractionInformation> {
+        //If the item is not installed, allow pickup
+        if ecs.read_storage::<Installed>().get(target).is_some() {
+            return Vec::new();
+        }
+
+        vec![InteractionInformation::new(
+            target.id(),
+            "Pick up".to_string(),
+            target.id(),
+            1.0,
+            queue_intent::<Self>,
+        )]
+    }
+    fn from_interaction(
+        initiator: Entity,
+        target: Entity,
+        interaction: &InteractionInformation,
+    ) -> Option<Self> {
+        Some(PickUpIntent::new(initiator, target, interaction.cost))
+    }
+    fn is_valid(&self, _entity: Entity, context: &mut IntentContext) -> bool {
+        //Someone else got to it first
+        let item = match context.items.get(self.target) {
+            Some(item) if context.in_container.get(self.target).is_none() => item,
+            _ => return false,
+        };
+
+        //TODO: Check item weight etc
+        match context.containers.get(self.initiator) {
+            Some(container) => {
+                if container.remaining_volume - item.volume < 0.0 {
+                    context
+                        .game_log
+                        .entries
+                        .push("Item is too large".to_string());
+                    return false;
+                }
+            }
+            None => {
+                //TODO: Change this when clothes etc have storage
+                context
+                    .game_log
+                    .entries
+                    .push("No place to store item".to_string());
+                return false;
+            }
+        }
+        true
+    }
+    fn execute(&mut self, _entity: Entity, context: &mut IntentContext) {
+        let volume = match context.items.get(self.target) {
+            Some(item) => item.volume,
+            None => return,
+        };
+
+        if let Some(container) = context.containers.get_mut(self.initiator) {
+            let _ = context
+                .in_container
+                .insert(self.target, InContainer::new(container.id));
+
+            context.positions.remove(self.target);
+
+            container.try_insert_item(volume);
+        }
     }
-    fn execute(&mut self) {}
 }
 
 #[derive(Component, Clone)]
@@ -166,8 +495,7 @@ pub struct OpenIntent {
     pub target: Entity,
     pub interaction_id: u32,
     pub interaction_description: String,
-    pub cost: f32,
-    pub remaining_cost: f32,
+    pub progress: IntentProgress,
 }
 
 impl OpenIntent {
@@ -183,31 +511,62 @@ impl OpenIntent {
             target,
             interaction_id,
             interaction_description,
-            cost,
-            remaining_cost: cost,
+            progress: IntentProgress::new(cost),
         }
     }
 }
 
 impl Intent for OpenIntent {
-    fn get_cost(&self) -> f32 {
-        self.cost
+    fn intent_progress(&self) -> &IntentProgress {
+        &self.progress
     }
-    fn get_remaining_cost(&self) -> f32 {
-        self.remaining_cost
+    fn intent_progress_mut(&mut self) -> &mut IntentProgress {
+        &mut self.progress
     }
-    fn update_remaining_cost(&mut self, delta: f32) {
-        self.remaining_cost += delta;
+    fn describe(&self) -> String {
+        "Opening".to_string()
+    }
+    fn interactions(ecs: &World, target: Entity) -> Vec<InteractionInformation> {
+        match ecs.read_storage::<Container>().get(target) {
+            Some(container) => vec![InteractionInformation::new(
+                container.id,
+                "Open".to_string(),
+                target.id(),
+                1.0,
+                queue_intent::<Self>,
+            )],
+            None => Vec::new(),
+        }
+    }
+    fn from_interaction(
+        initiator: Entity,
+        target: Entity,
+        interaction: &InteractionInformation,
+    ) -> Option<Self> {
+        Some(OpenIntent::new(
+            initiator,
+            target,
+            interaction.id,
+            interaction.description.clone(),
+            interaction.cost,
+        ))
+    }
+    fn execute(&mut self, entity: Entity, context: &mut IntentContext) {
+        //Only the player has an inventory screen to show
+        if entity == *context.player {
+            *context.run_state = RunState::ShowInventory {
+                id: self.interaction_id,
+                selected_item: None,
+            };
+        }
     }
-    fn execute(&mut self) {}
 }
 
 #[derive(Component, Clone)]
 pub struct DropIntent {
     pub initiator: Entity,
     pub target: Entity,
-    pub cost: f32,
-    pub remaining_cost: f32,
+    pub progress: IntentProgress,
 }
 
 impl DropIntent {
@@ -215,21 +574,670 @@ impl DropIntent {
         DropIntent {
             initiator,
             target,
-            cost,
-            remaining_cost: cost,
+            progress: IntentProgress::new(cost),
         }
     }
 }
 
 impl Intent for DropIntent {
-    fn get_cost(&self) -> f32 {
-        self.cost
+    fn intent_progress(&self) -> &IntentProgress {
+        &self.progress
     }
-    fn get_remaining_cost(&self) -> f32 {
-        self.remaining_cost
+    fn intent_progress_mut(&mut self) -> &mut IntentProgress {
+        &mut self.progress
     }
-    fn update_remaining_cost(&mut self, delta: f32) {
-        self.remaining_cost += delta;
+    fn describe(&self) -> String {
+        "Dropping".to_string()
     }
-    fn execute(&mut self) {}
+    fn interactions(ecs: &World, target: Entity) -> Vec<InteractionInformation> {
+        //If item is in a container, give an option to drop it
+        if ecs.read_storage::<InContainer>().get(target).is_none() {
+            return Vec::new();
+        }
+
+        vec![InteractionInformation::new(
+            target.id(),
+            "Drop".to_string(),
+            target.id(),
+            1.0,
+            queue_intent::<Self>,
+        )]
+    }
+    fn from_interaction(
+        initiator: Entity,
+        target: Entity,
+        interaction: &InteractionInformation,
+    ) -> Option<Self> {
+        Some(DropIntent::new(initiator, target, interaction.cost))
+    }
+    fn is_valid(&self, _entity: Entity, context: &mut IntentContext) -> bool {
+        context.items.get(self.target).is_some() && context.in_container.get(self.target).is_some()
+    }
+    fn execute(&mut self, _entity: Entity, context: &mut IntentContext) {
+        let volume = match context.items.get(self.target) {
+            Some(item) => item.volume,
+            None => return,
+        };
+
+        if let Some(container) = context.containers.get_mut(self.initiator) {
+            //Add position
+            if let Some(position) = context.positions.get(self.initiator).cloned() {
+                let _ = context.positions.insert(self.target, position);
+            }
+
+            context.in_container.remove(self.target);
+
+            container.remove_item(volume);
+        }
+    }
+}
+
+#[derive(Component, Clone)]
+pub struct BuildIntent {
+    pub initiator: Entity,
+    pub target: Vector3i,
+    pub construction: Construction,
+    pub progress: IntentProgress,
+}
+
+impl BuildIntent {
+    pub fn new(initiator: Entity, target: Vector3i, construction: Construction) -> BuildIntent {
+        BuildIntent {
+            initiator,
+            target,
+            construction,
+            progress: IntentProgress::new(construction.cost()),
+        }
+    }
+}
+
+impl Intent for BuildIntent {
+    fn intent_progress(&self) -> &IntentProgress {
+        &self.progress
+    }
+    fn intent_progress_mut(&mut self) -> &mut IntentProgress {
+        &mut self.progress
+    }
+    fn describe(&self) -> String {
+        format!("Building {}", self.construction.name().to_lowercase())
+    }
+    fn is_valid(&self, _entity: Entity, context: &mut IntentContext) -> bool {
+        if !within_reach(self.initiator, self.target, context) {
+            return false;
+        }
+
+        match context.map.tiles.get(&self.target) {
+            Some(tile) if tile.passable => {}
+            _ => {
+                context
+                    .game_log
+                    .entries
+                    .push("Something is already built there".to_string());
+                return false;
+            }
+        }
+
+        //Characters, loose items and other fittings would end up inside the wall
+        if (&context.entities, &context.positions)
+            .join()
+            .filter(|(_, position)| **position == self.target)
+            .any(|(entity, _)| {
+                context.installed.get(entity).is_none()
+                    || context.blockers.get(entity).is_some()
+                    || context.doors.get(entity).is_some()
+            })
+        {
+            context
+                .game_log
+                .entries
+                .push("Something is in the way".to_string());
+            return false;
+        }
+
+        if find_material(self.initiator, self.construction.material(), context).is_none() {
+            context.game_log.entries.push(format!(
+                "No {} to build a {} with",
+                self.construction.material().name(),
+                self.construction.name().to_lowercase()
+            ));
+            return false;
+        }
+        true
+    }
+    fn execute(&mut self, _entity: Entity, context: &mut IntentContext) {
+        let material_item = match find_material(self.initiator, self.construction.material(), context)
+        {
+            Some(item) => item,
+            None => return,
+        };
+
+        //The material is used up
+        let volume = context.items.get(material_item).map_or(0.0, |item| item.volume);
+        if let Some(container) = context.containers.get_mut(self.initiator) {
+            container.remove_item(volume);
+        }
+        context.in_container.remove(material_item);
+        let _ = context.entities.delete(material_item);
+
+        let target = self.target;
+
+        match self.construction.blueprint() {
+            Some(key) => {
+                let tile = match tile_blueprints::get_tile(key) {
+                    Ok(tile) => tile,
+                    Err(_) => return,
+                };
+
+                replace_tile(&mut context.map, target, tile);
+                refresh_around(
+                    target,
+                    &mut context.map,
+                    &mut context.zones,
+                    &context.positions,
+                    &mut context.viewsheds,
+                    &mut context.crew_ais,
+                );
+            }
+            //Doors are entities, they only exist once the world is updated
+            None => context.lazy_update.exec_mut(move |ecs| {
+                spawner::door(
+                    ecs,
+                    target,
+                    false,
+                    RGB::named(rltk::GRAY).to_rgba(1.0),
+                    char_to_glyph('/'),
+                    char_to_glyph('+'),
+                );
+                refresh_world_around(ecs, target);
+            }),
+        }
+
+        context
+            .game_log
+            .entries
+            .push(format!("Built a {}", self.construction.name().to_lowercase()));
+    }
+}
+
+#[derive(Component, Clone)]
+pub struct DeconstructIntent {
+    pub initiator: Entity,
+    pub target: Vector3i,
+    pub progress: IntentProgress,
+}
+
+impl DeconstructIntent {
+    pub fn new(initiator: Entity, target: Vector3i) -> DeconstructIntent {
+        DeconstructIntent {
+            initiator,
+            target,
+            progress: IntentProgress::new(DECONSTRUCT_COST),
+        }
+    }
+}
+
+impl Intent for DeconstructIntent {
+    fn intent_progress(&self) -> &IntentProgress {
+        &self.progress
+    }
+    fn intent_progress_mut(&mut self) -> &mut IntentProgress {
+        &mut self.progress
+    }
+    fn describe(&self) -> String {
+        "Deconstructing".to_string()
+    }
+    fn is_valid(&self, _entity: Entity, context: &mut IntentContext) -> bool {
+        if !within_reach(self.initiator, self.target, context) {
+            return false;
+        }
+
+        let has_structure = context
+            .map
+            .tiles
+            .get(&self.target)
+            .is_some_and(|tile| tile.structure.is_destructible());
+
+        if !has_structure && door_at(self.target, context).is_none() {
+            context
+                .game_log
+                .entries
+                .push("Nothing to take apart there".to_string());
+            return false;
+        }
+        true
+    }
+    fn execute(&mut self, _entity: Entity, context: &mut IntentContext) {
+        let target = self.target;
+        let drop_position = match context.positions.get(self.initiator) {
+            Some(position) => *position,
+            None => return,
+        };
+
+        //Doors come off first, the tile under them is left alone
+        let (name, material) = match door_at(target, context) {
+            Some(door) => {
+                context.lazy_update.exec_mut(move |ecs| {
+                    let _ = ecs.delete_entity(door);
+                    refresh_world_around(ecs, target);
+                });
+                ("door".to_string(), Material::Steel)
+            }
+            None => {
+                let old_tile = match context.map.tiles.get(&target) {
+                    Some(tile) => tile.clone(),
+                    None => return,
+                };
+                let tile = match tile_blueprints::get_tile("vacuume") {
+                    Ok(tile) => tile,
+                    Err(_) => return,
+                };
+
+                replace_tile(&mut context.map, target, tile);
+                refresh_around(
+                    target,
+                    &mut context.map,
+                    &mut context.zones,
+                    &context.positions,
+                    &mut context.viewsheds,
+                    &mut context.crew_ais,
+                );
+                (old_tile.name.to_lowercase(), old_tile.structure.material)
+            }
+        };
+
+        //Salvage is left at the feet of whoever took it apart
+        if let Some(key) = salvage_item(material) {
+            context.lazy_update.exec_mut(move |ecs| {
+                let _ = spawn_named(ecs, key, drop_position);
+            });
+        }
+
+        context
+            .game_log
+            .entries
+            .push(format!("Took apart the {}", name));
+    }
+}
+
+//Turns it takes to load a fuel item into a generator
+pub const REFUEL_COST: f32 = 2.0;
+
+#[derive(Component, Clone)]
+pub struct RefuelIntent {
+    pub initiator: Entity,
+    pub target: Entity,
+    pub progress: IntentProgress,
+}
+
+impl RefuelIntent {
+    pub fn new(initiator: Entity, target: Entity) -> RefuelIntent {
+        RefuelIntent {
+            initiator,
+            target,
+            progress: IntentProgress::new(REFUEL_COST),
+        }
+    }
+}
+
+impl Intent for RefuelIntent {
+    fn intent_progress(&self) -> &IntentProgress {
+        &self.progress
+    }
+    fn intent_progress_mut(&mut self) -> &mut IntentProgress {
+        &mut self.progress
+    }
+    fn describe(&self) -> String {
+        "Refuelling".to_string()
+    }
+    fn interactions(ecs: &World, target: Entity) -> Vec<InteractionInformation> {
+        //Load fuel into a generator's hopper
+        match ecs.read_storage::<Generator>().get(target) {
+            Some(generator)
+                if generator.fuel == GeneratorFuel::Item
+                    && ecs.read_storage::<Container>().get(target).is_some() =>
+            {
+                vec![InteractionInformation::new(
+                    generator.interaction_id,
+                    "Load fuel".to_string(),
+                    target.id(),
+                    REFUEL_COST,
+                    queue_intent::<Self>,
+                )]
+            }
+            _ => Vec::new(),
+        }
+    }
+    fn from_interaction(
+        initiator: Entity,
+        target: Entity,
+        _interaction: &InteractionInformation,
+    ) -> Option<Self> {
+        Some(RefuelIntent::new(initiator, target))
+    }
+    fn is_valid(&self, _entity: Entity, context: &mut IntentContext) -> bool {
+        match context.positions.get(self.target).copied() {
+            Some(position) if within_reach(self.initiator, position, context) => {}
+            _ => return false,
+        }
+
+        if !matches!(context.generators.get(self.target), Some(generator) if generator.fuel == GeneratorFuel::Item)
+        {
+            return false;
+        }
+
+        //Nowhere to put the fuel
+        if context.containers.get(self.target).is_none() {
+            return false;
+        }
+
+        let fuel = match find_fuel(self.initiator, context) {
+            Some(fuel) => fuel,
+            None => {
+                context
+                    .game_log
+                    .entries
+                    .push("No fuel to load".to_string());
+                return false;
+            }
+        };
+        let volume = context.items.get(fuel).map_or(0.0, |item| item.volume);
+
+        match context.containers.get(self.target) {
+            Some(hopper) if hopper.remaining_volume < volume => {
+                context
+                    .game_log
+                    .entries
+                    .push("The hopper is full".to_string());
+                false
+            }
+            _ => true,
+        }
+    }
+    fn execute(&mut self, _entity: Entity, context: &mut IntentContext) {
+        let fuel = match find_fuel(self.initiator, context) {
+            Some(fuel) => fuel,
+            None => return,
+        };
+        let volume = context.items.get(fuel).map_or(0.0, |item| item.volume);
+
+        let hopper_id = match context.containers.get_mut(self.target) {
+            Some(hopper) => {
+                hopper.try_insert_item(volume);
+                hopper.id
+            }
+            None => return,
+        };
+
+        if let Some(container) = context.containers.get_mut(self.initiator) {
+            container.remove_item(volume);
+        }
+        let _ = context
+            .in_container
+            .insert(fuel, InContainer::new(hopper_id));
+
+        let fuel_name = context
+            .names
+            .get(fuel)
+            .map_or("fuel".to_string(), |name| name.name.to_lowercase());
+        let target_name = context
+            .names
+            .get(self.target)
+            .map_or("generator".to_string(), |name| name.name.to_lowercase());
+        context
+            .game_log
+            .entries
+            .push(format!("Loaded a {} into the {}", fuel_name, target_name));
+    }
+}
+
+//Turns it takes to get a failed device working again
+pub const REPAIR_COST: f32 = 5.0;
+
+#[derive(Component, Clone)]
+pub struct RepairIntent {
+    pub initiator: Entity,
+    pub target: Entity,
+    pub progress: IntentProgress,
+}
+
+impl RepairIntent {
+    pub fn new(initiator: Entity, target: Entity) -> RepairIntent {
+        RepairIntent {
+            initiator,
+            target,
+            progress: IntentProgress::new(REPAIR_COST),
+        }
+    }
+}
+
+impl Intent for RepairIntent {
+    fn intent_progress(&self) -> &IntentProgress {
+        &self.progress
+    }
+    fn intent_progress_mut(&mut self) -> &mut IntentProgress {
+        &mut self.progress
+    }
+    fn describe(&self) -> String {
+        "Repairing".to_string()
+    }
+    fn interactions(ecs: &World, target: Entity) -> Vec<InteractionInformation> {
+        //Get a failed device working again
+        if ecs.read_storage::<Broken>().get(target).is_none() {
+            return Vec::new();
+        }
+
+        vec![InteractionInformation::new(
+            target.id(),
+            "Repair".to_string(),
+            target.id(),
+            REPAIR_COST,
+            queue_intent::<Self>,
+        )]
+    }
+    fn from_interaction(
+        initiator: Entity,
+        target: Entity,
+        _interaction: &InteractionInformation,
+    ) -> Option<Self> {
+        Some(RepairIntent::new(initiator, target))
+    }
+    fn is_valid(&self, _entity: Entity, context: &mut IntentContext) -> bool {
+        match context.positions.get(self.target).copied() {
+            Some(position) if within_reach(self.initiator, position, context) => {}
+            _ => return false,
+        }
+
+        context.broken.get(self.target).is_some()
+    }
+    fn execute(&mut self, _entity: Entity, context: &mut IntentContext) {
+        context.broken.remove(self.target);
+
+        //Let the power system pick the device back up
+        if let Some(power_node) = context.power_nodes.get_mut(self.target) {
+            power_node.dirty = true;
+        }
+
+        let target_name = context
+            .names
+            .get(self.target)
+            .map_or("device".to_string(), |name| name.name.to_lowercase());
+        context
+            .game_log
+            .entries
+            .push(format!("Repaired the {}", target_name));
+    }
+}
+
+//Every kind of intent with the name of its storage. Registering, handling, interrupting,
+//continuing and clearing intents and listing what the menus offer all go through the storages
+//made here, so a new intent is only listed once
+macro_rules! intent_storages {
+    ( $( $storage:ident: $typ:ty ),* ) => {
+        #[derive(SystemData)]
+        pub struct IntentStorages<'a> {
+            $( pub $storage: WriteStorage<'a, $typ>, )*
+        }
+
+        impl<'a> IntentStorages<'a> {
+            //Pays a turn towards each of the entity's intents, returns whether it had any
+            pub fn handle(&mut self, entity: Entity, context: &mut IntentContext) -> bool {
+                let mut has_intent = false;
+                $( has_intent |= handle_intent(entity, &mut self.$storage, context); )*
+                has_intent
+            }
+
+            //Stops the entity's multi-turn intents, returns the descriptions of what was stopped
+            pub fn interrupt(&mut self, entity: Entity, pause: bool) -> Vec<String> {
+                let mut interrupted = Vec::new();
+                $( interrupted.extend(interrupt_intent(entity, &mut self.$storage, pause)); )*
+                interrupted
+            }
+
+            //The entity's multi-turn action if it has one, as its description, progress and whether it is paused
+            pub fn action_progress(&self, entity: Entity) -> Option<(String, f32, bool)> {
+                $(
+                    if let Some(intent) = self.$storage.get(entity) {
+                        if intent.get_cost() > SECONDS_PER_TURN {
+                            return Some((intent.describe(), intent.get_progress(), intent.is_paused()));
+                        }
+                    }
+                )*
+                None
+            }
+
+            //Unpauses everything the entity had been interrupted in, returns whether there was anything
+            pub fn continue_paused(&mut self, entity: Entity) -> bool {
+                let mut continued = false;
+                $(
+                    if let Some(intent) = self.$storage.get_mut(entity) {
+                        if intent.is_paused() {
+                            intent.set_paused(false);
+                            continued = true;
+                        }
+                    }
+                )*
+                continued
+            }
+
+            //Drops whatever the entity was doing
+            pub fn remove(&mut self, entity: Entity) {
+                $( self.$storage.remove(entity); )*
+            }
+
+            //Everything the interaction menus offer to do to the target through an intent
+            pub fn interactions(ecs: &World, target: Entity) -> Vec<InteractionInformation> {
+                let mut interactions = Vec::new();
+                $( interactions.extend(<$typ>::interactions(ecs, target)); )*
+                interactions
+            }
+        }
+    };
+}
+
+intent_storages!(
+    interact_intents: InteractIntent,
+    move_intents: MoveIntent,
+    pick_up_intents: PickUpIntent,
+    drop_intents: DropIntent,
+    open_intents: OpenIntent,
+    build_intents: BuildIntent,
+    deconstruct_intents: DeconstructIntent,
+    refuel_intents: RefuelIntent,
+    repair_intents: RepairIntent
+);
+
+//Pays a turn towards the entity's intent of this type and executes it once fully paid,
+//returns whether the entity had one
+fn handle_intent<I: Intent + Component>(
+    entity: Entity,
+    intents: &mut WriteStorage<I>,
+    context: &mut IntentContext,
+) -> bool {
+    let finished = match intents.get_mut(entity) {
+        Some(intent) if intent.is_paused() => return false,
+        Some(intent) => {
+            if !intent.is_valid(entity, context) {
+                true
+            } else {
+                intent.update_remaining_cost(-SECONDS_PER_TURN);
+
+                if intent.get_remaining_cost() <= 0.0 {
+                    intent.execute(entity, context);
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+        None => return false,
+    };
+
+    if finished {
+        intents.remove(entity);
+    }
+    true
+}
+
+//Stops the entity's intent of this type if it takes more than a turn, the player's is paused
+//so it can be continued, anyone else's is dropped. Returns the description of what was stopped
+fn interrupt_intent<I: Intent + Component>(
+    entity: Entity,
+    intents: &mut WriteStorage<I>,
+    pause: bool,
+) -> Option<String> {
+    let description = match intents.get_mut(entity) {
+        Some(intent) if !intent.is_paused() && intent.get_cost() > SECONDS_PER_TURN => {
+            if pause {
+                intent.set_paused(true);
+            }
+            intent.describe()
+        }
+        _ => return None,
+    };
+
+    if !pause {
+        intents.remove(entity);
+    }
+    Some(description)
+}
+
+//Building and taking apart is done from a neighbouring tile
+fn within_reach(initiator: Entity, target: Vector3i, context: &IntentContext) -> bool {
+    match context.positions.get(initiator) {
+        Some(position) => *position != target && utils::within_reach(*position, target),
+        None => false,
+    }
+}
+
+//First item of the material carried by the initiator
+fn find_material(initiator: Entity, material: Material, context: &IntentContext) -> Option<Entity> {
+    let container_id = context.containers.get(initiator)?.id;
+
+    (
+        &context.entities,
+        &context.construction_materials,
+        &context.in_container,
+    )
+        .join()
+        .find(|(_, construction_material, in_container)| {
+            construction_material.material == material && in_container.owner == container_id
+        })
+        .map(|(entity, ..)| entity)
+}
+
+//First fuel item carried by the initiator
+fn find_fuel(initiator: Entity, context: &IntentContext) -> Option<Entity> {
+    let container_id = context.containers.get(initiator)?.id;
+
+    (&context.entities, &context.fuels, &context.in_container)
+        .join()
+        .find(|(_, _, in_container)| in_container.owner == container_id)
+        .map(|(entity, ..)| entity)
+}
+
+fn door_at(position: Vector3i, context: &IntentContext) -> Option<Entity> {
+    (&context.entities, &context.doors, &context.positions)
+        .join()
+        .find(|(_, _, door_position)| **door_position == position)
+        .map(|(entity, ..)| entity)
 }
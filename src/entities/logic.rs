@@ -0,0 +1,316 @@
+use serde::Deserialize;
+use serde::Serialize;
+use specs::prelude::*;
+use specs_derive::*;
+
+use super::{intents::Interactable, network::NetworkAddress};
+
+//Thresholds a sensor steps through: kPa for pressure, kelvin for temperature and tiles of range
+//for motion
+const PRESSURE_THRESHOLDS: [f32; 4] = [25.0, 50.0, 75.0, 90.0];
+const TEMPERATURE_THRESHOLDS: [f32; 5] = [273.15, 283.15, 293.15, 303.15, 313.15];
+const MOTION_RANGES: [f32; 4] = [1.0, 2.0, 3.0, 5.0];
+//Ticks a delay timer steps through
+pub const TIMER_DELAYS: [u32; 4] = [1, 3, 5, 10];
+
+//The output of a sensor, gate or timer, drives one addressed device on the data network
+#[derive(Component, Default, Serialize, Deserialize, Clone)]
+pub struct Signal {
+    pub on: bool,
+    pub target: Option<u32>,
+    //Addresses the output can reach, refreshed by the logic system
+    #[serde(skip)]
+    pub reachable: Vec<u32>,
+    pub interaction_description: String,
+    pub interaction_id: u32,
+    pub cost: f32,
+}
+
+impl Signal {
+    pub fn new(target: Option<u32>) -> Self {
+        Self {
+            on: false,
+            target,
+            reachable: Vec::new(),
+            interaction_description: "Disconnect output".to_string(),
+            interaction_id: crate::rng::random_int() as u32,
+            cost: 1.0,
+        }
+    }
+
+    //The target is cut off when the wiring no longer reaches it
+    pub fn linked_target(&self) -> Option<u32> {
+        self.target.filter(|target| self.reachable.contains(target))
+    }
+
+    //Steps through everything reachable and then back to nothing
+    pub fn next_target(&self) -> Option<u32> {
+        let index = self
+            .target
+            .and_then(|target| self.reachable.iter().position(|address| *address == target));
+
+        match index {
+            Some(index) => self.reachable.get(index + 1).copied(),
+            None => self.reachable.first().copied(),
+        }
+    }
+
+    pub fn set_reachable(&mut self, reachable: Vec<u32>) {
+        self.reachable = reachable;
+
+        self.interaction_description = match self.next_target() {
+            Some(target) => format!("Drive {}", NetworkAddress::new(target).label()),
+            None => "Disconnect output".to_string(),
+        };
+    }
+
+    pub fn state_description(&self) -> String {
+        let state = if self.on { "on" } else { "off" };
+
+        match self.linked_target() {
+            Some(target) => format!("{}, driving {}", state, NetworkAddress::new(target).label()),
+            None => format!("{}, not linked", state),
+        }
+    }
+}
+
+impl Interactable for Signal {
+    fn get_cost(&self) -> f32 {
+        self.cost
+    }
+    fn interact(&mut self) {
+        self.target = self.next_target();
+        self.set_reachable(self.reachable.clone());
+    }
+
+    fn interaction_id(&self) -> u32 {
+        self.interaction_id
+    }
+
+    fn interaction_description(&self) -> String {
+        self.interaction_description.clone()
+    }
+
+    fn state_description(&self) -> String {
+        self.state_description()
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SensorKind {
+    //Pressure of its tile in kPa
+    #[default]
+    Pressure,
+    //Temperature of its tile in kelvin
+    Temperature,
+    //Anything alive within range, the threshold is the range in tiles
+    Motion,
+}
+
+//Turns its signal on while the reading is past the threshold
+#[derive(Component, Default, Serialize, Deserialize, Clone)]
+pub struct Sensor {
+    pub kind: SensorKind,
+    pub threshold: f32,
+    //Triggers below the threshold rather than above it
+    pub below: bool,
+    pub reading: f32,
+    pub interaction_description: String,
+    pub interaction_id: u32,
+    pub cost: f32,
+}
+
+impl Sensor {
+    pub fn new(kind: SensorKind, threshold: f32, below: bool) -> Self {
+        let mut sensor = Self {
+            kind,
+            threshold,
+            below,
+            reading: 0.0,
+            interaction_description: String::new(),
+            interaction_id: crate::rng::random_int() as u32,
+            cost: 1.0,
+        };
+        sensor.set_threshold(threshold);
+        sensor
+    }
+
+    pub fn triggered(&self) -> bool {
+        match self.kind {
+            SensorKind::Motion => self.reading > 0.0,
+            _ if self.below => self.reading < self.threshold,
+            _ => self.reading > self.threshold,
+        }
+    }
+
+    fn thresholds(&self) -> &'static [f32] {
+        match self.kind {
+            SensorKind::Pressure => &PRESSURE_THRESHOLDS,
+            SensorKind::Temperature => &TEMPERATURE_THRESHOLDS,
+            SensorKind::Motion => &MOTION_RANGES,
+        }
+    }
+
+    pub fn next_threshold(&self) -> f32 {
+        let thresholds = self.thresholds();
+
+        thresholds
+            .iter()
+            .find(|threshold| **threshold > self.threshold)
+            .copied()
+            .unwrap_or(thresholds[0])
+    }
+
+    pub fn set_threshold(&mut self, threshold: f32) {
+        self.threshold = threshold;
+        self.interaction_description = match self.kind {
+            SensorKind::Motion => format!("Set range to {:.0} tiles", self.next_threshold()),
+            _ => format!("Set threshold to {}", self.format(self.next_threshold())),
+        };
+    }
+
+    fn format(&self, value: f32) -> String {
+        match self.kind {
+            SensorKind::Pressure => format!("{:.0} kPa", value),
+            SensorKind::Temperature => format!("{:.1} C", value - 273.15),
+            SensorKind::Motion => format!("{:.0}", value),
+        }
+    }
+
+    pub fn state_description(&self) -> String {
+        match self.kind {
+            SensorKind::Motion => {
+                format!("{:.0} in range, {:.0} tiles", self.reading, self.threshold)
+            }
+            _ => format!(
+                "{}, trips {} {}",
+                self.format(self.reading),
+                if self.below { "below" } else { "above" },
+                self.format(self.threshold)
+            ),
+        }
+    }
+}
+
+impl Interactable for Sensor {
+    fn get_cost(&self) -> f32 {
+        self.cost
+    }
+    fn interact(&mut self) {
+        self.set_threshold(self.next_threshold());
+    }
+
+    fn interaction_id(&self) -> u32 {
+        self.interaction_id
+    }
+
+    fn interaction_description(&self) -> String {
+        self.interaction_description.clone()
+    }
+
+    fn state_description(&self) -> String {
+        self.state_description()
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GateKind {
+    //Passes its input on
+    #[default]
+    Relay,
+    And,
+    Or,
+    Not,
+}
+
+//Works out its signal from the signals driving it
+#[derive(Component, Default, Serialize, Deserialize, Clone)]
+pub struct LogicGate {
+    pub kind: GateKind,
+}
+
+impl LogicGate {
+    pub fn new(kind: GateKind) -> Self {
+        Self { kind }
+    }
+
+    pub fn evaluate(&self, inputs: &[bool]) -> bool {
+        match self.kind {
+            GateKind::Relay | GateKind::Or => inputs.iter().any(|input| *input),
+            GateKind::And => !inputs.is_empty() && inputs.iter().all(|input| *input),
+            GateKind::Not => !inputs.iter().any(|input| *input),
+        }
+    }
+}
+
+//Follows its input once the input has held steady for the delay, the switch waits in the
+//event scheduler
+#[derive(Component, Default, Serialize, Deserialize, Clone)]
+pub struct DelayTimer {
+    pub delay: u32,
+    //A switch of the output is scheduled
+    pub waiting: bool,
+    pub interaction_description: String,
+    pub interaction_id: u32,
+    pub cost: f32,
+}
+
+impl DelayTimer {
+    pub fn new(delay: u32) -> Self {
+        let mut timer = Self {
+            delay,
+            waiting: false,
+            interaction_description: String::new(),
+            interaction_id: crate::rng::random_int() as u32,
+            cost: 1.0,
+        };
+        timer.set_delay(delay);
+        timer
+    }
+
+    pub fn next_delay(&self) -> u32 {
+        TIMER_DELAYS
+            .iter()
+            .find(|delay| **delay > self.delay)
+            .copied()
+            .unwrap_or(TIMER_DELAYS[0])
+    }
+
+    //Starts counting again with the new delay
+    pub fn set_delay(&mut self, delay: u32) {
+        self.delay = delay;
+        self.waiting = false;
+        self.interaction_description = format!("Set delay to {} ticks", self.next_delay());
+    }
+
+    pub fn state_description(&self) -> String {
+        if self.waiting {
+            format!("{} ticks, counting", self.delay)
+        } else {
+            format!("{} ticks", self.delay)
+        }
+    }
+}
+
+impl Interactable for DelayTimer {
+    fn get_cost(&self) -> f32 {
+        self.cost
+    }
+    fn interact(&mut self) {
+        self.set_delay(self.next_delay());
+    }
+
+    fn interaction_id(&self) -> u32 {
+        self.interaction_id
+    }
+
+    fn interaction_description(&self) -> String {
+        self.interaction_description.clone()
+    }
+
+    fn state_description(&self) -> String {
+        self.state_description()
+    }
+}
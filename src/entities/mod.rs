@@ -1,6 +1,12 @@
 pub mod atmospherics;
 pub mod biology;
 pub mod components;
+pub mod construction;
+pub mod crew;
+pub mod fire;
 pub mod intents;
+pub mod logic;
+pub mod network;
 pub mod power_components;
+pub mod power_graph;
 pub mod props;
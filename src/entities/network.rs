@@ -0,0 +1,107 @@
+use serde::Deserialize;
+use serde::Serialize;
+use specs::prelude::*;
+use specs_derive::*;
+
+use crate::vectors::Vector3i;
+
+use super::intents::Interactable;
+
+//Alarms a terminal holds on to before the oldest ones scroll off
+pub const TERMINAL_ALARMS: usize = 8;
+
+//Where a device can be reached on the data network, handed out by the network system
+#[derive(Component, Default, Serialize, Deserialize, Clone)]
+pub struct NetworkAddress {
+    pub address: u32,
+}
+
+impl NetworkAddress {
+    pub fn new(address: u32) -> Self {
+        Self { address }
+    }
+
+    pub fn label(&self) -> String {
+        format!("#{:03}", self.address)
+    }
+}
+
+//Lists every device it can reach over data wiring and collects their alarms, needs power to do either
+#[derive(Component, Default, Serialize, Deserialize, Clone)]
+pub struct Terminal {
+    pub alarms: Vec<String>,
+    pub interaction_description: String,
+    pub interaction_id: u32,
+    pub cost: f32,
+}
+
+impl Terminal {
+    pub fn new() -> Self {
+        Self {
+            alarms: Vec::new(),
+            interaction_description: "Acknowledge alarms".to_string(),
+            interaction_id: crate::rng::random_int() as u32,
+            cost: 1.0,
+        }
+    }
+
+    pub fn receive(&mut self, alarm: String) {
+        self.alarms.push(alarm);
+
+        if self.alarms.len() > TERMINAL_ALARMS {
+            self.alarms.remove(0);
+        }
+    }
+
+    pub fn state_description(&self) -> String {
+        match self.alarms.len() {
+            0 => "no alarms".to_string(),
+            1 => "1 alarm".to_string(),
+            count => format!("{} alarms", count),
+        }
+    }
+}
+
+impl Interactable for Terminal {
+    fn get_cost(&self) -> f32 {
+        self.cost
+    }
+    fn interact(&mut self) {
+        self.alarms.clear();
+    }
+
+    fn interaction_id(&self) -> u32 {
+        self.interaction_id
+    }
+
+    fn interaction_description(&self) -> String {
+        self.interaction_description.clone()
+    }
+
+    fn state_description(&self) -> String {
+        self.state_description()
+    }
+}
+
+pub struct Alarm {
+    pub position: Vector3i,
+    pub message: String,
+}
+
+//Alarms raised this tick, delivered to the terminals that can hear them by the network system
+#[derive(Default)]
+pub struct AlarmQueue {
+    pub pending: Vec<Alarm>,
+}
+
+impl AlarmQueue {
+    pub fn new() -> Self {
+        Self {
+            pending: Vec::new(),
+        }
+    }
+
+    pub fn raise(&mut self, position: Vector3i, message: String) {
+        self.pending.push(Alarm { position, message });
+    }
+}
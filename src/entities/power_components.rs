@@ -67,8 +67,42 @@ impl PoweredState {
     }
 }
 
+//Breakers hold for this many ticks of overload before they trip
+pub const BREAKER_TRIP_TICKS: u32 = 3;
+
 #[derive(Component, Default, Serialize, Deserialize, Clone)]
-pub struct ControlPanel {}
+pub struct ControlPanel {
+    //Watts the breaker passes on before it trips
+    #[serde(default)]
+    pub rated_wattage: f32,
+    //Watts drawn through the breaker, set when the network is rebuilt
+    #[serde(default)]
+    pub load: f32,
+    #[serde(default)]
+    pub overload_ticks: u32,
+}
+
+impl ControlPanel {
+    pub fn new(rated_wattage: f32) -> ControlPanel {
+        ControlPanel {
+            rated_wattage,
+            load: 0.0,
+            overload_ticks: 0,
+        }
+    }
+
+    pub fn is_overloaded(&self) -> bool {
+        self.rated_wattage > 0.0 && self.load > self.rated_wattage
+    }
+
+    pub fn state_description(&self) -> String {
+        format!("{:.0} / {:.0} W", self.load, self.rated_wattage)
+    }
+}
+
+//Failed device that stays dead whatever power it gets until a RepairIntent fixes it
+#[derive(Component, Default, Serialize, Deserialize, Clone)]
+pub struct Broken {}
 
 #[derive(Component, Default, Serialize, Deserialize, Clone)]
 pub struct PowerSwitch {
@@ -135,6 +169,248 @@ impl Interactable for PowerSwitch {
     }
 }
 
+//Stores surplus from its network in joules and feeds it back when generation falls short,
+//it supplies the network through its power source while discharging
+#[derive(Component, Default, Serialize, Deserialize, Clone)]
+pub struct Battery {
+    pub charge: f32,
+    pub capacity: f32,
+    //Watts it can take in from the network
+    pub max_charge_rate: f32,
+    //Watts going in (positive) or out (negative) last tick
+    pub flow: f32,
+    pub connected: bool,
+    pub interaction_description: String,
+    pub interaction_id: u32,
+    pub cost: f32,
+}
+
+impl Battery {
+    pub fn new(capacity: f32, charge: f32, max_charge_rate: f32) -> Battery {
+        Battery {
+            charge: charge.clamp(0.0, capacity),
+            capacity,
+            max_charge_rate,
+            flow: 0.0,
+            connected: true,
+            interaction_description: "Disconnect".to_string(),
+            interaction_id: crate::rng::random_int() as u32,
+            cost: 1.0,
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.connected = !self.connected;
+
+        if self.connected {
+            self.interaction_description = "Disconnect".to_string()
+        } else {
+            self.interaction_description = "Connect".to_string()
+        }
+    }
+
+    pub fn charge_ratio(&self) -> f32 {
+        if self.capacity <= 0.0 {
+            return 0.0;
+        }
+
+        (self.charge / self.capacity).clamp(0.0, 1.0)
+    }
+
+    pub fn state_description(&self) -> String {
+        let state = if !self.connected {
+            "disconnected"
+        } else if self.flow > 0.0 {
+            "charging"
+        } else if self.flow < 0.0 {
+            "discharging"
+        } else {
+            "idle"
+        };
+
+        format!(
+            "{:.0}%, {:.0} kJ, {}",
+            self.charge_ratio() * 100.0,
+            self.charge / 1000.0,
+            state
+        )
+    }
+}
+
+impl Interactable for Battery {
+    fn get_cost(&self) -> f32 {
+        self.cost
+    }
+    fn interact(&mut self) {
+        self.toggle();
+    }
+
+    fn interaction_id(&self) -> u32 {
+        self.interaction_id
+    }
+
+    fn interaction_description(&self) -> String {
+        self.interaction_description.clone()
+    }
+
+    fn state_description(&self) -> String {
+        self.state_description()
+    }
+}
+
+//Throttle settings a generator steps through, idle first
+pub const GENERATOR_THROTTLES: [f32; 4] = [0.1, 0.5, 0.75, 1.0];
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GeneratorFuel {
+    //Fuel items loaded into its hopper
+    #[default]
+    Item,
+    //Hydrogen and oxygen drawn from its tile
+    Hydrogen,
+}
+
+//Burns fuel for the power source on the same entity, whatever does not become electricity
+//heats the room it stands in
+#[derive(Component, Default, Serialize, Deserialize, Clone)]
+pub struct Generator {
+    pub fuel: GeneratorFuel,
+    pub max_output: f32,
+    pub throttle: f32,
+    //Share of the fuel's energy that comes out as electricity
+    pub efficiency: f32,
+    //Joules left of the fuel item being burnt
+    pub burning: f32,
+    //Watts produced last tick
+    pub output: f32,
+    //Up to speed, a generator switched on takes a while to spin up before it delivers power
+    pub online: bool,
+    pub interaction_description: String,
+    pub interaction_id: u32,
+    pub cost: f32,
+}
+
+impl Generator {
+    pub fn new(fuel: GeneratorFuel, max_output: f32, efficiency: f32, throttle: f32) -> Generator {
+        let mut generator = Generator {
+            fuel,
+            max_output,
+            throttle,
+            efficiency,
+            burning: 0.0,
+            output: 0.0,
+            online: true,
+            interaction_description: String::new(),
+            interaction_id: crate::rng::random_int() as u32,
+            cost: 1.0,
+        };
+        generator.set_throttle(throttle);
+        generator
+    }
+
+    pub fn set_throttle(&mut self, throttle: f32) {
+        self.throttle = throttle.clamp(GENERATOR_THROTTLES[0], 1.0);
+        self.interaction_description = format!(
+            "Set throttle to {}",
+            throttle_name(self.next_throttle())
+        );
+    }
+
+    //The next setting up, back to idle from full
+    pub fn next_throttle(&self) -> f32 {
+        GENERATOR_THROTTLES
+            .iter()
+            .copied()
+            .find(|throttle| *throttle > self.throttle + 0.01)
+            .unwrap_or(GENERATOR_THROTTLES[0])
+    }
+
+    //Watts asked of the generator at its throttle
+    pub fn target_output(&self) -> f32 {
+        self.max_output * self.throttle
+    }
+
+    pub fn state_description(&self) -> String {
+        format!(
+            "{}, {:.0} of {:.0} W",
+            throttle_name(self.throttle),
+            self.output,
+            self.target_output()
+        )
+    }
+}
+
+fn throttle_name(throttle: f32) -> String {
+    if throttle <= GENERATOR_THROTTLES[0] {
+        "idle".to_string()
+    } else {
+        format!("{:.0}%", throttle * 100.0)
+    }
+}
+
+impl Interactable for Generator {
+    fn get_cost(&self) -> f32 {
+        self.cost
+    }
+    fn interact(&mut self) {
+        self.set_throttle(self.next_throttle());
+    }
+
+    fn interaction_id(&self) -> u32 {
+        self.interaction_id
+    }
+
+    fn interaction_description(&self) -> String {
+        self.interaction_description.clone()
+    }
+
+    fn state_description(&self) -> String {
+        self.state_description()
+    }
+}
+
+//Something a generator can burn, in joules
+#[derive(Component, Default, Serialize, Deserialize, Clone)]
+pub struct Fuel {
+    pub energy: f32,
+}
+
+impl Fuel {
+    pub fn new(energy: f32) -> Self {
+        Self { energy }
+    }
+}
+
+//Overheat a wire takes before it burns out, one point is a tick at double its rating
+pub const WIRE_BURN_OUT: f32 = 10.0;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WireGauge {
+    Light,
+    #[default]
+    Standard,
+    Heavy,
+    //Data cable, carries no power at all
+    Signal,
+}
+
+impl WireGauge {
+    //Watts the wire carries without heating up
+    pub fn rated_wattage(&self) -> f32 {
+        match self {
+            WireGauge::Light => 500.0,
+            WireGauge::Standard => 2000.0,
+            WireGauge::Heavy => 10000.0,
+            WireGauge::Signal => 0.0,
+        }
+    }
+
+    pub fn carries_power(&self) -> bool {
+        *self != WireGauge::Signal
+    }
+}
+
 #[derive(Component, Default, Serialize, Deserialize, Clone)]
 pub struct Wire {
     pub power_load: f32,
@@ -142,6 +418,11 @@ pub struct Wire {
     pub color: RGBA,
     pub color_name: String,
     pub data: bool,
+    #[serde(default)]
+    pub gauge: WireGauge,
+    //Builds up while the wire carries more than its rating and cools off slowly
+    #[serde(default)]
+    pub overheat: f32,
 }
 
 impl Wire {
@@ -152,14 +433,48 @@ impl Wire {
             color,
             color_name,
             data,
+            gauge: WireGauge::Standard,
+            overheat: 0.0,
+        }
+    }
+
+    pub fn is_overloaded(&self) -> bool {
+        self.power_load > self.gauge.rated_wattage()
+    }
+
+    pub fn state_description(&self) -> String {
+        if !self.gauge.carries_power() {
+            return "Signal cable".to_string();
         }
+
+        let state = if self.overheat >= WIRE_BURN_OUT / 2.0 {
+            ", hot"
+        } else if self.overheat > 0.0 {
+            ", warm"
+        } else {
+            ""
+        };
+
+        format!(
+            "{:?} gauge, {:.0} W rated{}",
+            self.gauge,
+            self.gauge.rated_wattage(),
+            state
+        )
     }
 }
 
+//Kelvin either side of the target before the thermostat switches, stops it flicking every tick
+pub const HEATER_HYSTERESIS: f32 = 0.5;
+
 #[derive(Component, Default, Serialize, Deserialize, Clone, Debug)]
 pub struct ElectronicHeater {
     pub target_temperature: f32,
+    //Running, set by the power network
     pub on: bool,
+    //The thermostat is calling for heat
+    #[serde(default)]
+    pub heating: bool,
 }
 
 #[allow(dead_code)]
@@ -168,13 +483,18 @@ impl ElectronicHeater {
         Self {
             target_temperature,
             on,
+            heating: false,
         }
     }
 
     pub fn check_status(&mut self, current_temperature: f32) -> bool {
-        self.on = current_temperature < self.target_temperature;
+        if current_temperature < self.target_temperature - HEATER_HYSTERESIS {
+            self.heating = true;
+        } else if current_temperature > self.target_temperature + HEATER_HYSTERESIS {
+            self.heating = false;
+        }
 
-        self.on
+        self.heating
     }
 
     pub fn set_state(&mut self, on: bool) {
@@ -0,0 +1,557 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use specs::prelude::*;
+
+use crate::vectors::{utils::get_cardinal_neighbours_with_z, Vector3i};
+
+//A wire of one colour on one tile
+#[derive(Clone)]
+struct WireNode {
+    entity: Entity,
+    color_name: String,
+}
+
+//Everything the power network needs to know about the ship's wiring, indexed by tile so the
+//solver never has to scan every wire. Kept up to date from dirty power nodes
+pub struct PowerGraph {
+    //Everything gets placed again, set for new and loaded games
+    pub dirty: bool,
+    wires: HashMap<Vector3i, Vec<WireNode>>,
+    //Wires that carry data, whether or not they carry power too
+    data_wires: HashMap<Vector3i, Vec<WireNode>>,
+    //Breakers join all colours on their tile while closed and cut the tile off while open. For data
+    //they are junctions that join every colour on their tile whatever state they are in
+    breakers: HashMap<Vector3i, (Entity, bool)>,
+    //Powered devices and power sources
+    devices: HashMap<Vector3i, Vec<Entity>>,
+    placed: HashMap<Entity, Vector3i>,
+    //Circuit of every entity on a wire as of the last update, the rest are a circuit of their own
+    circuits: HashMap<Entity, usize>,
+    //Entities placed or taken out since the last update
+    changed: HashSet<Entity>,
+}
+
+//What the power system hands out after a solve
+#[derive(Default)]
+pub struct PowerSolution {
+    //Every placed entity on a solved circuit and the circuit it is on
+    pub circuits: HashMap<Entity, usize>,
+    //Watts each wire carries to whatever is beyond it
+    pub wire_loads: HashMap<Entity, f32>,
+    //Watts each closed breaker passes on into its other colours
+    pub breaker_loads: HashMap<Entity, f32>,
+    //What is left of each connected source after its share of the load
+    pub source_available: HashMap<Entity, f32>,
+    //Supply minus demand of each circuit
+    pub circuit_available: HashMap<usize, f32>,
+}
+
+impl PowerSolution {
+    //Entities on no wire are a circuit of their own
+    pub fn circuit(&self, entity: Entity) -> usize {
+        self.circuits
+            .get(&entity)
+            .copied()
+            .unwrap_or(entity.id() as usize)
+    }
+
+    pub fn available(&self, entity: Entity) -> f32 {
+        match self.circuits.get(&entity) {
+            Some(circuit) => self.circuit_available.get(circuit).copied().unwrap_or(0.0),
+            None => 0.0,
+        }
+    }
+}
+
+impl Default for PowerGraph {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PowerGraph {
+    pub fn new() -> Self {
+        Self {
+            dirty: true,
+            wires: HashMap::new(),
+            data_wires: HashMap::new(),
+            breakers: HashMap::new(),
+            devices: HashMap::new(),
+            placed: HashMap::new(),
+            circuits: HashMap::new(),
+            changed: HashSet::new(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.wires.clear();
+        self.data_wires.clear();
+        self.breakers.clear();
+        self.devices.clear();
+        self.placed.clear();
+        self.circuits.clear();
+        self.changed.clear();
+    }
+
+    pub fn insert_wire(
+        &mut self,
+        entity: Entity,
+        position: Vector3i,
+        color_name: String,
+        data: bool,
+        power: bool,
+    ) {
+        let node = WireNode { entity, color_name };
+
+        if data {
+            self.data_wires
+                .entry(position)
+                .or_default()
+                .push(node.clone());
+        }
+
+        if power {
+            self.wires.entry(position).or_default().push(node);
+        }
+        self.placed.insert(entity, position);
+        self.changed.insert(entity);
+    }
+
+    pub fn insert_breaker(&mut self, entity: Entity, position: Vector3i, closed: bool) {
+        self.breakers.insert(position, (entity, closed));
+        self.placed.insert(entity, position);
+        self.changed.insert(entity);
+    }
+
+    pub fn insert_device(&mut self, entity: Entity, position: Vector3i) {
+        self.devices.entry(position).or_default().push(entity);
+        self.placed.insert(entity, position);
+        self.changed.insert(entity);
+    }
+
+    //Takes the entity out wherever it was placed, before it is placed again or once it is gone
+    pub fn remove(&mut self, entity: Entity) {
+        let position = match self.placed.remove(&entity) {
+            Some(position) => position,
+            None => return,
+        };
+        self.changed.insert(entity);
+
+        for wire_map in [&mut self.wires, &mut self.data_wires] {
+            if let Some(wires) = wire_map.get_mut(&position) {
+                wires.retain(|wire| wire.entity != entity);
+
+                if wires.is_empty() {
+                    wire_map.remove(&position);
+                }
+            }
+        }
+
+        if matches!(self.breakers.get(&position), Some((breaker, _)) if *breaker == entity) {
+            self.breakers.remove(&position);
+        }
+
+        if let Some(devices) = self.devices.get_mut(&position) {
+            devices.retain(|device| *device != entity);
+
+            if devices.is_empty() {
+                self.devices.remove(&position);
+            }
+        }
+    }
+
+    //Takes out everything that has been deleted since it was placed
+    pub fn prune(&mut self, entities: &Entities) {
+        let gone: Vec<Entity> = self
+            .placed
+            .keys()
+            .filter(|entity| !entities.is_alive(**entity))
+            .copied()
+            .collect();
+
+        for entity in gone.iter() {
+            self.remove(*entity);
+        }
+    }
+
+    pub fn wire_count(&self) -> usize {
+        self.wires.values().map(|wires| wires.len()).sum()
+    }
+
+    pub fn has_data(&self, position: &Vector3i) -> bool {
+        self.data_wires.contains_key(position)
+    }
+
+    //Every tile a terminal on this tile can reach, through any breaker on the way
+    pub fn data_network(&self, position: Vector3i) -> HashSet<Vector3i> {
+        self.data_search(position, true)
+    }
+
+    //Tiles on the data runs leaving this tile, stopping at the next breaker and leaving out
+    //signal cable, what a breaker box controls
+    pub fn data_run(&self, position: Vector3i) -> HashSet<Vector3i> {
+        self.data_search(position, false)
+    }
+
+    fn data_search(&self, start: Vector3i, through_junctions: bool) -> HashSet<Vector3i> {
+        let carries_power = |position: &Vector3i, wire: &WireNode| {
+            through_junctions
+                || matches!(self.wires.get(position), Some(wires) if wires.iter().any(|power| power.entity == wire.entity))
+        };
+
+        let mut tiles = HashSet::from([start]);
+        let mut visited = HashSet::new();
+        let mut queue: VecDeque<(Vector3i, &WireNode)> = self
+            .data_wires
+            .get(&start)
+            .into_iter()
+            .flatten()
+            .filter(|wire| carries_power(&start, wire))
+            .map(|wire| (start, wire))
+            .collect();
+
+        while let Some((position, wire)) = queue.pop_front() {
+            if !visited.insert(wire.entity) {
+                continue;
+            }
+            tiles.insert(position);
+
+            let junction = self.breakers.contains_key(&position);
+
+            if position != start && junction && !through_junctions {
+                continue;
+            }
+
+            if junction {
+                for other in self.data_wires.get(&position).into_iter().flatten() {
+                    if !visited.contains(&other.entity) && carries_power(&position, other) {
+                        queue.push_back((position, other));
+                    }
+                }
+            }
+
+            for neighbour in get_cardinal_neighbours_with_z(position).into_iter() {
+                for next in self.data_wires.get(&neighbour).into_iter().flatten() {
+                    if next.color_name == wire.color_name
+                        && !visited.contains(&next.entity)
+                        && carries_power(&neighbour, next)
+                    {
+                        queue.push_back((neighbour, next));
+                    }
+                }
+            }
+        }
+
+        tiles
+    }
+
+    fn is_open(&self, position: &Vector3i) -> bool {
+        matches!(self.breakers.get(position), Some((_, false)))
+    }
+
+    //Wires a current can pass to from this one
+    fn connected(&self, position: Vector3i, color_name: &str) -> Vec<(Vector3i, &WireNode)> {
+        let mut connected = Vec::new();
+
+        if self.is_open(&position) {
+            return connected;
+        }
+
+        if self.breakers.contains_key(&position) {
+            if let Some(wires) = self.wires.get(&position) {
+                connected.extend(wires.iter().map(|wire| (position, wire)));
+            }
+        }
+
+        for neighbour in get_cardinal_neighbours_with_z(position).into_iter() {
+            if self.is_open(&neighbour) {
+                continue;
+            }
+
+            if let Some(wires) = self.wires.get(&neighbour) {
+                connected.extend(
+                    wires
+                        .iter()
+                        .filter(|wire| wire.color_name == color_name)
+                        .map(|wire| (neighbour, wire)),
+                );
+            }
+        }
+
+        connected
+    }
+
+    //Circuit the entity was on at the last update
+    pub fn circuit(&self, entity: Entity) -> usize {
+        self.circuits
+            .get(&entity)
+            .copied()
+            .unwrap_or(entity.id() as usize)
+    }
+
+    //Labels the circuits again after anything was placed or taken out and hands back the ones
+    //that need solving: wherever the changed entities are now and whatever they split off from
+    pub fn update_circuits(&mut self) -> HashSet<usize> {
+        if self.changed.is_empty() {
+            return HashSet::new();
+        }
+
+        let circuits = self.label_circuits();
+        let old: HashSet<usize> = self
+            .changed
+            .iter()
+            .map(|entity| self.circuit(*entity))
+            .collect();
+
+        let mut moved: Vec<Entity> = self
+            .circuits
+            .iter()
+            .filter(|(_, circuit)| old.contains(circuit))
+            .map(|(entity, _)| *entity)
+            .collect();
+        moved.extend(self.changed.drain());
+
+        let updated = moved
+            .into_iter()
+            .filter(|entity| self.placed.contains_key(entity))
+            .map(|entity| {
+                circuits
+                    .get(&entity)
+                    .copied()
+                    .unwrap_or(entity.id() as usize)
+            })
+            .collect();
+
+        self.circuits = circuits;
+        updated
+    }
+
+    //Every wire, and the devices and breakers on its tile, named after the circuit's lowest wire
+    //id so the names stay put
+    fn label_circuits(&self) -> HashMap<Entity, usize> {
+        let mut circuits = HashMap::new();
+        let mut positions: Vec<&Vector3i> = self.wires.keys().collect();
+        positions.sort();
+
+        for position in positions.into_iter() {
+            for seed in self.wires[position].iter() {
+                if circuits.contains_key(&seed.entity) {
+                    continue;
+                }
+
+                let mut members = vec![seed.entity];
+                let mut queue = VecDeque::from([(*position, seed)]);
+                let mut visited = HashSet::from([seed.entity]);
+
+                while let Some((wire_position, wire)) = queue.pop_front() {
+                    for (next_position, next) in self.connected(wire_position, &wire.color_name) {
+                        if visited.insert(next.entity) {
+                            members.push(next.entity);
+                            queue.push_back((next_position, next));
+                        }
+                    }
+                }
+
+                let circuit = members
+                    .iter()
+                    .map(|wire| wire.id() as usize)
+                    .min()
+                    .unwrap_or(0);
+
+                for wire in members.into_iter() {
+                    circuits.insert(wire, circuit);
+                }
+            }
+        }
+
+        //Devices and breakers join the circuit of the first wire on their tile
+        let on_tiles = self
+            .devices
+            .iter()
+            .flat_map(|(position, devices)| devices.iter().map(move |device| (position, device)))
+            .chain(
+                self.breakers
+                    .iter()
+                    .map(|(position, (breaker, _))| (position, breaker)),
+            );
+
+        for (position, entity) in on_tiles {
+            if let Some(wire) = self.wires.get(position).and_then(|wires| wires.first()) {
+                if let Some(circuit) = circuits.get(&wire.entity).copied() {
+                    circuits.insert(*entity, circuit);
+                }
+            }
+        }
+
+        circuits
+    }
+
+    //Loads and what is left over on the given circuits, everything else is left out of the
+    //solution. Sources maps each switched on source to its output, loads each switched on and
+    //working device to its draw
+    pub fn solve(
+        &self,
+        circuits: &HashSet<usize>,
+        sources: &HashMap<Entity, f32>,
+        loads: &HashMap<Entity, f32>,
+    ) -> PowerSolution {
+        let mut solution = PowerSolution::default();
+
+        for entity in self.placed.keys() {
+            let circuit = self.circuit(*entity);
+
+            if circuits.contains(&circuit) {
+                solution.circuits.insert(*entity, circuit);
+
+                if self.circuits.contains_key(entity) {
+                    solution.circuit_available.insert(circuit, 0.0);
+                }
+            }
+        }
+
+        //Supply and demand of every circuit
+        for device in self.devices.values().flatten() {
+            if let Some(circuit) = solution.circuits.get(device) {
+                if let Some(available) = solution.circuit_available.get_mut(circuit) {
+                    *available += sources.get(device).copied().unwrap_or(0.0);
+                    *available -= loads.get(device).copied().unwrap_or(0.0);
+                }
+            }
+        }
+
+        let sources: HashMap<Entity, f32> = sources
+            .iter()
+            .filter(|(source, _)| solution.circuits.contains_key(source))
+            .map(|(source, output)| (*source, *output))
+            .collect();
+        self.branch_loads(&sources, loads, &mut solution);
+
+        solution
+    }
+
+    //Walks the wiring outwards from the sources, every wire carries what is drawn beyond it
+    fn branch_loads(
+        &self,
+        sources: &HashMap<Entity, f32>,
+        loads: &HashMap<Entity, f32>,
+        solution: &mut PowerSolution,
+    ) {
+        let mut source_positions: Vec<Vector3i> = sources
+            .keys()
+            .filter_map(|source| self.placed.get(source))
+            .filter(|position| self.wires.contains_key(position))
+            .copied()
+            .collect();
+        source_positions.sort();
+        source_positions.dedup();
+
+        let mut wire_loads: HashMap<Entity, f32> = HashMap::new();
+        let mut parents: HashMap<Entity, (Entity, Vector3i)> = HashMap::new();
+        let mut order = Vec::new();
+        let mut queue = VecDeque::new();
+
+        for position in source_positions.iter() {
+            for wire in self.wires[position].iter() {
+                if wire_loads.insert(wire.entity, 0.0).is_none() {
+                    queue.push_back((*position, wire));
+                }
+            }
+        }
+
+        while let Some((position, wire)) = queue.pop_front() {
+            order.push((wire.entity, position));
+
+            for (next_position, next) in self.connected(position, &wire.color_name) {
+                if let std::collections::hash_map::Entry::Vacant(entry) =
+                    wire_loads.entry(next.entity)
+                {
+                    entry.insert(0.0);
+                    parents.insert(next.entity, (wire.entity, position));
+                    queue.push_back((next_position, next));
+                }
+            }
+        }
+
+        //Devices draw through the first wire that reached their tile
+        let mut loaded_positions = HashSet::new();
+
+        for (entity, position) in order.iter() {
+            if !loaded_positions.insert(*position) {
+                continue;
+            }
+
+            let draw: f32 = self
+                .devices
+                .get(position)
+                .into_iter()
+                .flatten()
+                .map(|device| loads.get(device).copied().unwrap_or(0.0))
+                .sum();
+
+            if let Some(load) = wire_loads.get_mut(entity) {
+                *load += draw;
+            }
+        }
+
+        for (entity, _) in order.iter().rev() {
+            let load = wire_loads.get(entity).copied().unwrap_or(0.0);
+
+            if let Some((parent, _)) = parents.get(entity) {
+                if let Some(parent_load) = wire_loads.get_mut(parent) {
+                    *parent_load += load;
+                }
+            }
+        }
+
+        //A breaker passes on whatever it feeds from one colour into another
+        for (entity, position) in order.iter() {
+            if let Some((breaker, true)) = self.breakers.get(position) {
+                if matches!(parents.get(entity), Some((_, parent_position)) if parent_position == position)
+                {
+                    *solution.breaker_loads.entry(*breaker).or_default() +=
+                        wire_loads.get(entity).copied().unwrap_or(0.0);
+                }
+            }
+        }
+
+        //Sources on the same tile feed the same wiring and split its load between them
+        let mut tile_sources: HashMap<Vector3i, (f32, usize)> = HashMap::new();
+
+        for (source, output) in sources.iter() {
+            if let Some(position) = self.placed.get(source) {
+                let (total_output, count) = tile_sources.entry(*position).or_default();
+                *total_output += output;
+                *count += 1;
+            }
+        }
+
+        //Each source covers its share of the load on the wiring it feeds first
+        for (source, output) in sources.iter() {
+            let position = match self.placed.get(source) {
+                Some(position) => position,
+                None => continue,
+            };
+            let root_load = self
+                .wires
+                .get(position)
+                .and_then(|wires| wires.first())
+                .and_then(|wire| wire_loads.get(&wire.entity))
+                .copied();
+
+            if let Some(root_load) = root_load {
+                let (total_output, count) = tile_sources[position];
+                let share = if total_output > 0.0 {
+                    output / total_output
+                } else {
+                    1.0 / count as f32
+                };
+
+                solution
+                    .source_available
+                    .insert(*source, output - root_load * share);
+            }
+        }
+
+        solution.wire_loads = wire_loads;
+    }
+}
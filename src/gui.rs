@@ -1,16 +1,19 @@
 use std::u32::MAX;
 
-use crate::entities::intents::{InteractIntent, OpenIntent, PickUpIntent};
+use crate::clock::GameClock;
+use crate::entities::logic::{DelayTimer, LogicGate, Sensor, Signal};
+use crate::entities::network::{NetworkAddress, Terminal};
 use crate::entities::power_components::{
-    ControlPanel, PowerNode, PowerSource, PowerSwitch, PoweredState, Wire,
+    Battery, ControlPanel, Generator, PowerNode, PowerSource, PowerSwitch, PoweredState, Wire,
 };
 use crate::graphics::char_to_glyph;
 use crate::menu::interaction_menu;
 use crate::systems::event_system::{
-    get_default_interactions, InteractionInformation, InteractionType,
+    get_action_progress, get_default_interactions, InteractionInformation,
 };
+use crate::systems::network_system::get_devices_on_data_network;
 use crate::systems::power_system::get_devices_on_subnetwork;
-use crate::{mouse_to_map, InContainer, Installed, Item, INTERACT_MENU_WIDTH};
+use crate::{mouse_to_map, InContainer, Installed, INTERACT_MENU_WIDTH};
 use crate::{systems::event_system::get_entity_interactions, Renderable};
 use rltk::{letter_to_option, to_char, Point, Rltk, VirtualKeyCode, RGB};
 use specs::prelude::*;
@@ -60,6 +63,15 @@ pub fn draw_ui(ecs: &World, ctx: &mut Rltk, draw_pointer: bool) {
         y += 1;
     }
 
+    draw_action_progress(ecs, ctx);
+
+    let ship_time = format!("Ship time {}", ecs.fetch::<GameClock>().ship_time());
+    ctx.print(
+        TERMINAL_WIDTH - ship_time.len() as i32 - 2,
+        MAP_SCREEN_HEIGHT,
+        ship_time,
+    );
+
     let viewport_position = get_viewport_position(&ecs);
 
     if draw_pointer {
@@ -107,6 +119,7 @@ pub fn draw_ui(ecs: &World, ctx: &mut Rltk, draw_pointer: bool) {
     let viewsheds = ecs.read_storage::<Viewshed>();
     let positions = ecs.read_storage::<Vector3i>();
     let control_paneles = ecs.read_storage::<ControlPanel>();
+    let terminals = ecs.read_storage::<Terminal>();
     let entities = ecs.entities();
 
     let player = get_player_entity(&entities, &players);
@@ -131,6 +144,11 @@ pub fn draw_ui(ecs: &World, ctx: &mut Rltk, draw_pointer: bool) {
                     if let Some(_) = control_paneles.get(entity) {
                         interactables.append(&mut get_devices_on_subnetwork(&ecs, entity));
                     }
+
+                    //Terminals reach everything on the data network
+                    if terminals.get(entity).is_some() {
+                        interactables.append(&mut get_devices_on_data_network(&ecs, entity));
+                    }
                 }
             }
         }
@@ -211,6 +229,32 @@ pub fn draw_ui(ecs: &World, ctx: &mut Rltk, draw_pointer: bool) {
     }
 }
 
+//Progress bar for the player's current multi-turn action, drawn on the log box border
+fn draw_action_progress(ecs: &World, ctx: &mut Rltk) {
+    const BAR_WIDTH: usize = 10;
+
+    let player = *ecs.fetch::<Entity>();
+
+    if let Some((description, progress, paused)) = get_action_progress(ecs, player) {
+        let filled = (progress * BAR_WIDTH as f32).round() as usize;
+        let bar = format!("{}{}", "#".repeat(filled), "-".repeat(BAR_WIDTH - filled));
+
+        let text = if paused {
+            format!("[{}] {} {:.0}% (paused, C to continue)", bar, description, progress * 100.0)
+        } else {
+            format!("[{}] {} {:.0}%", bar, description, progress * 100.0)
+        };
+
+        ctx.print_color(
+            2,
+            MAP_SCREEN_HEIGHT,
+            RGB::named(if paused { rltk::ORANGE } else { rltk::GOLD }),
+            RGB::named(rltk::BLACK),
+            text,
+        );
+    }
+}
+
 pub fn draw_tooltips(ecs: &World, ctx: &mut Rltk, target: Vector3i) {
     let entities = ecs.entities();
     let players = ecs.read_storage::<Player>();
@@ -375,11 +419,18 @@ pub fn interact_gui(
     let power_states = game_state.ecs.read_storage::<PoweredState>();
     let power_switches = game_state.ecs.read_storage::<PowerSwitch>();
     let power_sources = game_state.ecs.read_storage::<PowerSource>();
+    let batteries = game_state.ecs.read_storage::<Battery>();
     let wires = game_state.ecs.read_storage::<Wire>();
     let nodes = game_state.ecs.read_storage::<PowerNode>();
     let control_paneles = game_state.ecs.read_storage::<ControlPanel>();
+    let generators = game_state.ecs.read_storage::<Generator>();
+    let terminals = game_state.ecs.read_storage::<Terminal>();
+    let addresses = game_state.ecs.read_storage::<NetworkAddress>();
+    let signals = game_state.ecs.read_storage::<Signal>();
+    let sensors = game_state.ecs.read_storage::<Sensor>();
+    let logic_gates = game_state.ecs.read_storage::<LogicGate>();
+    let delay_timers = game_state.ecs.read_storage::<DelayTimer>();
     let installed = game_state.ecs.read_storage::<Installed>();
-    let items = game_state.ecs.read_storage::<Item>();
 
     let player = get_player_entity(&entities, &players);
 
@@ -409,6 +460,11 @@ pub fn interact_gui(
             if let Some(_) = control_paneles.get(entity) {
                 interactables.append(&mut get_devices_on_subnetwork(&game_state.ecs, entity));
             }
+
+            //Terminals reach everything on the data network
+            if terminals.get(entity).is_some() {
+                interactables.append(&mut get_devices_on_data_network(&game_state.ecs, entity));
+            }
         }
         _ => {}
     }
@@ -592,6 +648,81 @@ pub fn interact_gui(
                         format!("Power load: {}", wire.power_load),
                     );
                     y += 1;
+                    ctx.print(
+                        MAP_SCREEN_WIDTH + 1,
+                        entity_menu_y + y,
+                        format!("Wire: {}", wire.state_description()),
+                    );
+                    y += 1;
+                }
+                if let Some(control_panel) = control_paneles.get(*entity) {
+                    ctx.print(
+                        MAP_SCREEN_WIDTH + 1,
+                        entity_menu_y + y,
+                        format!("Breaker: {}", control_panel.state_description()),
+                    );
+                    y += 1;
+                }
+                if let Some(generator) = generators.get(*entity) {
+                    ctx.print(
+                        MAP_SCREEN_WIDTH + 1,
+                        entity_menu_y + y,
+                        format!("Generator: {}", generator.state_description()),
+                    );
+                    y += 1;
+                }
+                if let Some(address) = addresses.get(*entity) {
+                    ctx.print(
+                        MAP_SCREEN_WIDTH + 1,
+                        entity_menu_y + y,
+                        format!("Address: {}", address.label()),
+                    );
+                    y += 1;
+                }
+                if let Some(terminal) = terminals.get(*entity) {
+                    ctx.print(
+                        MAP_SCREEN_WIDTH + 1,
+                        entity_menu_y + y,
+                        format!("Terminal: {}", terminal.state_description()),
+                    );
+                    y += 1;
+
+                    for alarm in terminal.alarms.iter() {
+                        ctx.print(MAP_SCREEN_WIDTH + 2, entity_menu_y + y, alarm);
+                        y += 1;
+                    }
+                }
+                if let Some(sensor) = sensors.get(*entity) {
+                    ctx.print(
+                        MAP_SCREEN_WIDTH + 1,
+                        entity_menu_y + y,
+                        format!("Sensor: {}", sensor.state_description()),
+                    );
+                    y += 1;
+                }
+                if let Some(logic_gate) = logic_gates.get(*entity) {
+                    ctx.print(
+                        MAP_SCREEN_WIDTH + 1,
+                        entity_menu_y + y,
+                        format!("Gate: {:?}", logic_gate.kind),
+                    );
+                    y += 1;
+                }
+                if let Some(delay_timer) = delay_timers.get(*entity) {
+                    ctx.print(
+                        MAP_SCREEN_WIDTH + 1,
+                        entity_menu_y + y,
+                        format!("Timer: {}", delay_timer.state_description()),
+                    );
+                    y += 1;
+                }
+                if let Some(signal) = signals.get(*entity) {
+                    ctx.print(
+                        MAP_SCREEN_WIDTH + 1,
+                        entity_menu_y + y,
+                        format!("Signal: {}", signal.state_description()),
+                    );
+                    y += 1;
                 }
                 if let Some(power_switch) = power_switches.get(*entity) {
                     ctx.print(
@@ -609,6 +740,14 @@ pub fn interact_gui(
                     );
                     y += 1;
                 }
+                if let Some(battery) = batteries.get(*entity) {
+                    ctx.print(
+                        MAP_SCREEN_WIDTH + 1,
+                        entity_menu_y + y,
+                        format!("Charge: {}", battery.state_description()),
+                    );
+                    y += 1;
+                }
                 if let Some(node) = nodes.get(*entity) {
                     ctx.print(
                         MAP_SCREEN_WIDTH + 1,
@@ -823,53 +962,8 @@ pub fn interact_gui(
                     }
                 } else {
                     if let Some(interactable) = interactables.get(selection as usize) {
-                        let entity = entities.entity(interactable.entity_id);
                         if let Some(player) = player {
-                            match interactable.interaction_type {
-                                InteractionType::Component => {
-                                    let mut interactions =
-                                        game_state.ecs.write_storage::<InteractIntent>();
-                                    let _ = interactions.insert(
-                                        player,
-                                        InteractIntent::new(
-                                            player,
-                                            entity,
-                                            interactable.id,
-                                            interactable.description.clone(),
-                                            interactable.cost,
-                                        ),
-                                    );
-                                }
-                                InteractionType::PickUp => {
-                                    let mut pick_up_intents =
-                                        game_state.ecs.write_storage::<PickUpIntent>();
-                                    let volume = if let Some(item) = items.get(entity) {
-                                        item.volume
-                                    } else {
-                                        0.0
-                                    };
-
-                                    let _ = pick_up_intents.insert(
-                                        player,
-                                        PickUpIntent::new(player, entity, interactable.cost),
-                                    );
-                                }
-                                InteractionType::Open => {
-                                    let mut open_intents =
-                                        game_state.ecs.write_storage::<OpenIntent>();
-                                    let _ = open_intents.insert(
-                                        player,
-                                        OpenIntent::new(
-                                            player,
-                                            entity,
-                                            interactable.id,
-                                            interactable.description.clone(),
-                                            interactable.cost,
-                                        ),
-                                    );
-                                }
-                                InteractionType::Drop => {}
-                            }
+                            interactable.queue(&game_state.ecs, player);
 
                             return RunState::Ticking;
                         }
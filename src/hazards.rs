@@ -0,0 +1,111 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::rng;
+
+//Quiet period at the start before anything goes wrong
+const GRACE_TURNS: u64 = 300;
+//Turns between hazards at the start and once the ship has been falling apart for a while
+const BASE_INTERVAL: u64 = 400;
+const MIN_INTERVAL: u64 = 100;
+//Elapsed turns per turn taken off the interval
+const INTERVAL_RAMP: u64 = 20;
+//Elapsed turns for severity to go up by one
+const SEVERITY_RAMP: f32 = 3600.0;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Hazard {
+    //A hull section next to a pressurised room gives way to space
+    HullBreach,
+    //A control panel switches itself off
+    BreakerTrip,
+    //A powered lamp burns out
+    LampFailure,
+    //Gas escapes into a pressurised tile
+    GasLeak,
+}
+
+impl Hazard {
+    pub fn message(&self) -> &'static str {
+        match self {
+            Hazard::HullBreach => "A deep groan runs through the hull",
+            Hazard::BreakerTrip => "Somewhere a breaker trips with a loud clack",
+            Hazard::LampFailure => "A light flickers and dies",
+            Hazard::GasLeak => "You hear the hiss of escaping gas",
+        }
+    }
+}
+
+//Decides when the next emergency happens and which one it is, the emergency itself waits in
+//the event scheduler
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HazardDirector {
+    pub enabled: bool,
+    //Hazards and their relative weights
+    pub table: Vec<(Hazard, u32)>,
+}
+
+impl Default for HazardDirector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HazardDirector {
+    pub fn new() -> Self {
+        Self {
+            enabled: true,
+            table: vec![
+                (Hazard::BreakerTrip, 4),
+                (Hazard::LampFailure, 4),
+                (Hazard::GasLeak, 2),
+                (Hazard::HullBreach, 1),
+            ],
+        }
+    }
+
+    pub fn pick(&self) -> Option<Hazard> {
+        let total: u32 = self.table.iter().map(|(_, weight)| weight).sum();
+
+        if total == 0 {
+            return None;
+        }
+
+        let mut roll = rng::range(0, total as i32) as u32;
+
+        for (hazard, weight) in self.table.iter() {
+            if roll < *weight {
+                return Some(*hazard);
+            }
+            roll -= weight;
+        }
+
+        None
+    }
+
+    //Hazards come closer together the longer the ship has been running
+    pub fn interval(turn: u64) -> u64 {
+        BASE_INTERVAL
+            .saturating_sub(turn / INTERVAL_RAMP)
+            .max(MIN_INTERVAL)
+    }
+
+    //Multiplier for how bad a hazard is, grows with elapsed time
+    pub fn severity(turn: u64) -> f32 {
+        1.0 + turn as f32 / SEVERITY_RAMP
+    }
+
+    //Turn of the first hazard, nothing happens before the grace period is over
+    pub fn first_turn(turn: u64) -> u64 {
+        turn.max(GRACE_TURNS)
+    }
+
+    //Turn of the hazard after one that happened on the given turn
+    pub fn next_turn(turn: u64) -> u64 {
+        let interval = Self::interval(turn);
+        let jitter = rng::range(0, (interval / 2) as i32) as u64;
+
+        //Spread evenly around the interval
+        turn + interval * 3 / 4 + jitter
+    }
+}
@@ -0,0 +1,208 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use specs::prelude::*;
+
+use crate::{states::RunState, Illuminant, Map, PowerSource, PoweredState, State};
+
+pub const DEFAULT_STEPS: usize = 100;
+
+#[derive(Serialize)]
+pub struct SimulationReport {
+    pub steps: usize,
+    pub atmosphere: AtmosphereStats,
+    pub power: PowerStats,
+    pub lighting: LightingStats,
+}
+
+//Temperature and pressure only cover tiles that hold any gas
+#[derive(Serialize, Default)]
+pub struct AtmosphereStats {
+    pub tiles: usize,
+    pub pressurised_tiles: usize,
+    pub total_mols: f32,
+    pub gas_mols: BTreeMap<String, f32>,
+    pub min_temperature: f32,
+    pub mean_temperature: f32,
+    pub max_temperature: f32,
+    pub min_pressure: f32,
+    pub mean_pressure: f32,
+    pub max_pressure: f32,
+}
+
+#[derive(Serialize, Default)]
+pub struct PowerStats {
+    pub sources: usize,
+    pub sources_on: usize,
+    pub max_wattage: f32,
+    pub available_wattage: f32,
+    pub devices: usize,
+    pub devices_on: usize,
+    pub devices_supplied: usize,
+    pub demanded_wattage: f32,
+}
+
+#[derive(Serialize, Default)]
+pub struct LightingStats {
+    pub illuminants: usize,
+    pub illuminants_on: usize,
+    pub lit_tiles: usize,
+    pub mean_light_level: f32,
+    pub max_light_level: f32,
+}
+
+//Returns the number of steps to run if the game was started with --headless
+pub fn parse_args(args: &[String]) -> Result<Option<usize>, String> {
+    if !args.iter().any(|arg| arg == "--headless") {
+        return Ok(None);
+    }
+
+    match args.iter().position(|arg| arg == "--steps") {
+        Some(index) => match args.get(index + 1) {
+            Some(steps) => steps
+                .parse::<usize>()
+                .map(Some)
+                .map_err(|_| format!("Invalid step count: {}", steps)),
+            None => Err("--steps needs a value".to_string()),
+        },
+        None => Ok(Some(DEFAULT_STEPS)),
+    }
+}
+
+pub fn run(game_state: &mut State, steps: usize) -> rltk::BError {
+    game_state.ecs.insert(RunState::Simulation { steps });
+
+    for _ in 0..steps {
+        game_state.run_simulation();
+    }
+
+    let report = SimulationReport {
+        steps,
+        atmosphere: atmosphere_stats(&game_state.ecs),
+        power: power_stats(&game_state.ecs),
+        lighting: lighting_stats(&game_state.ecs),
+    };
+
+    println!("{}", serde_json::to_string_pretty(&report)?);
+
+    Ok(())
+}
+
+pub fn atmosphere_stats(ecs: &World) -> AtmosphereStats {
+    let map = ecs.fetch::<Map>();
+    let mut stats = AtmosphereStats {
+        tiles: map.tiles.len(),
+        min_temperature: f32::MAX,
+        min_pressure: f32::MAX,
+        ..Default::default()
+    };
+
+    //Sum in f64, f32 drifts noticeably over a few thousand tiles
+    let mut mols_sum = 0.0f64;
+    let mut gas_mols_sums: BTreeMap<String, f64> = BTreeMap::new();
+    let mut temperature_sum = 0.0f64;
+    let mut pressure_sum = 0.0f64;
+
+    for tile in map.tiles.values() {
+        let atmosphere = &tile.atmosphere;
+        let total_mols = atmosphere.get_total_mols();
+
+        if total_mols <= 0.0 {
+            continue;
+        }
+
+        stats.pressurised_tiles += 1;
+        mols_sum += total_mols as f64;
+
+        for (gas, mols) in atmosphere.gasses.iter() {
+            *gas_mols_sums.entry(gas.to_string()).or_insert(0.0) += *mols as f64;
+        }
+
+        stats.min_temperature = stats.min_temperature.min(atmosphere.temperature);
+        stats.max_temperature = stats.max_temperature.max(atmosphere.temperature);
+        temperature_sum += atmosphere.temperature as f64;
+
+        stats.min_pressure = stats.min_pressure.min(atmosphere.pressure);
+        stats.max_pressure = stats.max_pressure.max(atmosphere.pressure);
+        pressure_sum += atmosphere.pressure as f64;
+    }
+
+    stats.total_mols = mols_sum as f32;
+    stats.gas_mols = gas_mols_sums
+        .into_iter()
+        .map(|(gas, mols)| (gas, mols as f32))
+        .collect();
+
+    if stats.pressurised_tiles > 0 {
+        stats.mean_temperature = (temperature_sum / stats.pressurised_tiles as f64) as f32;
+        stats.mean_pressure = (pressure_sum / stats.pressurised_tiles as f64) as f32;
+    } else {
+        stats.min_temperature = 0.0;
+        stats.min_pressure = 0.0;
+    }
+
+    stats
+}
+
+pub fn power_stats(ecs: &World) -> PowerStats {
+    let power_sources = ecs.read_storage::<PowerSource>();
+    let power_states = ecs.read_storage::<PoweredState>();
+    let mut stats = PowerStats::default();
+
+    for power_source in power_sources.join() {
+        stats.sources += 1;
+        stats.max_wattage += power_source.max_wattage;
+        stats.available_wattage += power_source.available_wattage;
+
+        if power_source.on {
+            stats.sources_on += 1;
+        }
+    }
+
+    for power_state in power_states.join() {
+        stats.devices += 1;
+
+        if power_state.on {
+            stats.devices_on += 1;
+            stats.demanded_wattage += power_state.wattage;
+
+            if power_state.available_wattage >= power_state.wattage {
+                stats.devices_supplied += 1;
+            }
+        }
+    }
+
+    stats
+}
+
+pub fn lighting_stats(ecs: &World) -> LightingStats {
+    let map = ecs.fetch::<Map>();
+    let illuminants = ecs.read_storage::<Illuminant>();
+    let mut stats = LightingStats::default();
+    let mut total_light_level = 0.0f64;
+
+    for illuminant in illuminants.join() {
+        stats.illuminants += 1;
+
+        if illuminant.on {
+            stats.illuminants_on += 1;
+        }
+    }
+
+    for tile in map.tiles.values() {
+        let light_level = tile.photometry.light_level;
+
+        if light_level > 0.0 {
+            stats.lit_tiles += 1;
+        }
+
+        total_light_level += light_level as f64;
+        stats.max_light_level = stats.max_light_level.max(light_level);
+    }
+
+    if !map.tiles.is_empty() {
+        stats.mean_light_level = (total_light_level / map.tiles.len() as f64) as f32;
+    }
+
+    stats
+}
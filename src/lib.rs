@@ -0,0 +1,417 @@
+use entities::atmospherics::Atmosphere;
+use entities::biology::{Breather, Thermoregulation, Vitals};
+use entities::construction::ConstructionMaterial;
+use entities::crew::CrewAi;
+use entities::intents::{Initiative, IntentStorages, Interrupt};
+use entities::power_components::{
+    Battery, Broken, ControlPanel, ElectronicHeater, Fuel, Generator, PowerNode, PowerSource,
+    PowerSwitch, PoweredState, Wire,
+};
+use entities::fire::{Burning, Fire, Flammable};
+use entities::logic::{DelayTimer, LogicGate, Sensor, Signal};
+use entities::network::{NetworkAddress, Terminal};
+use entities::props::Cabinet;
+use graphics::render_map;
+use menu::ItemMenuResult;
+use raws::RawError;
+use rltk::{GameState, Rltk, VirtualKeyCode};
+use specs::prelude::*;
+
+extern crate serde;
+
+use specs::saveload::{SimpleMarker, SimpleMarkerAllocator};
+
+use states::RunState;
+use vectors::Vector3i;
+
+use crate::camera::*;
+use crate::entities::components::*;
+use crate::graphics::components::*;
+use crate::map::components::*;
+use crate::map::*;
+use crate::player::*;
+
+pub const FONT_WIDTH: i32 = 14;
+pub const FONT_HEIGHT: i32 = 14;
+
+pub const SEED: u64 = 1;
+
+//const FONT: &str = "MxPlus_HP_100LX_16x12.png";
+pub const FONT: &str = "cp437_14x14.png";
+//const FONT: &str = "unicode_16x16.png";
+pub const GUI_FONT: &str = "terminal8x8.png";
+
+pub const TERMINAL_WIDTH: i32 = 160;
+pub const TERMINAL_HEIGHT: i32 = 90;
+pub const INTERACT_MENU_WIDTH: i32 = 35;
+pub const MAP_SCREEN_WIDTH: i32 = TERMINAL_WIDTH - INTERACT_MENU_WIDTH;
+pub const MAP_SCREEN_HEIGHT: i32 = 80;
+pub const MAP_SIZE: i32 = 100;
+
+pub const SHOW_FPS: bool = true;
+
+pub mod camera;
+pub mod clock;
+pub mod colors;
+pub mod entities;
+pub mod gamelog;
+pub mod graphics;
+pub mod gui;
+pub mod hazards;
+pub mod headless;
+pub mod map;
+pub mod map_builders;
+pub mod menu;
+pub mod player;
+pub mod raws;
+pub mod rng;
+pub mod save_load_system;
+pub mod spawner;
+pub mod states;
+pub mod vectors;
+
+pub mod systems;
+
+pub struct State {
+    pub ecs: World,
+    pub dispatcher: Box<dyn systems::UnifiedDispatcher + 'static>,
+}
+
+impl State {
+    pub fn run_systems(&mut self) {
+        //use std::time::Instant;
+        //let now = Instant::now();
+
+        self.dispatcher.run_now(&mut self.ecs);
+
+        //let elapsed = now.elapsed();
+        //println!("Elapsed: {:.2?}", elapsed);
+
+        self.ecs.maintain();
+    }
+    pub fn run_simulation(&mut self) {
+        self.dispatcher.run_now(&mut self.ecs);
+        self.ecs.maintain();
+    }
+}
+
+impl GameState for State {
+    fn tick(&mut self, ctx: &mut Rltk) {
+        for index in 0..3 {
+            ctx.set_active_console(index);
+            ctx.set_translation_mode(index, rltk::CharacterTranslationMode::Codepage437);
+            ctx.cls();
+        }
+
+        let mut new_runstate;
+        {
+            let runstate = self.ecs.fetch::<RunState>();
+            new_runstate = (*runstate).clone();
+        }
+
+        match new_runstate {
+            RunState::MainMenu { .. } => {}
+            _ => {
+                render_map(&mut self.ecs, ctx);
+                gui::draw_ui(&self.ecs, ctx, true);
+            }
+        }
+
+        match new_runstate {
+            RunState::PreRun => {
+                self.run_systems();
+                self.ecs.maintain();
+
+                new_runstate = RunState::AwaitingInput;
+            }
+            RunState::AwaitingInput => {
+                new_runstate = player_input(self, ctx);
+            }
+            RunState::Ticking => {
+                if ctx.key == Some(VirtualKeyCode::Escape) {
+                    player::interrupt_player(&mut self.ecs, "cancelled");
+                }
+
+                self.run_systems();
+                self.ecs.maintain();
+
+                match *self.ecs.fetch::<RunState>() {
+                    RunState::AwaitingInput => new_runstate = RunState::AwaitingInput,
+                    RunState::ShowInventory {
+                        id: container_id,
+                        selected_item,
+                    } => {
+                        new_runstate = RunState::ShowInventory {
+                            id: container_id,
+                            selected_item,
+                        }
+                    }
+                    _ => new_runstate = RunState::Ticking,
+                }
+            }
+            RunState::MainMenu { .. } => {
+                let result = menu::main_menu(self, ctx);
+
+                match result {
+                    gui::MainMenuResult::NoSelection { selected } => {
+                        new_runstate = RunState::MainMenu {
+                            menu_selection: selected,
+                        }
+                    }
+                    gui::MainMenuResult::Selected { selected } => {
+                        match selected {
+                            gui::MainMenuSelection::NewGame => new_runstate = RunState::PreRun,
+                            gui::MainMenuSelection::LoadGame => {
+                                save_load_system::load_game(&mut self.ecs, ctx);
+                                new_runstate = RunState::AwaitingInput;
+                                //save_load_system::delete_save();
+                            }
+                            gui::MainMenuSelection::Quit => {
+                                ::std::process::exit(0);
+                            }
+                        }
+                    }
+                }
+            }
+            RunState::SaveGame => {
+                save_load_system::save_game(&mut self.ecs);
+                new_runstate = RunState::MainMenu {
+                    menu_selection: gui::MainMenuSelection::LoadGame,
+                };
+            }
+            RunState::InteractGUI {
+                range,
+                source,
+                target,
+                prev_mouse_position,
+                selected_entity,
+            } => {
+                new_runstate = gui::interact_gui(
+                    self,
+                    ctx,
+                    range,
+                    source,
+                    target,
+                    prev_mouse_position,
+                    selected_entity,
+                    false,
+                );
+
+                //If the gui exits snap the camera position to the player
+                match new_runstate {
+                    RunState::AwaitingInput | RunState::PreRun => {
+                        crate::reset_camera_position(&mut self.ecs);
+                    }
+                    _ => {}
+                }
+            }
+            RunState::HandleOtherInput { next_runstate, key } => {
+                new_runstate = handle_other_input(&mut self.ecs, key, (*next_runstate).clone());
+            }
+            RunState::Simulation { steps } => {
+                for _ in 0..steps {
+                    self.run_simulation();
+                }
+            }
+            RunState::ConstructGUI { target } => {
+                new_runstate = menu::show_construction(self, ctx, target);
+            }
+            RunState::ShowInventory {
+                id: container_id,
+                selected_item,
+            } => {
+                let result = menu::show_inventory(self, ctx, container_id, selected_item);
+
+                match result.0 {
+                    ItemMenuResult::NoResponse => {
+                        //new_runstate = RunState::AwaitingInput;
+                    }
+                    ItemMenuResult::Cancel => {
+                        new_runstate = RunState::Ticking;
+                    }
+                    ItemMenuResult::Selected => {
+                        let item_entity = result.1.unwrap();
+
+                        new_runstate = RunState::ShowInventory {
+                            id: container_id,
+                            selected_item: Some(item_entity),
+                        };
+                    }
+                    ItemMenuResult::Action => {
+                        new_runstate = RunState::Ticking;
+                    }
+                }
+            }
+        }
+
+        {
+            let mut run_writer = self.ecs.write_resource::<RunState>();
+            *run_writer = new_runstate;
+        }
+        if SHOW_FPS {
+            ctx.print(1, 1, format!("FPS: {}", ctx.fps));
+        }
+    }
+}
+
+pub fn register_components(ecs: &mut World) {
+    ecs.register::<Vector3i>();
+    ecs.register::<Renderable>();
+    ecs.register::<Tile>();
+    ecs.register::<Player>();
+    ecs.register::<Name>();
+    ecs.register::<Viewshed>();
+    ecs.register::<Camera>();
+    ecs.register::<Illuminant>();
+    ecs.register::<Photometry>();
+    ecs.register::<SimpleMarker<SerializeThis>>();
+    ecs.register::<SerializationHelper>();
+    ecs.register::<Blocker>();
+    ecs.register::<VisionBlocker>();
+    ecs.register::<Door>();
+    ecs.register::<AutoClose>();
+    ecs.register::<PowerSource>();
+    ecs.register::<Wire>();
+    ecs.register::<Duct>();
+    ecs.register::<EntityDirection>();
+
+    //Power
+    ecs.register::<PoweredState>();
+    ecs.register::<PowerSwitch>();
+    ecs.register::<PowerNode>();
+    ecs.register::<ControlPanel>();
+    ecs.register::<Broken>();
+    ecs.register::<Battery>();
+    ecs.register::<Generator>();
+    ecs.register::<Fuel>();
+    ecs.register::<Terminal>();
+    ecs.register::<NetworkAddress>();
+
+    //Logic
+    ecs.register::<Signal>();
+    ecs.register::<Sensor>();
+    ecs.register::<LogicGate>();
+    ecs.register::<DelayTimer>();
+
+    //Atmospherics
+    ecs.register::<Atmosphere>();
+    ecs.register::<Breather>();
+    ecs.register::<Vitals>();
+    ecs.register::<Thermoregulation>();
+    ecs.register::<Dragged>();
+
+    //Temperature
+    ecs.register::<ElectronicHeater>();
+
+    //Fire
+    ecs.register::<Fire>();
+    ecs.register::<Flammable>();
+    ecs.register::<Burning>();
+    ecs.register::<Health>();
+
+    //Intents
+    ecs.register::<Initiative>();
+    IntentStorages::setup(ecs);
+    ecs.register::<Interrupt>();
+
+    //Crew
+    ecs.register::<CrewAi>();
+
+    //Item
+    ecs.register::<Installed>();
+    ecs.register::<Cabinet>();
+    ecs.register::<ConstructionMaterial>();
+
+    //Classification
+    ecs.register::<Prop>();
+    ecs.register::<Item>();
+    ecs.register::<InContainer>();
+    ecs.register::<Container>();
+}
+
+pub struct WorldConfig {
+    pub seed: u64,
+    pub map_size: i32,
+    pub player_start_position: Vector3i,
+}
+
+impl Default for WorldConfig {
+    fn default() -> Self {
+        Self {
+            seed: SEED,
+            map_size: MAP_SIZE,
+            player_start_position: Vector3i::new(0, 0, 10),
+        }
+    }
+}
+
+//Builds the map, spawns its entities and the player and inserts the starting resources
+pub fn create_world(
+    config: WorldConfig,
+) -> Result<(World, Box<dyn systems::UnifiedDispatcher + 'static>), RawError> {
+    let mut ecs = World::new();
+    register_components(&mut ecs);
+
+    tile_blueprints::load_blueprints()?;
+    raws::load_raws()?;
+
+    rng::reseed(config.seed);
+
+    ecs.insert(SimpleMarkerAllocator::<SerializeThis>::new());
+
+    let mut builder = map_builders::build_small_cargo_ship_map(
+        Vector3i::new(config.map_size, config.map_size, 5),
+        config.player_start_position,
+    );
+
+    builder.build_map()?;
+
+    let map = builder.get_map();
+
+    ecs.insert(map);
+    ecs.insert(map::zones::AtmosphereZones::new());
+    ecs.insert(map::pressure::PressureForces::new());
+    ecs.insert(map::structure::DamageQueue::new());
+    ecs.insert(entities::power_graph::PowerGraph::new());
+    ecs.insert(entities::network::AlarmQueue::new());
+    ecs.insert(clock::GameClock::new());
+    ecs.insert(clock::EventScheduler::new());
+    ecs.insert(hazards::HazardDirector::new());
+    builder.spawn_entities(&mut ecs);
+    ecs.insert(builder.get_map());
+
+    ecs.insert(gamelog::GameLog {
+        entries: vec!["Game log".to_string()],
+    });
+    ecs.insert(RunState::MainMenu {
+        menu_selection: gui::MainMenuSelection::NewGame,
+    });
+
+    //Create player
+    let player_entity = spawner::player(&mut ecs, config.player_start_position);
+
+    //Enough to patch a breach or two, anything that does not fit stays at the player's feet
+    let kit = vec![
+        spawner::steel_plating(&mut ecs, config.player_start_position),
+        spawner::steel_plating(&mut ecs, config.player_start_position),
+        spawner::glass_pane(&mut ecs, config.player_start_position),
+    ];
+    for item in kit {
+        if !spawner::put_item_in_container(&mut ecs, item, player_entity) {
+            let name = ecs
+                .read_storage::<Name>()
+                .get(item)
+                .map(|name| name.name.clone())
+                .unwrap_or("item".to_string());
+
+            ecs.fetch_mut::<gamelog::GameLog>()
+                .entries
+                .push(format!("There is no room for the {} in your pack", name));
+        }
+    }
+
+    ecs.insert(config.player_start_position);
+    ecs.insert(player_entity);
+
+    Ok((ecs, systems::build()))
+}
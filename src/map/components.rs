@@ -1,4 +1,5 @@
 use crate::entities::atmospherics::Atmosphere;
+use crate::map::structure::Structure;
 use crate::graphics::char_to_glyph;
 use crate::Photometry;
 use crate::Renderable;
@@ -8,6 +9,27 @@ use serde::{Deserialize, Serialize};
 use specs::prelude::*;
 use specs_derive::Component;
 
+//Heat held and passed on by the material of a tile, open tiles only hold their gas
+#[derive(Default, Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ThermalProperties {
+    //J/K of the tile material, on top of the heat capacity of its gas
+    pub mass: f32,
+    //W/K conducted to each neighbouring tile
+    pub conductivity: f32,
+    //How well faces open to space radiate heat away, 0.0 to 1.0
+    pub emissivity: f32,
+}
+
+impl ThermalProperties {
+    pub fn new(mass: f32, conductivity: f32, emissivity: f32) -> Self {
+        Self {
+            mass,
+            conductivity,
+            emissivity,
+        }
+    }
+}
+
 #[derive(Component, Debug, Clone, Serialize, Deserialize)]
 pub struct Tile {
     pub passable: bool,
@@ -17,6 +39,10 @@ pub struct Tile {
     pub airtight: bool,
     pub photometry: Photometry,
     pub atmosphere: Atmosphere,
+    #[serde(default)]
+    pub thermal: ThermalProperties,
+    #[serde(default)]
+    pub structure: Structure,
 }
 
 impl Tile {
@@ -40,6 +66,8 @@ impl Tile {
             atmosphere,
             name,
             airtight,
+            thermal: ThermalProperties::default(),
+            structure: Structure::default(),
         }
     }
     pub fn new_empty_stp() -> Tile {
@@ -57,9 +85,10 @@ impl Tile {
             atmosphere: Atmosphere::new_stp(),
             name: "Empty space".to_string(),
             airtight: false,
+            thermal: ThermalProperties::default(),
+            structure: Structure::default(),
         }
     }
-    #[allow(dead_code)]
     pub fn new_vacuume() -> Tile {
         Tile {
             passable: true,
@@ -75,6 +104,34 @@ impl Tile {
             atmosphere: Atmosphere::new_vacuume(),
             name: "Vacuume".to_string(),
             airtight: false,
+            thermal: ThermalProperties::default(),
+            structure: Structure::default(),
+        }
+    }
+
+    //Solid tiles share the temperature field of their (empty) atmosphere
+    pub fn temperature(&self) -> f32 {
+        self.atmosphere.temperature
+    }
+
+    pub fn heat_capacity(&self) -> f32 {
+        self.thermal.mass + self.atmosphere.get_heat_capacity()
+    }
+
+    pub fn add_heat(&mut self, joules: f32) {
+        let heat_capacity = self.heat_capacity();
+
+        if heat_capacity <= 0.0 {
+            return;
+        }
+
+        let delta_t = joules / heat_capacity;
+
+        //Only gas needs the atmosphere system to look at it again
+        if self.atmosphere.get_total_mols() > 0.0 {
+            self.atmosphere.update_temperature(delta_t);
+        } else {
+            self.atmosphere.temperature += delta_t;
         }
     }
 }
@@ -5,7 +5,10 @@ use specs::Entity;
 use std::collections::HashMap;
 
 pub mod pathfinding;
+pub mod pressure;
+pub mod structure;
 pub mod tile_blueprints;
+pub mod zones;
 
 #[serde_with::serde_as]
 #[derive(Default, Clone, Serialize, Deserialize)]
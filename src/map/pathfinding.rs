@@ -144,6 +144,64 @@ pub fn wall_climb_path(
     path
 }
 
+//Path for characters walking the ship, only through tiles that exist and are passable and around
+//the avoided positions. Gives up once max_nodes tiles have been searched so hopeless searches stay cheap
+pub fn find_character_path(
+    map: &Map,
+    start_position: Vector3i,
+    target: Vector3i,
+    avoid_positions: &HashSet<Vector3i>,
+    max_nodes: usize,
+) -> Option<Vec<Vector3i>> {
+    let mut open_set: BinaryHeap<Reverse<(i32, Vector3i)>> = BinaryHeap::new();
+    let mut came_from: HashMap<Vector3i, Vector3i> = HashMap::new();
+    let mut g_score: HashMap<Vector3i, i32> = HashMap::new();
+    let mut closed_set: HashSet<Vector3i> = HashSet::new();
+
+    g_score.insert(start_position, 0);
+    open_set.push(Reverse((
+        start_position.manhattan(target),
+        start_position,
+    )));
+
+    while let Some(Reverse((_, current_position))) = open_set.pop() {
+        if current_position == target {
+            return Some(reconstruct_path(&came_from, current_position));
+        }
+
+        if !closed_set.insert(current_position) {
+            continue;
+        }
+
+        if closed_set.len() > max_nodes {
+            return None;
+        }
+
+        for neighbour in get_cardinal_neighbours(current_position) {
+            if avoid_positions.contains(&neighbour) && neighbour != target {
+                continue;
+            }
+
+            match map.tiles.get(&neighbour) {
+                Some(tile) if tile.passable => {}
+                _ => continue,
+            }
+
+            let tentative_g_score = g_score[&current_position] + 1;
+
+            if !g_score.contains_key(&neighbour) || tentative_g_score < g_score[&neighbour] {
+                came_from.insert(neighbour, current_position);
+                g_score.insert(neighbour, tentative_g_score);
+                open_set.push(Reverse((
+                    tentative_g_score + neighbour.manhattan(target),
+                    neighbour,
+                )));
+            }
+        }
+    }
+    None
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 struct State {
     g_score: i32,
@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+use crate::vectors::Vector3i;
+
+//Net push on a tile from pressure differences with its neighbours, in pascal
+#[derive(Default, Clone, Copy, Debug)]
+pub struct PressureForce {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl PressureForce {
+    pub fn add(&mut self, direction: Vector3i, pressure_delta: f32) {
+        let length = (direction.x.pow(2) + direction.y.pow(2) + direction.z.pow(2)) as f32;
+        let length = length.sqrt();
+
+        if length == 0.0 {
+            return;
+        }
+
+        self.x += direction.x as f32 / length * pressure_delta;
+        self.y += direction.y as f32 / length * pressure_delta;
+        self.z += direction.z as f32 / length * pressure_delta;
+    }
+
+    pub fn horizontal_magnitude(&self) -> f32 {
+        (self.x.powi(2) + self.y.powi(2)).sqrt()
+    }
+
+    //Single tile step along the flow, ignoring the vertical part of the force
+    pub fn horizontal_step(&self) -> Vector3i {
+        let largest = self.x.abs().max(self.y.abs());
+
+        if largest == 0.0 {
+            return Vector3i::new_equi(0);
+        }
+
+        let step = |component: f32| {
+            if component.abs() >= largest * 0.5 {
+                component.signum() as i32
+            } else {
+                0
+            }
+        };
+
+        Vector3i::new(step(self.x), step(self.y), 0)
+    }
+}
+
+//Written by the atmosphere system every tick, only tiles with gas flowing out of them are present
+#[derive(Default)]
+pub struct PressureForces {
+    pub forces: HashMap<Vector3i, PressureForce>,
+}
+
+impl PressureForces {
+    pub fn new() -> Self {
+        Self {
+            forces: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, position: &Vector3i) -> Option<&PressureForce> {
+        self.forces.get(position)
+    }
+}
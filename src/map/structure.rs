@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+
+use crate::vectors::Vector3i;
+
+//What a tile is built from, decides how it stands up to each kind of damage
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Material {
+    //Open space and gas, nothing to damage
+    #[default]
+    None,
+    Steel,
+    Glass,
+    Debris,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DamageType {
+    Explosion,
+    Fire,
+    Impact,
+    Tool,
+}
+
+impl Material {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Material::None => "nothing",
+            Material::Steel => "steel",
+            Material::Glass => "glass",
+            Material::Debris => "debris",
+        }
+    }
+
+    //Multiplier on incoming damage
+    pub fn vulnerability(&self, damage_type: DamageType) -> f32 {
+        match (self, damage_type) {
+            (Material::None, _) => 0.0,
+            (Material::Steel, DamageType::Fire) => 0.1,
+            (Material::Steel, DamageType::Impact) => 0.5,
+            (Material::Steel, _) => 1.0,
+            (Material::Glass, DamageType::Fire) => 0.3,
+            (Material::Glass, DamageType::Tool) => 1.0,
+            (Material::Glass, _) => 2.0,
+            (Material::Debris, DamageType::Fire) => 0.5,
+            (Material::Debris, _) => 1.0,
+        }
+    }
+}
+
+//How much punishment a tile takes before it comes down and what it leaves behind
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct Structure {
+    pub material: Material,
+    pub integrity: f32,
+    pub max_integrity: f32,
+    //Blueprint the tile is replaced with once destroyed
+    pub destroyed_into: Option<String>,
+}
+
+impl Structure {
+    pub fn new(material: Material, max_integrity: f32, destroyed_into: Option<String>) -> Self {
+        Self {
+            material,
+            integrity: max_integrity,
+            max_integrity,
+            destroyed_into,
+        }
+    }
+
+    pub fn is_destructible(&self) -> bool {
+        self.material != Material::None && self.max_integrity > 0.0
+    }
+
+    //Returns true if this brought the tile down
+    pub fn damage(&mut self, damage_type: DamageType, amount: f32) -> bool {
+        if !self.is_destructible() || self.integrity <= 0.0 {
+            return false;
+        }
+
+        self.integrity -= amount * self.material.vulnerability(damage_type);
+        self.integrity <= 0.0
+    }
+
+    pub fn repair(&mut self, amount: f32) {
+        self.integrity = (self.integrity + amount).min(self.max_integrity);
+    }
+
+    pub fn condition(&self) -> f32 {
+        if self.max_integrity <= 0.0 {
+            return 1.0;
+        }
+
+        (self.integrity / self.max_integrity).clamp(0.0, 1.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TileDamage {
+    pub position: Vector3i,
+    pub damage_type: DamageType,
+    pub amount: f32,
+}
+
+//Damage dealt to tiles this tick, applied by the structural system
+#[derive(Default)]
+pub struct DamageQueue {
+    pub pending: Vec<TileDamage>,
+}
+
+impl DamageQueue {
+    pub fn new() -> Self {
+        Self {
+            pending: Vec::new(),
+        }
+    }
+
+    pub fn damage(&mut self, position: Vector3i, damage_type: DamageType, amount: f32) {
+        self.pending.push(TileDamage {
+            position,
+            damage_type,
+            amount,
+        });
+    }
+
+    //Blast damage falling off linearly to nothing at the edge of the radius
+    pub fn explode(&mut self, centre: Vector3i, radius: i32, amount: f32) {
+        for x in -radius..=radius {
+            for y in -radius..=radius {
+                for z in -radius..=radius {
+                    let position = centre + Vector3i::new(x, y, z);
+                    let distance = ((x * x + y * y + z * z) as f32).sqrt();
+
+                    if distance > radius as f32 {
+                        continue;
+                    }
+
+                    let falloff = 1.0 - distance / (radius as f32 + 1.0);
+                    self.damage(position, DamageType::Explosion, amount * falloff);
+                }
+            }
+        }
+    }
+}
@@ -1,78 +1,119 @@
-use crate::{entities::atmospherics::Atmosphere, graphics::char_to_glyph, Tile};
+use crate::{
+    entities::atmospherics::Atmosphere,
+    raws::{
+        parse_color, parse_glyph,
+        tile_structs::{AtmospherePreset, TileRaw, TileRaws},
+        RawError,
+    },
+    map::structure::Structure,
+    ThermalProperties, Tile,
+};
 use lazy_static::lazy_static;
-use rltk::prelude::*;
-use std::{collections::HashMap, sync::Mutex};
+use std::{collections::HashMap, fs, sync::Mutex};
+
+pub const TILES_PATH: &str = "raws/tiles.json";
 
 lazy_static! {
     static ref TILES: Mutex<HashMap<String, Tile>> = Mutex::new(HashMap::new());
 }
 
-pub fn initalise() {
-    TILES.lock().unwrap().insert(
-        "glass_hull".to_string(),
-        Tile::new(
-            false,
-            false,
-            char_to_glyph('█'),
-            char_to_glyph('█'),
-            RGB::named(rltk::SKYBLUE).to_rgba(0.5),
-            RGB::named(rltk::BLACK).to_rgba(1.0),
-            true,
-            Atmosphere::new_vacuume(),
-            "Glass hull section".to_string(),
-            true,
-        ),
-    );
+pub fn load_blueprints() -> Result<(), RawError> {
+    load_blueprints_from(TILES_PATH)
+}
 
-    TILES.lock().unwrap().insert(
-        "hull".to_string(),
-        Tile::new(
-            false,
-            true,
-            char_to_glyph('░'),
-            char_to_glyph('█'),
-            RGB::named(rltk::WHITE).to_rgba(1.0),
-            RGB::named(rltk::BLACK).to_rgba(1.0),
-            true,
-            Atmosphere::new_vacuume(),
-            "Hull section".to_string(),
-            true,
-        ),
-    );
+pub fn load_blueprints_from(path: &str) -> Result<(), RawError> {
+    let data = fs::read_to_string(path).map_err(|error| RawError::Io(path.to_string(), error))?;
+    let raws: TileRaws =
+        serde_json::from_str(&data).map_err(|error| RawError::Parse(path.to_string(), error))?;
 
-    TILES.lock().unwrap().insert(
-        "breathable_atmosphere".to_string(),
-        Tile::new(
-            true,
-            false,
-            char_to_glyph(' '),
-            char_to_glyph(' '),
-            RGB::named(rltk::WHITE).to_rgba(0.0),
-            RGB::named(rltk::BLACK).to_rgba(0.0),
-            false,
-            Atmosphere::new_stp(),
-            "Empty space".to_string(),
-            false,
-        ),
+    //Build every tile before touching the table so a bad file leaves the old blueprints intact
+    let mut tiles = HashMap::new();
+
+    for raw in raws.tiles.iter() {
+        if tiles.insert(raw.key.clone(), tile_from_raw(raw)?).is_some() {
+            return Err(RawError::DuplicateKey(raw.key.clone()));
+        }
+    }
+
+    //Destroyed tiles have to turn into something that exists
+    for tile in tiles.values() {
+        if let Some(destroyed_into) = &tile.structure.destroyed_into {
+            if !tiles.contains_key(destroyed_into) {
+                return Err(RawError::UnknownBlueprint(destroyed_into.clone()));
+            }
+        }
+    }
+
+    *TILES.lock().unwrap() = tiles;
+    Ok(())
+}
+
+fn tile_from_raw(raw: &TileRaw) -> Result<Tile, RawError> {
+    let mut atmosphere = match raw.atmosphere {
+        AtmospherePreset::Stp => Atmosphere::new_stp(),
+        AtmospherePreset::Vacuum => Atmosphere::new_vacuume(),
+    };
+
+    if let Some(temperature) = raw.temperature {
+        atmosphere.temperature = temperature;
+        atmosphere.recalculate_pressure();
+    }
+
+    if raw.thermal.mass < 0.0 || raw.thermal.conductivity < 0.0 {
+        return Err(RawError::InvalidValue(format!(
+            "{}: thermal mass and conductivity can not be negative",
+            raw.key
+        )));
+    }
+    if !(0.0..=1.0).contains(&raw.thermal.emissivity) {
+        return Err(RawError::InvalidValue(format!(
+            "{}: emissivity must be between 0 and 1",
+            raw.key
+        )));
+    }
+
+    let mut tile = Tile::new(
+        raw.passable,
+        raw.opaque,
+        parse_glyph(&raw.top_glyph)?,
+        parse_glyph(&raw.side_glyph)?,
+        parse_color(&raw.foreground)?,
+        parse_color(&raw.background)?,
+        raw.airtight,
+        atmosphere,
+        raw.name.clone(),
+        raw.visible,
     );
 
-    TILES.lock().unwrap().insert(
-        "vacuume".to_string(),
-        Tile::new(
-            true,
-            false,
-            char_to_glyph(' '),
-            char_to_glyph(' '),
-            RGB::named(rltk::WHITE).to_rgba(0.0),
-            RGB::named(rltk::BLACK).to_rgba(0.0),
-            false,
-            Atmosphere::new_vacuume(),
-            "Vacuume".to_string(),
-            false,
-        ),
+    tile.thermal = ThermalProperties::new(
+        raw.thermal.mass,
+        raw.thermal.conductivity,
+        raw.thermal.emissivity,
     );
+
+    if let Some(structure) = &raw.structure {
+        if structure.integrity <= 0.0 {
+            return Err(RawError::InvalidValue(format!(
+                "{}: integrity must be positive",
+                raw.key
+            )));
+        }
+
+        tile.structure = Structure::new(
+            structure.material,
+            structure.integrity,
+            structure.destroyed_into.clone(),
+        );
+    }
+
+    Ok(tile)
 }
 
-pub fn get_tile(tile_type: &str) -> Option<Tile> {
-    TILES.lock().unwrap().get(tile_type).cloned()
+pub fn get_tile(tile_type: &str) -> Result<Tile, RawError> {
+    TILES
+        .lock()
+        .unwrap()
+        .get(tile_type)
+        .cloned()
+        .ok_or_else(|| RawError::UnknownBlueprint(tile_type.to_string()))
 }
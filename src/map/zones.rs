@@ -0,0 +1,318 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    hash::{Hash, Hasher},
+};
+
+use specs::prelude::*;
+
+use crate::{
+    entities::atmospherics::Gas,
+    systems::event_system::side_blocks,
+    vectors::{utils::get_cardinal_neighbours_with_z, Vector3i},
+    Blocker, Direction, Map,
+};
+
+//Tiles that are not part of any zone (walls, hull) get this space id
+pub const NO_ZONE: usize = 0;
+
+//A zone whose pressure spread is within this fraction of its highest pressure is treated as one pool
+const SETTLE_PRESSURE_RATIO: f32 = 0.005;
+const VACUUM_PRESSURE: f32 = 0.001;
+
+//Airtight blockers indexed by position so flow checks do not scan every blocker
+pub struct AirtightBlockers {
+    sides_by_position: HashMap<Vector3i, Vec<Direction>>,
+    signature: u64,
+}
+
+impl AirtightBlockers {
+    pub fn new(blockers: &ReadStorage<Blocker>, positions: &ReadStorage<Vector3i>) -> Self {
+        let mut sides_by_position: HashMap<Vector3i, Vec<Direction>> = HashMap::new();
+        let mut signature: u64 = 0;
+
+        for (blocker, position) in (blockers, positions).join() {
+            if !blocker.airtight {
+                continue;
+            }
+
+            //Order independent so the signature only changes when a blocker does
+            let mut hasher = DefaultHasher::new();
+            position.hash(&mut hasher);
+            blocker.sides.hash(&mut hasher);
+            signature = signature.wrapping_add(hasher.finish());
+
+            sides_by_position
+                .entry(*position)
+                .or_default()
+                .extend(blocker.sides.iter().cloned());
+        }
+
+        Self {
+            sides_by_position,
+            signature,
+        }
+    }
+
+    pub fn is_blocked(&self, from: Vector3i, to: Vector3i) -> bool {
+        let delta = (to - from).normalize_delta();
+
+        if self.blocked_between(from, to) {
+            return true;
+        }
+
+        //Diagonal flow also passes through the two tiles beside it
+        if delta.x != 0 && delta.y != 0 {
+            let horizontal = Vector3i::new(delta.x, 0, 0);
+            let vertical = Vector3i::new(0, delta.y, 0);
+
+            return self.blocked_between(from + vertical, to)
+                || self.blocked_between(from + horizontal, to)
+                || self.blocked_between(from, from + vertical)
+                || self.blocked_between(from, from + horizontal);
+        }
+
+        false
+    }
+
+    fn blocked_between(&self, from: Vector3i, to: Vector3i) -> bool {
+        if let Some(sides) = self.sides_by_position.get(&from) {
+            if side_blocks(sides, (to - from).normalize_delta()) {
+                return true;
+            }
+        }
+
+        if let Some(sides) = self.sides_by_position.get(&to) {
+            if side_blocks(sides, (from - to).normalize_delta()) {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+pub struct AtmosphereZone {
+    pub tiles: Vec<Vector3i>,
+    pub settled: bool,
+}
+
+//Regions of open tiles bounded by airtight tiles and blockers. Settled zones are handled as a
+//single equalised pool, unsettled ones (a door just opened) fall back to per tile flow
+pub struct AtmosphereZones {
+    pub dirty: bool,
+    zones: HashMap<usize, AtmosphereZone>,
+    blocker_signature: u64,
+}
+
+impl Default for AtmosphereZones {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AtmosphereZones {
+    pub fn new() -> Self {
+        Self {
+            dirty: true,
+            zones: HashMap::new(),
+            blocker_signature: 0,
+        }
+    }
+
+    pub fn get_zone(&self, space_id: usize) -> Option<&AtmosphereZone> {
+        self.zones.get(&space_id)
+    }
+
+    pub fn zone_count(&self) -> usize {
+        self.zones.len()
+    }
+
+    pub fn is_settled(&self, space_id: usize) -> bool {
+        self.zones
+            .get(&space_id)
+            .map(|zone| zone.settled)
+            .unwrap_or(false)
+    }
+
+    pub fn unsettled_zones(&self) -> Vec<usize> {
+        self.zones
+            .iter()
+            .filter(|(_, zone)| !zone.settled)
+            .map(|(space_id, _)| *space_id)
+            .collect()
+    }
+
+    //Blockers are added and removed as doors change state, so comparing signatures covers both
+    pub fn check_blockers(&mut self, blockers: &AirtightBlockers) {
+        if blockers.signature != self.blocker_signature {
+            self.blocker_signature = blockers.signature;
+            self.dirty = true;
+        }
+    }
+
+    pub fn rebuild(&mut self, map: &mut Map, blockers: &AirtightBlockers) {
+        self.zones.clear();
+        self.dirty = false;
+
+        let mut visited: HashSet<Vector3i> = HashSet::new();
+        let mut next_id = NO_ZONE + 1;
+
+        let mut starts: Vec<Vector3i> = map
+            .tiles
+            .iter()
+            .filter(|(_, tile)| tile.passable && !tile.airtight)
+            .map(|(position, _)| *position)
+            .collect();
+
+        //Sorted so zone ids are stable between runs with the same seed
+        starts.sort();
+
+        for start in starts.into_iter() {
+            if visited.contains(&start) {
+                continue;
+            }
+
+            let mut tiles = Vec::new();
+            let mut open = vec![start];
+            visited.insert(start);
+
+            while let Some(position) = open.pop() {
+                tiles.push(position);
+
+                for neighbour in get_cardinal_neighbours_with_z(position).into_iter() {
+                    if visited.contains(&neighbour) {
+                        continue;
+                    }
+
+                    match map.tiles.get(&neighbour) {
+                        Some(tile) if tile.passable && !tile.airtight => {}
+                        _ => continue,
+                    }
+
+                    if blockers.is_blocked(position, neighbour) {
+                        continue;
+                    }
+
+                    visited.insert(neighbour);
+                    open.push(neighbour);
+                }
+            }
+
+            for position in tiles.iter() {
+                if let Some(tile) = map.tiles.get_mut(position) {
+                    tile.atmosphere.space_id = next_id;
+                }
+            }
+
+            self.zones.insert(
+                next_id,
+                AtmosphereZone {
+                    tiles,
+                    settled: false,
+                },
+            );
+            next_id += 1;
+        }
+
+        for (position, tile) in map.tiles.iter_mut() {
+            if !visited.contains(position) {
+                tile.atmosphere.space_id = NO_ZONE;
+            }
+        }
+
+        //Zones that merged rooms at different pressures start flowing tile by tile
+        let space_ids: Vec<usize> = self.zones.keys().cloned().collect();
+        for space_id in space_ids.into_iter() {
+            if !self.update_settled(map, space_id) {
+                if let Some(zone) = self.zones.get(&space_id) {
+                    for position in zone.tiles.iter() {
+                        if let Some(tile) = map.tiles.get_mut(position) {
+                            tile.atmosphere.dirty = true;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    //Marks the zone settled once its pressure has evened out, returns the new state
+    pub fn update_settled(&mut self, map: &mut Map, space_id: usize) -> bool {
+        let zone = match self.zones.get_mut(&space_id) {
+            Some(zone) => zone,
+            None => return false,
+        };
+
+        let mut min_pressure = f32::MAX;
+        let mut max_pressure: f32 = 0.0;
+
+        for position in zone.tiles.iter() {
+            if let Some(tile) = map.tiles.get(position) {
+                min_pressure = min_pressure.min(tile.atmosphere.pressure);
+                max_pressure = max_pressure.max(tile.atmosphere.pressure);
+            }
+        }
+
+        zone.settled = max_pressure < VACUUM_PRESSURE
+            || (max_pressure - min_pressure) <= max_pressure * SETTLE_PRESSURE_RATIO;
+
+        if zone.settled {
+            self.equalise(map, space_id);
+            return true;
+        }
+
+        false
+    }
+
+    //Spreads the gas and heat of a zone evenly over all of its tiles
+    pub fn equalise(&self, map: &mut Map, space_id: usize) {
+        let zone = match self.zones.get(&space_id) {
+            Some(zone) => zone,
+            None => return,
+        };
+
+        if zone.tiles.is_empty() {
+            return;
+        }
+
+        let mut total_gasses: HashMap<Gas, f32> = HashMap::new();
+        let mut total_heat_capacity = 0.0;
+        let mut weighted_temperature = 0.0;
+        let mut total_temperature = 0.0;
+
+        for position in zone.tiles.iter() {
+            if let Some(tile) = map.tiles.get(position) {
+                let heat_capacity = tile.atmosphere.get_heat_capacity();
+
+                for (gas, gas_mols) in tile.atmosphere.gasses.iter() {
+                    *total_gasses.entry(*gas).or_insert(0.0) += gas_mols;
+                }
+
+                total_heat_capacity += heat_capacity;
+                weighted_temperature += heat_capacity * tile.atmosphere.temperature;
+                total_temperature += tile.atmosphere.temperature;
+            }
+        }
+
+        let tile_count = zone.tiles.len() as f32;
+
+        //Temperature is heat capacity weighted, same as when gas is mixed in Atmosphere::update_gas
+        let temperature = if total_heat_capacity > 0.0 {
+            weighted_temperature / total_heat_capacity
+        } else {
+            total_temperature / tile_count
+        };
+
+        let gasses: HashMap<Gas, f32> = total_gasses
+            .into_iter()
+            .filter(|(_, mols)| *mols > 0.0)
+            .map(|(gas, mols)| (gas, mols / tile_count))
+            .collect();
+
+        for position in zone.tiles.iter() {
+            if let Some(tile) = map.tiles.get_mut(position) {
+                tile.atmosphere.set_gasses(&gasses, temperature);
+                tile.atmosphere.dirty = false;
+            }
+        }
+    }
+}
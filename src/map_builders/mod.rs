@@ -3,7 +3,7 @@ use common::Area;
 use small_cargo_ship::SmallCargoShipMapBuilder;
 use specs::World;
 
-use crate::vectors::Vector3i;
+use crate::{raws::RawError, vectors::Vector3i};
 
 use super::Map;
 
@@ -13,7 +13,7 @@ mod small_cargo_ship;
 
 #[allow(dead_code)]
 pub trait MapBuilder {
-    fn build_map(&mut self);
+    fn build_map(&mut self) -> Result<(), RawError>;
     fn spawn_entities(&mut self, ecs: &mut World);
     fn get_map(&mut self) -> Map;
     fn get_start_position(&mut self) -> Vector3i;
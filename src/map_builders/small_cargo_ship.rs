@@ -4,13 +4,16 @@ use std::{
 };
 
 use rltk::RGB;
-use specs::World;
+use specs::{World, WorldExt};
 
 use crate::{
+    entities::power_components::WireGauge,
     graphics::char_to_glyph,
     pathfinding::find_path_with_width,
+    raws::RawError,
     rng::{self, range},
-    spawner::{self, lay_wiring},
+    spawner::{self, lay_wiring, WireSpec},
+    tile_blueprints::get_tile,
     vectors::{utils::get_cardinal_neighbours, Vector3i},
     Map, Tile,
 };
@@ -35,11 +38,10 @@ impl SmallCargoShipMapBuilder {
         }
     }
 
-    pub fn build_corridor(&mut self, corridor: &mut Corridor) {
-        let hull =
-            crate::tile_blueprints::get_tile("hull").unwrap_or_else(|| Tile::new_empty_stp());
-        let breathable_atmosphere = crate::tile_blueprints::get_tile("breathable_atmosphere")
-            .unwrap_or_else(|| Tile::new_empty_stp());
+    pub fn build_corridor(&mut self, corridor: &mut Corridor) -> Result<(), RawError> {
+        let hull = get_tile("hull")?;
+        let breathable_atmosphere = get_tile("breathable_atmosphere")?;
+        let vacuume = get_tile("vacuume")?;
 
         let path = find_path_with_width(
             self.map.clone(),
@@ -76,7 +78,7 @@ impl SmallCargoShipMapBuilder {
                         //Add empty tile for ducts in the roof.
                         self.map.tiles.insert(
                             *position + Vector3i::UP * 3 + Vector3i::new(x, y, 0),
-                            Tile::new_vacuume(),
+                            vacuume.clone(),
                         );
 
                         corridor_tiles.insert(*position + Vector3i::new(x, y, 0));
@@ -109,17 +111,14 @@ impl SmallCargoShipMapBuilder {
                 corridor.nodes.push(*position);
             }
         }
-    }
-
-    pub fn build_room(&mut self, room: &mut Room) -> bool {
-        let hull =
-            crate::tile_blueprints::get_tile("hull").unwrap_or_else(|| Tile::new_empty_stp());
 
-        let breathable_atmosphere = crate::tile_blueprints::get_tile("breathable_atmosphere")
-            .unwrap_or_else(|| Tile::new_empty_stp());
+        Ok(())
+    }
 
-        let vacuume =
-            crate::tile_blueprints::get_tile("vacuume").unwrap_or_else(|| Tile::new_vacuume());
+    pub fn build_room(&mut self, room: &mut Room) -> Result<bool, RawError> {
+        let hull = get_tile("hull")?;
+        let breathable_atmosphere = get_tile("breathable_atmosphere")?;
+        let vacuume = get_tile("vacuume")?;
 
         //Pick side to expand into
         let neighbours = get_cardinal_neighbours(room.centre);
@@ -147,7 +146,7 @@ impl SmallCargoShipMapBuilder {
         }
 
         if open_tile == room.centre {
-            return false;
+            return Ok(false);
         }
 
         //Add open tile to the rooms doors
@@ -167,7 +166,7 @@ impl SmallCargoShipMapBuilder {
                     let current_position = room_centre + Vector3i::new(x, y, z);
 
                     if let Some(_) = self.map.tiles.get(&current_position) {
-                        return false;
+                        return Ok(false);
                     }
                 }
                 area_tiles.push(room_centre + Vector3i::new(x, y, 0));
@@ -222,7 +221,7 @@ impl SmallCargoShipMapBuilder {
         }
 
         room.centre = room_centre;
-        true
+        Ok(true)
     }
 
     pub fn populate_area(ecs: &mut World, area: &mut Box<dyn Area>) {
@@ -280,12 +279,29 @@ impl SmallCargoShipMapBuilder {
         if area.get_area_type() == AreaType::GeneratorRoom {
             let generator_position = area.get_area_position();
 
-            spawner::power_source(ecs, *generator_position, true, 1000.0);
+            spawner::fuel_generator(ecs, *generator_position, 6);
             connections.push(*generator_position);
+            entity_positions.insert(*generator_position);
+
+            //Battery bank against a wall to carry the ship through a generator failure
+            for _ in 0..10 {
+                if let Some(battery_position) = get_wall_adjacent_position(area.as_ref()) {
+                    if !entity_positions.contains(&battery_position)
+                        && nodes
+                            .iter()
+                            .all(|node| node.distance_to(battery_position) > 1.0)
+                    {
+                        spawner::battery(ecs, battery_position);
+                        connections.push(battery_position);
+                        entity_positions.insert(battery_position);
+                        break;
+                    }
+                }
+            }
         }
 
         if area.get_area_type() != AreaType::Corridor {
-            // Doors
+            // Doors, wired to the breaker so they can be worked from a terminal
             for node in area.get_nodes().iter() {
                 spawner::door(
                     ecs,
@@ -295,6 +311,70 @@ impl SmallCargoShipMapBuilder {
                     char_to_glyph('/'),
                     char_to_glyph('+'),
                 );
+                connections.push(*node);
+            }
+
+            // Terminals on the bridge and in engineering to run the ship from
+            if area.get_area_type() == AreaType::Cockpit
+                || area.get_area_type() == AreaType::GeneratorRoom
+            {
+                for _ in 0..10 {
+                    if let Some(terminal_position) = get_wall_adjacent_position(area.as_ref()) {
+                        if !entity_positions.contains(&terminal_position)
+                            && nodes
+                                .iter()
+                                .all(|node| node.distance_to(terminal_position) > 1.0)
+                        {
+                            spawner::terminal(ecs, terminal_position);
+                            connections.push(terminal_position);
+                            entity_positions.insert(terminal_position);
+                            break;
+                        }
+                    }
+                }
+            }
+
+            // Sensors in every room and a bench of gates on the bridge, left unlinked for the crew to
+            // set up
+            let mut logic_devices = vec!["pressure_sensor", "motion_sensor"];
+            if area.get_area_type() == AreaType::GeneratorRoom {
+                logic_devices.push("temperature_sensor");
+            }
+            if area.get_area_type() == AreaType::Cockpit {
+                logic_devices.extend(["relay", "and_gate", "or_gate", "not_gate", "delay_timer"]);
+            }
+
+            for key in logic_devices {
+                for _ in 0..10 {
+                    if let Some(device_position) = get_wall_adjacent_position(area.as_ref()) {
+                        if !entity_positions.contains(&device_position)
+                            && nodes
+                                .iter()
+                                .all(|node| node.distance_to(device_position) > 1.0)
+                        {
+                            spawner::logic_device(ecs, key, device_position);
+                            connections.push(device_position);
+                            entity_positions.insert(device_position);
+                            break;
+                        }
+                    }
+                }
+            }
+
+            // Heater to keep the room warm, wired up with the rest of the devices
+            for _ in 0..10 {
+                if let Some(heater_position) = get_wall_adjacent_position(area.as_ref()) {
+                    if !entity_positions.contains(&heater_position)
+                        && nodes
+                            .iter()
+                            .all(|node| node.distance_to(heater_position) > 1.0)
+                    {
+                        spawner::heater(ecs, heater_position, ROOM_TEMPERATURE, true);
+                        connections.push(heater_position);
+                        entity_positions.insert(heater_position);
+                        break;
+                    }
+                }
             }
 
             // Add storage cabinets
@@ -314,8 +394,15 @@ impl SmallCargoShipMapBuilder {
                             placed = true;
                             let cabinet = spawner::storage_cabinet(ecs, cabinet_position);
                             let item = spawner::test_item(ecs, Vector3i::new_equi(0));
-
-                            spawner::put_item_in_container(ecs, item, cabinet);
+                            let plating = spawner::steel_plating(ecs, Vector3i::new_equi(0));
+                            let canister = spawner::fuel_canister(ecs, Vector3i::new_equi(0));
+
+                            //Anything the cabinet has no room for is left out
+                            for stock in [item, plating, canister] {
+                                if !spawner::put_item_in_container(ecs, stock, cabinet) {
+                                    let _ = ecs.delete_entity(stock);
+                                }
+                            }
                             entity_positions.insert(cabinet_position);
                         }
                     }
@@ -330,9 +417,11 @@ impl SmallCargoShipMapBuilder {
 }
 
 const MIN_AREA_SIZE: i32 = 5;
+//Thermostat setting for room heaters
+const ROOM_TEMPERATURE: f32 = 293.15;
 
 impl MapBuilder for SmallCargoShipMapBuilder {
-    fn build_map(&mut self) {
+    fn build_map(&mut self) -> Result<(), RawError> {
         let mut back_bone = Corridor::new(
             self.start_position,
             self.start_position + Vector3i::new(40, 0, 0),
@@ -341,7 +430,7 @@ impl MapBuilder for SmallCargoShipMapBuilder {
             AreaType::Corridor,
             true,
         );
-        self.build_corridor(&mut back_bone);
+        self.build_corridor(&mut back_bone)?;
 
         let mut open_nodes: Vec<Vector3i> = Vec::new();
 
@@ -360,7 +449,7 @@ impl MapBuilder for SmallCargoShipMapBuilder {
             true,
         );
 
-        self.build_room(&mut stern_room);
+        self.build_room(&mut stern_room)?;
 
         self.areas.push(Box::new(stern_room.clone()));
 
@@ -374,7 +463,7 @@ impl MapBuilder for SmallCargoShipMapBuilder {
             true,
         );
 
-        self.build_room(&mut aft_room);
+        self.build_room(&mut aft_room)?;
 
         self.areas.push(Box::new(aft_room.clone()));
 
@@ -406,7 +495,7 @@ impl MapBuilder for SmallCargoShipMapBuilder {
                     AreaType::GenericRoom,
                     true,
                 );
-                if self.build_room(&mut room) && self.build_room(&mut mirrored_room) {
+                if self.build_room(&mut room)? && self.build_room(&mut mirrored_room)? {
                     self.areas.push(Box::new(room));
                     self.areas.push(Box::new(mirrored_room));
                     break;
@@ -415,6 +504,8 @@ impl MapBuilder for SmallCargoShipMapBuilder {
                 }
             }
         }
+
+        Ok(())
     }
 
     fn spawn_entities(&mut self, ecs: &mut specs::World) {
@@ -480,6 +571,20 @@ impl MapBuilder for SmallCargoShipMapBuilder {
                 .insert(area_position.0 + Vector3i::DOWN, Tile::new_empty_stp());
         }
 
+        let trunk = WireSpec::new(
+            RGB::named(rltk::RED).to_rgba(1.0),
+            "RED".to_string(),
+            false,
+            WireGauge::Heavy,
+        );
+        //Signal backbone alongside the trunk, every breaker box is a junction on it
+        let backbone = WireSpec::new(
+            RGB::named(rltk::CYAN).to_rgba(1.0),
+            "CYAN".to_string(),
+            true,
+            WireGauge::Signal,
+        );
+
         for position in breaker_positions.iter() {
             let color_hex = format!(
                 "#{:X}{:X}{:X}",
@@ -495,11 +600,26 @@ impl MapBuilder for SmallCargoShipMapBuilder {
                 generator_breaker.clone(),
                 *position,
                 &breaker_positions,
-                RGB::named(rltk::RED).to_rgba(1.0),
-                "RED".to_string(),
+                &trunk,
                 true,
-                false,
             );
+            lay_wiring(
+                ecs,
+                self.get_map(),
+                generator_breaker,
+                *position,
+                &breaker_positions,
+                &backbone,
+                true,
+            );
+
+            //The generator room feeds the whole ship through its own breaker
+            let gauge = if *position == generator_breaker {
+                WireGauge::Heavy
+            } else {
+                WireGauge::Standard
+            };
+            let circuit = WireSpec::new(color.to_rgba(1.0), color_hex, true, gauge);
 
             for (breaker_position, device_position) in device_positions
                 .iter()
@@ -511,13 +631,24 @@ impl MapBuilder for SmallCargoShipMapBuilder {
                     *device_position,
                     *breaker_position,
                     &breaker_positions,
-                    color.to_rgba(1.0),
-                    color_hex.clone(),
-                    true,
+                    &circuit,
                     true,
                 );
             }
         }
+
+        //Crew on duty in the cockpit and engineering
+        for area in self.get_areas().iter() {
+            match area.get_area_type() {
+                AreaType::Cockpit => {
+                    spawner::crew(ecs, *area.get_area_position());
+                }
+                AreaType::GeneratorRoom => {
+                    spawner::crew(ecs, *area.get_area_position() + Vector3i::E);
+                }
+                _ => {}
+            }
+        }
     }
 
     fn get_map(&mut self) -> Map {
@@ -1,16 +1,22 @@
+use std::collections::HashMap;
+
 use rltk::prelude::*;
 use specs::{prelude::*, storage::GenericReadStorage};
 
 use crate::{
-    entities::intents::{DropIntent, InteractIntent, OpenIntent, PickUpIntent},
+    entities::{
+        construction::{Construction, ConstructionMaterial},
+        intents::{BuildIntent, DeconstructIntent},
+    },
+    map::structure::Material,
     gui::{interact_gui, MainMenuResult, MainMenuSelection},
     save_load_system,
     systems::event_system::{
-        get_default_interactions, get_entity_interactions, InteractionInformation, InteractionType,
+        get_default_interactions, get_entity_interactions, InteractionInformation,
     },
     vectors::Vector3i,
-    InContainer, Name, Renderable, RunState, State, INTERACT_MENU_WIDTH, MAP_SCREEN_WIDTH,
-    TERMINAL_HEIGHT,
+    Container, InContainer, Map, Name, Renderable, RunState, State, INTERACT_MENU_WIDTH,
+    MAP_SCREEN_WIDTH, TERMINAL_HEIGHT,
 };
 
 pub fn main_menu(game_state: &mut State, ctx: &mut Rltk) -> MainMenuResult {
@@ -336,51 +342,7 @@ pub fn show_inventory(
                 if key >= VirtualKeyCode::A && key <= VirtualKeyCode::Z && ctx.shift {
                     let selection = letter_to_option(key);
                     if let Some(interactable) = interactables.get(selection as usize) {
-                        let entity = entities.entity(interactable.entity_id);
-                        match interactable.interaction_type {
-                            InteractionType::Component => {
-                                let mut interactions =
-                                    game_state.ecs.write_storage::<InteractIntent>();
-                                let _ = interactions.insert(
-                                    player,
-                                    InteractIntent::new(
-                                        player,
-                                        entity,
-                                        interactable.id,
-                                        interactable.description.clone(),
-                                        interactable.cost,
-                                    ),
-                                );
-                            }
-                            InteractionType::PickUp => {
-                                let mut pick_up_intents =
-                                    game_state.ecs.write_storage::<PickUpIntent>();
-                                let _ = pick_up_intents.insert(
-                                    player,
-                                    PickUpIntent::new(player, entity, interactable.cost),
-                                );
-                            }
-                            InteractionType::Open => {
-                                let mut open_intents = game_state.ecs.write_storage::<OpenIntent>();
-                                let _ = open_intents.insert(
-                                    player,
-                                    OpenIntent::new(
-                                        player,
-                                        entity,
-                                        interactable.id,
-                                        interactable.description.clone(),
-                                        interactable.cost,
-                                    ),
-                                );
-                            }
-                            InteractionType::Drop => {
-                                let mut open_intents = game_state.ecs.write_storage::<DropIntent>();
-                                let _ = open_intents.insert(
-                                    player,
-                                    DropIntent::new(player, entity, interactable.cost),
-                                );
-                            }
-                        }
+                        interactable.queue(&game_state.ecs, player);
                         return (ItemMenuResult::Action, None);
                     }
                     (ItemMenuResult::NoResponse, None)
@@ -420,3 +382,160 @@ pub fn show_inventory(
         },
     }
 }
+
+//Picks a neighbouring tile and then what to build on it or whether to take it apart
+pub fn show_construction(
+    game_state: &mut State,
+    ctx: &mut Rltk,
+    target: Option<Vector3i>,
+) -> RunState {
+    let player = *game_state.ecs.fetch::<Entity>();
+    let player_position = *game_state.ecs.fetch::<Vector3i>();
+
+    let target = match target {
+        Some(target) => target,
+        None => {
+            ctx.draw_box(15, 23, 31, 3, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK));
+            ctx.print_color(
+                18,
+                23,
+                RGB::named(rltk::YELLOW),
+                RGB::named(rltk::BLACK),
+                "Construct",
+            );
+            ctx.print(17, 24, "Which direction?");
+            ctx.print_color(
+                18,
+                26,
+                RGB::named(rltk::YELLOW),
+                RGB::named(rltk::BLACK),
+                "ESCAPE to cancel",
+            );
+
+            let delta = match ctx.key {
+                None => return RunState::ConstructGUI { target: None },
+                Some(key) => match key {
+                    VirtualKeyCode::Escape => return RunState::AwaitingInput,
+                    VirtualKeyCode::Period => Vector3i::DOWN,
+                    VirtualKeyCode::Comma => Vector3i::UP,
+                    VirtualKeyCode::Up | VirtualKeyCode::Numpad8 => Vector3i::N,
+                    VirtualKeyCode::Numpad9 => Vector3i::NE,
+                    VirtualKeyCode::Right | VirtualKeyCode::Numpad6 => Vector3i::E,
+                    VirtualKeyCode::Numpad3 => Vector3i::SE,
+                    VirtualKeyCode::Down | VirtualKeyCode::Numpad2 => Vector3i::S,
+                    VirtualKeyCode::Numpad1 => Vector3i::SW,
+                    VirtualKeyCode::Left | VirtualKeyCode::Numpad4 => Vector3i::W,
+                    VirtualKeyCode::Numpad7 => Vector3i::NW,
+                    _ => return RunState::ConstructGUI { target: None },
+                },
+            };
+
+            return RunState::ConstructGUI {
+                target: Some(player_position + delta),
+            };
+        }
+    };
+
+    let tile_name = game_state
+        .ecs
+        .fetch::<Map>()
+        .tiles
+        .get(&target)
+        .map_or("nothing".to_string(), |tile| tile.name.to_lowercase());
+
+    //Count what the player has to build with
+    let mut carried: HashMap<Material, usize> = HashMap::new();
+    {
+        let containers = game_state.ecs.read_storage::<Container>();
+        let in_container = game_state.ecs.read_storage::<InContainer>();
+        let construction_materials = game_state.ecs.read_storage::<ConstructionMaterial>();
+
+        if let Some(container) = containers.get(player) {
+            for (construction_material, _) in (&construction_materials, &in_container)
+                .join()
+                .filter(|(_, in_container)| in_container.owner == container.id)
+            {
+                *carried.entry(construction_material.material).or_default() += 1;
+            }
+        }
+    }
+
+    let options = Construction::ALL.len() + 1;
+    let mut y = 23;
+    ctx.draw_box(
+        15,
+        y - 2,
+        41,
+        (options + 4) as i32,
+        RGB::named(rltk::WHITE),
+        RGB::named(rltk::BLACK),
+    );
+    ctx.print_color(
+        18,
+        y - 2,
+        RGB::named(rltk::YELLOW),
+        RGB::named(rltk::BLACK),
+        "Construct",
+    );
+    ctx.print(17, y - 1, format!("Target: {}", tile_name));
+    y += 1;
+
+    for (index, construction) in Construction::ALL.iter().enumerate() {
+        ctx.print(
+            17,
+            y,
+            format!(
+                "({}) {} [{}: {}]",
+                to_char(97 + index as u8),
+                construction.name(),
+                construction.material().name(),
+                carried.get(&construction.material()).copied().unwrap_or(0)
+            ),
+        );
+        y += 1;
+    }
+    ctx.print(
+        17,
+        y,
+        format!("({}) Deconstruct", to_char(97 + Construction::ALL.len() as u8)),
+    );
+    ctx.print_color(
+        18,
+        y + 2,
+        RGB::named(rltk::YELLOW),
+        RGB::named(rltk::BLACK),
+        "ESCAPE to cancel",
+    );
+
+    match ctx.key {
+        None => RunState::ConstructGUI {
+            target: Some(target),
+        },
+        Some(VirtualKeyCode::Escape) => RunState::AwaitingInput,
+        Some(key) => {
+            let selection = rltk::letter_to_option(key);
+
+            if selection < 0 || selection as usize >= options {
+                return RunState::ConstructGUI {
+                    target: Some(target),
+                };
+            }
+
+            match Construction::ALL.get(selection as usize) {
+                Some(construction) => {
+                    let _ = game_state.ecs.write_storage::<BuildIntent>().insert(
+                        player,
+                        BuildIntent::new(player, target, *construction),
+                    );
+                }
+                None => {
+                    let _ = game_state
+                        .ecs
+                        .write_storage::<DeconstructIntent>()
+                        .insert(player, DeconstructIntent::new(player, target));
+                }
+            }
+            RunState::Ticking
+        }
+    }
+}
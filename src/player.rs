@@ -1,9 +1,8 @@
 use std::usize;
 
-use crate::entities::biology::Breather;
-use crate::entities::intents::{InteractIntent, MoveIntent};
+use crate::entities::intents::{InteractIntent, Interrupt, MoveIntent};
 use crate::graphics::get_viewport_position;
-use crate::systems::event_system::InteractionInformation;
+use crate::systems::event_system::{continue_actions, InteractionInformation};
 use crate::{
     gamelog::GameLog, vectors::Vector3i, Illuminant, Photometry, RunState, State, Viewshed,
 };
@@ -76,14 +75,11 @@ pub fn player_input(game_state: &mut State, ctx: &mut Rltk) -> RunState {
                 return skip_turn(game_log);
             }
 
-            VirtualKeyCode::B => {
-                let mut breathers = game_state.ecs.write_storage::<Breather>();
-                let players = game_state.ecs.read_storage::<Player>();
+            //Continue an interrupted action
+            VirtualKeyCode::C => return continue_last_action(&mut game_state.ecs),
 
-                for (_player, breather) in (&players, &mut breathers).join() {
-                    breather.trigger_breath = true;
-                }
-            }
+            //Build or take apart a neighbouring tile
+            VirtualKeyCode::B => return RunState::ConstructGUI { target: None },
 
             //Look gui
             VirtualKeyCode::K => {
@@ -174,6 +170,25 @@ fn skip_turn(mut game_log: specs::shred::FetchMut<GameLog>) -> RunState {
     RunState::Ticking
 }
 
+pub fn continue_last_action(ecs: &mut World) -> RunState {
+    let player = *ecs.fetch::<Entity>();
+
+    if continue_actions(ecs, player) {
+        RunState::Ticking
+    } else {
+        RunState::AwaitingInput
+    }
+}
+
+//Stops whatever long action the player is busy with at the start of the next tick
+pub fn interrupt_player(ecs: &mut World, reason: &str) {
+    let player = *ecs.fetch::<Entity>();
+
+    ecs.write_storage::<Interrupt>()
+        .insert(player, Interrupt::new(reason.to_string()))
+        .expect("Player interrupt error");
+}
+
 pub fn toggle_power_overlay(ecs: &mut World) {
     let mut players = ecs.write_storage::<Player>();
     let player_positions = ecs.read_storage::<Vector3i>();
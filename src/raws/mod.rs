@@ -0,0 +1,90 @@
+use std::{fmt::Display, fs, sync::Mutex};
+
+use lazy_static::lazy_static;
+use rltk::{RGB, RGBA};
+use specs::{Entity, World};
+
+use crate::{graphics::char_to_glyph, vectors::Vector3i};
+
+mod rawmaster;
+pub mod spawn_structs;
+pub mod tile_structs;
+
+pub use rawmaster::RawMaster;
+
+pub const SPAWNS_PATH: &str = "raws/spawns.json";
+
+lazy_static! {
+    pub static ref RAWS: Mutex<RawMaster> = Mutex::new(RawMaster::empty());
+}
+
+#[derive(Debug)]
+pub enum RawError {
+    Io(String, std::io::Error),
+    Parse(String, serde_json::Error),
+    DuplicateKey(String),
+    UnknownTemplate(String),
+    UnknownBlueprint(String),
+    InvalidGlyph(String),
+    InvalidColor(String),
+    InvalidValue(String),
+}
+
+impl Display for RawError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RawError::Io(path, error) => write!(f, "Unable to read {}: {}", path, error),
+            RawError::Parse(path, error) => write!(f, "Unable to parse {}: {}", path, error),
+            RawError::DuplicateKey(key) => write!(f, "Duplicate raw key: {}", key),
+            RawError::UnknownTemplate(key) => write!(f, "No raw template named {}", key),
+            RawError::UnknownBlueprint(key) => write!(f, "No tile blueprint named {}", key),
+            RawError::InvalidGlyph(glyph) => write!(f, "Invalid glyph: \"{}\"", glyph),
+            RawError::InvalidColor(color) => write!(f, "Invalid color: \"{}\"", color),
+            RawError::InvalidValue(description) => write!(f, "Invalid value: {}", description),
+        }
+    }
+}
+
+impl std::error::Error for RawError {}
+
+pub fn load_raws() -> Result<(), RawError> {
+    load_raws_from(SPAWNS_PATH)
+}
+
+pub fn load_raws_from(path: &str) -> Result<(), RawError> {
+    let data = fs::read_to_string(path).map_err(|error| RawError::Io(path.to_string(), error))?;
+    let raws =
+        serde_json::from_str(&data).map_err(|error| RawError::Parse(path.to_string(), error))?;
+
+    RAWS.lock().unwrap().load(raws)
+}
+
+pub fn spawn_named(ecs: &mut World, key: &str, position: Vector3i) -> Result<Entity, RawError> {
+    RAWS.lock().unwrap().spawn_named_entity(ecs, key, position)
+}
+
+//Glyphs are given as a single character
+pub fn parse_glyph(glyph: &str) -> Result<u16, RawError> {
+    let mut chars = glyph.chars();
+
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(char_to_glyph(c)),
+        _ => Err(RawError::InvalidGlyph(glyph.to_string())),
+    }
+}
+
+//Colors are given as #RRGGBB or #RRGGBBAA, alpha defaults to opaque
+pub fn parse_color(color: &str) -> Result<RGBA, RawError> {
+    match color.len() {
+        7 => RGB::from_hex(color)
+            .map(|rgb| rgb.to_rgba(1.0))
+            .map_err(|_| RawError::InvalidColor(color.to_string())),
+        9 => RGBA::from_hex(color).map_err(|_| RawError::InvalidColor(color.to_string())),
+        _ => Err(RawError::InvalidColor(color.to_string())),
+    }
+}
+
+//Serde default for flags that are on unless a raw says otherwise
+fn default_true() -> bool {
+    true
+}
@@ -0,0 +1,284 @@
+use std::collections::HashMap;
+
+use specs::{
+    prelude::*,
+    saveload::{MarkedBuilder, SimpleMarker},
+};
+
+use crate::{
+    entities::{
+        biology::{Breather, Thermoregulation, Vitals},
+        construction::ConstructionMaterial,
+        crew::CrewAi,
+        fire::{Fire, Flammable},
+        intents::Initiative,
+        logic::{DelayTimer, LogicGate, Sensor, Signal},
+        network::Terminal,
+        power_components::{Battery, ControlPanel, ElectronicHeater, Fuel, Generator},
+        props::Cabinet,
+    },
+    vectors::Vector3i,
+    Blocker, Container, Health, Illuminant, Installed, Item, Name, Photometry, Player, PowerNode,
+    PowerSource, PowerSwitch, PoweredState, Prop, Renderable, SerializeThis, Viewshed,
+    VisionBlocker,
+};
+
+use super::{parse_color, parse_glyph, spawn_structs::SpawnRaws, RawError};
+
+pub struct RawMaster {
+    raws: SpawnRaws,
+    entity_index: HashMap<String, usize>,
+}
+
+impl RawMaster {
+    pub fn empty() -> Self {
+        Self {
+            raws: SpawnRaws {
+                entities: Vec::new(),
+            },
+            entity_index: HashMap::new(),
+        }
+    }
+
+    pub fn load(&mut self, raws: SpawnRaws) -> Result<(), RawError> {
+        let mut entity_index = HashMap::new();
+
+        for (index, entity) in raws.entities.iter().enumerate() {
+            if entity_index.insert(entity.key.clone(), index).is_some() {
+                return Err(RawError::DuplicateKey(entity.key.clone()));
+            }
+        }
+
+        self.raws = raws;
+        self.entity_index = entity_index;
+        Ok(())
+    }
+
+    pub fn spawn_named_entity(
+        &self,
+        ecs: &mut World,
+        key: &str,
+        position: Vector3i,
+    ) -> Result<Entity, RawError> {
+        let template = match self.entity_index.get(key) {
+            Some(index) => &self.raws.entities[*index],
+            None => return Err(RawError::UnknownTemplate(key.to_string())),
+        };
+
+        //Parse everything up front so a bad template never leaves half an entity behind
+        let renderable = match &template.renderable {
+            Some(renderable) => Some(Renderable::new(
+                parse_glyph(&renderable.top_glyph)?,
+                parse_glyph(&renderable.side_glyph)?,
+                parse_color(&renderable.foreground)?,
+                parse_color(&renderable.background)?,
+                renderable.visible,
+            )),
+            None => None,
+        };
+
+        let illuminant = match &template.illuminant {
+            Some(illuminant) => Some(Illuminant::new(
+                illuminant.intensity,
+                illuminant.range,
+                parse_color(&illuminant.color)?,
+                illuminant.beam_angle,
+                illuminant.on,
+            )),
+            None => None,
+        };
+
+        let breather = match template.breather.as_deref() {
+            Some("humanlike") => Some(Breather::new_humanlike()),
+            Some(other) => {
+                return Err(RawError::InvalidValue(format!(
+                    "{}: unknown breather type {}",
+                    key, other
+                )))
+            }
+            None => None,
+        };
+
+        let thermoregulation = match template.thermoregulation.as_deref() {
+            Some("humanlike") => Some(Thermoregulation::new_humanlike()),
+            Some(other) => {
+                return Err(RawError::InvalidValue(format!(
+                    "{}: unknown thermoregulation type {}",
+                    key, other
+                )))
+            }
+            None => None,
+        };
+
+        if template.battery.is_some() && template.power_source.is_none() {
+            return Err(RawError::InvalidValue(format!(
+                "{}: a battery needs a power source to discharge through",
+                key
+            )));
+        }
+
+        if template.generator.is_some() && template.power_source.is_none() {
+            return Err(RawError::InvalidValue(format!(
+                "{}: a generator needs a power source to feed",
+                key
+            )));
+        }
+
+        if (template.sensor.is_some()
+            || template.logic_gate.is_some()
+            || template.delay_timer.is_some())
+            && template.signal.is_none()
+        {
+            return Err(RawError::InvalidValue(format!(
+                "{}: a sensor, gate or timer needs a signal output",
+                key
+            )));
+        }
+
+        if matches!(&template.generator, Some(generator) if !(generator.efficiency > 0.0 && generator.efficiency <= 1.0))
+        {
+            return Err(RawError::InvalidValue(format!(
+                "{}: generator efficiency has to be above 0 and at most 1",
+                key
+            )));
+        }
+
+        if matches!(&template.control_panel, Some(control_panel) if control_panel.rated_wattage <= 0.0)
+        {
+            return Err(RawError::InvalidValue(format!(
+                "{}: a control panel needs a positive rating",
+                key
+            )));
+        }
+
+        let mut builder = ecs.create_entity().with(position);
+
+        if let Some(name) = &template.name {
+            builder = builder.with(Name::new(name.clone()));
+        }
+        if template.player.is_some() {
+            builder = builder.with(Player::new());
+        }
+        if let Some(renderable) = renderable {
+            builder = builder.with(renderable);
+        }
+        if let Some(viewshed) = &template.viewshed {
+            builder = builder.with(Viewshed::new(
+                viewshed.range,
+                viewshed.z_range,
+                viewshed.darkvision,
+            ));
+        }
+        if template.photometry.is_some() {
+            builder = builder.with(Photometry::new());
+        }
+        if let Some(illuminant) = illuminant {
+            builder = builder.with(illuminant);
+        }
+        if let Some(power) = &template.power {
+            builder = builder.with(PoweredState::new(power.on, power.wattage));
+        }
+        if let Some(power_switch) = &template.power_switch {
+            builder = builder.with(PowerSwitch::new(power_switch.on));
+        }
+        if template.power_node.is_some() {
+            builder = builder.with(PowerNode::new());
+        }
+        if let Some(power_source) = &template.power_source {
+            builder = builder.with(PowerSource::new(power_source.on, power_source.max_wattage));
+        }
+        if let Some(battery) = &template.battery {
+            builder = builder.with(Battery::new(
+                battery.capacity,
+                battery.charge,
+                battery.max_charge_rate,
+            ));
+        }
+        if let Some(generator) = &template.generator {
+            builder = builder.with(Generator::new(
+                generator.fuel,
+                generator.max_output,
+                generator.efficiency,
+                generator.throttle,
+            ));
+        }
+        if let Some(fuel) = &template.fuel {
+            builder = builder.with(Fuel::new(fuel.energy));
+        }
+        if let Some(control_panel) = &template.control_panel {
+            builder = builder.with(ControlPanel::new(control_panel.rated_wattage));
+        }
+        if template.terminal.is_some() {
+            builder = builder.with(Terminal::new());
+        }
+        if template.signal.is_some() {
+            builder = builder.with(Signal::new(None));
+        }
+        if let Some(sensor) = &template.sensor {
+            builder = builder.with(Sensor::new(sensor.kind, sensor.threshold, sensor.below));
+        }
+        if let Some(logic_gate) = &template.logic_gate {
+            builder = builder.with(LogicGate::new(logic_gate.kind));
+        }
+        if let Some(delay_timer) = &template.delay_timer {
+            builder = builder.with(DelayTimer::new(delay_timer.delay));
+        }
+        if let Some(heater) = &template.heater {
+            builder = builder.with(ElectronicHeater::new(heater.target_temperature, heater.on));
+        }
+        if let Some(blocker) = &template.blocker {
+            builder = builder.with(match &blocker.sides {
+                Some(sides) => Blocker::new(sides.clone(), blocker.airtight),
+                None => Blocker::new_all_sides(blocker.airtight),
+            });
+        }
+        if let Some(vision_blocker) = &template.vision_blocker {
+            builder = builder.with(match &vision_blocker.sides {
+                Some(sides) => VisionBlocker::new(sides.clone()),
+                None => VisionBlocker::new_all_sides(),
+            });
+        }
+        //Anything that breathes has blood gasses to keep track of
+        if let Some(breather) = breather {
+            builder = builder.with(breather).with(Vitals::new());
+        }
+        if let Some(thermoregulation) = thermoregulation {
+            builder = builder.with(thermoregulation);
+        }
+        if let Some(initiative) = &template.initiative {
+            builder = builder.with(Initiative::new(initiative.current));
+        }
+        if let Some(container) = &template.container {
+            builder = builder.with(Container::new(container.volume));
+        }
+        if let Some(item) = &template.item {
+            builder = builder.with(Item::new(item.volume, item.weight));
+        }
+        if let Some(material) = template.construction_material {
+            builder = builder.with(ConstructionMaterial::new(material));
+        }
+        if template.cabinet.is_some() {
+            builder = builder.with(Cabinet::new());
+        }
+        if template.prop.is_some() {
+            builder = builder.with(Prop::new());
+        }
+        if template.installed.is_some() {
+            builder = builder.with(Installed::new());
+        }
+        if let Some(flammable) = &template.flammable {
+            builder = builder.with(Flammable::new(flammable.fuel, flammable.ignition_temperature));
+        }
+        if template.fire.is_some() {
+            builder = builder.with(Fire::new());
+        }
+        if let Some(health) = &template.health {
+            builder = builder.with(Health::new(health.max));
+        }
+        if template.crew_ai.is_some() {
+            builder = builder.with(CrewAi::new());
+        }
+
+        Ok(builder.marked::<SimpleMarker<SerializeThis>>().build())
+    }
+}
@@ -0,0 +1,225 @@
+use serde::Deserialize;
+
+use crate::{
+    entities::{
+        logic::{GateKind, SensorKind},
+        power_components::GeneratorFuel,
+    },
+    map::structure::Material,
+    Direction,
+};
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SpawnRaws {
+    pub entities: Vec<EntityRaw>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct EntityRaw {
+    pub key: String,
+    pub name: Option<String>,
+    pub player: Option<PlayerRaw>,
+    pub renderable: Option<RenderableRaw>,
+    pub viewshed: Option<ViewshedRaw>,
+    pub photometry: Option<PhotometryRaw>,
+    pub illuminant: Option<IlluminantRaw>,
+    pub power: Option<PowerRaw>,
+    pub power_switch: Option<PowerSwitchRaw>,
+    pub power_node: Option<PowerNodeRaw>,
+    pub power_source: Option<PowerSourceRaw>,
+    pub battery: Option<BatteryRaw>,
+    pub generator: Option<GeneratorRaw>,
+    pub fuel: Option<FuelRaw>,
+    pub control_panel: Option<ControlPanelRaw>,
+    pub heater: Option<HeaterRaw>,
+    pub terminal: Option<TerminalRaw>,
+    pub signal: Option<SignalRaw>,
+    pub sensor: Option<SensorRaw>,
+    pub logic_gate: Option<LogicGateRaw>,
+    pub delay_timer: Option<DelayTimerRaw>,
+    pub blocker: Option<BlockerRaw>,
+    pub vision_blocker: Option<VisionBlockerRaw>,
+    pub breather: Option<String>,
+    pub thermoregulation: Option<String>,
+    pub initiative: Option<InitiativeRaw>,
+    pub container: Option<ContainerRaw>,
+    pub item: Option<ItemRaw>,
+    pub construction_material: Option<Material>,
+    pub cabinet: Option<CabinetRaw>,
+    pub prop: Option<PropRaw>,
+    pub installed: Option<InstalledRaw>,
+    pub flammable: Option<FlammableRaw>,
+    pub fire: Option<FireRaw>,
+    pub health: Option<HealthRaw>,
+    pub crew_ai: Option<CrewAiRaw>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct PlayerRaw {}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct RenderableRaw {
+    pub top_glyph: String,
+    pub side_glyph: String,
+    pub foreground: String,
+    pub background: String,
+    #[serde(default = "super::default_true")]
+    pub visible: bool,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ViewshedRaw {
+    pub range: usize,
+    pub z_range: usize,
+    pub darkvision: f32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct PhotometryRaw {}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct IlluminantRaw {
+    pub intensity: f32,
+    pub range: usize,
+    pub color: String,
+    pub beam_angle: f32,
+    pub on: bool,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct PowerRaw {
+    pub on: bool,
+    pub wattage: f32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct PowerSwitchRaw {
+    pub on: bool,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct PowerNodeRaw {}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct PowerSourceRaw {
+    pub on: bool,
+    pub max_wattage: f32,
+}
+
+//Discharges through the entity's power source, so that sets the maximum output
+#[derive(Deserialize, Debug, Clone)]
+pub struct BatteryRaw {
+    pub capacity: f32,
+    pub charge: f32,
+    pub max_charge_rate: f32,
+}
+
+//Drives the entity's power source, max_output in watts
+#[derive(Deserialize, Debug, Clone)]
+pub struct GeneratorRaw {
+    pub fuel: GeneratorFuel,
+    pub max_output: f32,
+    pub efficiency: f32,
+    #[serde(default = "default_throttle")]
+    pub throttle: f32,
+}
+
+fn default_throttle() -> f32 {
+    1.0
+}
+
+//Joules released when burnt in a generator
+#[derive(Deserialize, Debug, Clone)]
+pub struct FuelRaw {
+    pub energy: f32,
+}
+
+//Rating of the breaker in watts
+#[derive(Deserialize, Debug, Clone)]
+pub struct ControlPanelRaw {
+    pub rated_wattage: f32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct HeaterRaw {
+    pub target_temperature: f32,
+    pub on: bool,
+}
+
+//If no sides are given the blocker covers all sides
+#[derive(Deserialize, Debug, Clone)]
+pub struct BlockerRaw {
+    pub sides: Option<Vec<Direction>>,
+    pub airtight: bool,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct VisionBlockerRaw {
+    pub sides: Option<Vec<Direction>>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct InitiativeRaw {
+    pub current: f32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ContainerRaw {
+    pub volume: f32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ItemRaw {
+    pub volume: f32,
+    pub weight: f32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct CabinetRaw {}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct TerminalRaw {}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SignalRaw {}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SensorRaw {
+    pub kind: SensorKind,
+    pub threshold: f32,
+    #[serde(default)]
+    pub below: bool,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct LogicGateRaw {
+    pub kind: GateKind,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct DelayTimerRaw {
+    pub delay: u32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct PropRaw {}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct InstalledRaw {}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct FlammableRaw {
+    pub fuel: f32,
+    pub ignition_temperature: f32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct FireRaw {}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct HealthRaw {
+    pub max: f32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct CrewAiRaw {}
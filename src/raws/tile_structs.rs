@@ -0,0 +1,56 @@
+use serde::Deserialize;
+
+use crate::map::structure::Material;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct TileRaws {
+    pub tiles: Vec<TileRaw>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct TileRaw {
+    pub key: String,
+    pub name: String,
+    pub top_glyph: String,
+    pub side_glyph: String,
+    pub foreground: String,
+    pub background: String,
+    pub passable: bool,
+    pub opaque: bool,
+    pub airtight: bool,
+    #[serde(default = "super::default_true")]
+    pub visible: bool,
+    pub atmosphere: AtmospherePreset,
+    //Starting temperature, defaults to that of the atmosphere preset
+    pub temperature: Option<f32>,
+    #[serde(default)]
+    pub thermal: ThermalRaw,
+    //Tiles without one can not be damaged
+    pub structure: Option<StructureRaw>,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct ThermalRaw {
+    #[serde(default)]
+    pub mass: f32,
+    #[serde(default)]
+    pub conductivity: f32,
+    #[serde(default)]
+    pub emissivity: f32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct StructureRaw {
+    pub material: Material,
+    pub integrity: f32,
+    //Key of the blueprint left behind once destroyed
+    pub destroyed_into: Option<String>,
+}
+
+//Named starting atmospheres a tile can be filled with
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum AtmospherePreset {
+    Stp,
+    Vacuum,
+}
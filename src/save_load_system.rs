@@ -12,19 +12,28 @@ use specs::{
 };
 use specs::{Entity, Join};
 
-use crate::entities::biology::Breather;
+use crate::clock::{EventScheduler, GameClock};
+use crate::hazards::HazardDirector;
+use crate::entities::biology::{Breather, Thermoregulation, Vitals};
+use crate::entities::construction::ConstructionMaterial;
+use crate::entities::crew::CrewAi;
+use crate::entities::fire::{Burning, Fire, Flammable};
 use crate::entities::intents::Initiative;
+use crate::entities::logic::{DelayTimer, LogicGate, Sensor, Signal};
+use crate::entities::network::{NetworkAddress, Terminal};
 use crate::entities::power_components::{
-    ControlPanel, ElectronicHeater, PowerNode, PowerSource, PowerSwitch, PoweredState, Wire,
+    Battery, Broken, ControlPanel, ElectronicHeater, Fuel, Generator, PowerNode, PowerSource,
+    PowerSwitch, PoweredState, Wire,
 };
+use crate::entities::power_graph::PowerGraph;
 use crate::entities::props::Cabinet;
 use crate::{
     vectors::Vector3i, Illuminant, Name, Photometry, Player, Renderable, SerializationHelper,
     SerializeThis, Tile, Viewshed,
 };
 use crate::{
-    Atmosphere, Blocker, Camera, Container, Door, Duct, EntityDirection, InContainer, Installed,
-    Item, Prop, VisionBlocker, TERMINAL_HEIGHT, TERMINAL_WIDTH,
+    Atmosphere, AutoClose, Blocker, Camera, Container, Door, Dragged, Duct, EntityDirection,
+    Health, InContainer, Installed, Item, Prop, VisionBlocker, TERMINAL_HEIGHT, TERMINAL_WIDTH,
 };
 
 macro_rules! serialize_individually {
@@ -43,10 +52,18 @@ macro_rules! serialize_individually {
 
 pub fn save_game(ecs: &mut World) {
     let map_copy = ecs.get_mut::<super::map::Map>().unwrap().clone();
+    let clock_copy = (*ecs.fetch::<GameClock>()).clone();
+    let scheduler_copy = (*ecs.fetch::<EventScheduler>()).clone();
+    let hazard_director_copy = (*ecs.fetch::<HazardDirector>()).clone();
 
     let save_helper = ecs
         .create_entity()
-        .with(SerializationHelper { map: map_copy })
+        .with(SerializationHelper {
+            map: map_copy,
+            clock: clock_copy,
+            scheduler: scheduler_copy,
+            hazard_director: hazard_director_copy,
+        })
         .marked::<SimpleMarker<SerializeThis>>()
         .build();
 
@@ -78,9 +95,21 @@ pub fn save_game(ecs: &mut World) {
             PowerSwitch,
             Wire,
             ControlPanel,
+            Broken,
+            Battery,
+            Generator,
+            Fuel,
+            Terminal,
+            NetworkAddress,
+            Signal,
+            Sensor,
+            LogicGate,
+            DelayTimer,
             EntityDirection,
             Atmosphere,
             Breather,
+            Vitals,
+            Thermoregulation,
             ElectronicHeater,
             Initiative,
             Item,
@@ -89,10 +118,18 @@ pub fn save_game(ecs: &mut World) {
             Installed,
             Container,
             Cabinet,
+            ConstructionMaterial,
             VisionBlocker,
             Blocker,
             Door,
+            AutoClose,
             Duct,
+            Fire,
+            Flammable,
+            Burning,
+            Health,
+            Dragged,
+            CrewAi,
             SerializationHelper
         );
     }
@@ -176,9 +213,21 @@ pub fn load_game(ecs: &mut World, ctx: &mut Rltk) {
             PowerSwitch,
             Wire,
             ControlPanel,
+            Broken,
+            Battery,
+            Generator,
+            Fuel,
+            Terminal,
+            NetworkAddress,
+            Signal,
+            Sensor,
+            LogicGate,
+            DelayTimer,
             EntityDirection,
             Atmosphere,
             Breather,
+            Vitals,
+            Thermoregulation,
             ElectronicHeater,
             Initiative,
             Item,
@@ -187,10 +236,18 @@ pub fn load_game(ecs: &mut World, ctx: &mut Rltk) {
             Installed,
             Container,
             Cabinet,
+            ConstructionMaterial,
             VisionBlocker,
             Blocker,
             Door,
+            AutoClose,
             Duct,
+            Fire,
+            Flammable,
+            Burning,
+            Health,
+            Dragged,
+            CrewAi,
             SerializationHelper
         );
     }
@@ -207,6 +264,15 @@ pub fn load_game(ecs: &mut World, ctx: &mut Rltk) {
             *worldmap = helper.map.clone();
             worldmap.entities = BiMap::new();
             delete_me = Some(entity);
+
+            *ecs.write_resource::<GameClock>() = helper.clock.clone();
+            *ecs.write_resource::<EventScheduler>() = helper.scheduler.clone();
+            *ecs.write_resource::<HazardDirector>() = helper.hazard_director.clone();
+
+            //Zone ids are not saved, rebuild them for the loaded map
+            ecs.write_resource::<super::map::zones::AtmosphereZones>().dirty = true;
+            //Neither is the power graph
+            ecs.write_resource::<PowerGraph>().dirty = true;
         }
 
         for (entity, _player, position) in (&entities, &players, &positions).join() {
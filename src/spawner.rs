@@ -1,4 +1,4 @@
-use std::{collections::HashSet, f32::consts::PI};
+use std::collections::HashSet;
 
 use rltk::{RGB, RGBA};
 use specs::{
@@ -8,21 +8,17 @@ use specs::{
 };
 
 use crate::{
-    entities::{
-        biology::Breather,
-        intents::Initiative,
-        power_components::{ControlPanel, ElectronicHeater},
-        props::Cabinet,
-    },
+    entities::power_components::{ElectronicHeater, WireGauge},
     graphics::char_to_glyph,
     pathfinding::{find_walkable_path, wall_climb_path},
+    raws::spawn_named,
     vectors::{
         utils::{get_cardinal_neighbours, get_cardinal_neighbours_with_z},
         Vector3i,
     },
     Blocker, Container, Direction, Door, Duct, EntityDirection, Illuminant, InContainer, Installed,
-    Item, Map, Name, Photometry, Player, PowerNode, PowerSource, PowerSwitch, PoweredState, Prop,
-    Renderable, SerializeThis, Viewshed, VisionBlocker, Wire,
+    Item, Map, Name, Photometry, PowerNode, PowerSource, PowerSwitch, Prop, Renderable,
+    SerializeThis, VisionBlocker, Wire,
 };
 
 pub fn player(ecs: &mut World, player_position: Vector3i) -> Entity {
@@ -30,31 +26,7 @@ pub fn player(ecs: &mut World, player_position: Vector3i) -> Entity {
     {
         crate::add_camera(player_position, ecs, true);
     }
-    ecs.create_entity()
-        .with(player_position)
-        .with(Renderable::new(
-            char_to_glyph('@'),
-            char_to_glyph('@'),
-            RGB::named(rltk::YELLOW).to_rgba(1.0),
-            RGB::named(rltk::BLACK).to_rgba(1.0),
-            true,
-        ))
-        .with(Player::new())
-        .with(Viewshed::new(60, 3, 0.9))
-        .with(Photometry::new())
-        .with(Illuminant::new(
-            1.0,
-            10,
-            RGB::named(rltk::WHITE).to_rgba(1.0),
-            PI * 2.0,
-            false,
-        ))
-        .with(Name::new("Player".to_string()))
-        .with(Breather::new_humanlike())
-        .with(Initiative::new(0.0))
-        .with(Container::new(2.0))
-        .marked::<SimpleMarker<SerializeThis>>()
-        .build()
+    spawn_named(ecs, "player", player_position).expect("Error spawning player")
 }
 
 #[allow(dead_code)]
@@ -66,30 +38,14 @@ pub fn standing_lamp(
     color: RGBA,
     on: bool,
 ) -> Entity {
-    ecs.create_entity()
-        .with(position)
-        .with(Renderable::new(
-            char_to_glyph('î'),
-            char_to_glyph('î'),
-            RGB::named(rltk::ANTIQUEWHITE4).to_rgba(1.0),
-            RGB::named(rltk::BLACK).to_rgba(0.0),
-            true,
-        ))
-        .with(Viewshed::new(10, 3, 1.0))
-        .with(Photometry::new())
-        .with(Illuminant::new(intensity, 10, color, PI * 2.0, false))
-        .with(Name::new(name.to_string()))
-        .with(PoweredState::new(true, 10.0))
-        .with(PowerSwitch::new(on))
-        .with(PowerNode::new())
-        .with(Blocker::new_all_sides(true))
-        .with(Prop::new())
-        .with(Installed::new())
-        .marked::<SimpleMarker<SerializeThis>>()
-        .build()
-}
+    let entity =
+        spawn_named(ecs, "standing_lamp", position).expect("Error spawning standing lamp");
 
-pub const CEILING_LAMP_RANGE: usize = 30;
+    configure_lamp(ecs, entity, intensity, color, on);
+    let _ = ecs.write_storage::<Name>().insert(entity, Name::new(name));
+
+    entity
+}
 
 pub fn ceiling_lamp(
     ecs: &mut World,
@@ -98,32 +54,23 @@ pub fn ceiling_lamp(
     color: RGBA,
     on: bool,
 ) -> Entity {
-    ecs.create_entity()
-        .with(position)
-        .with(Renderable::new(
-            char_to_glyph('☼'),
-            char_to_glyph('☼'),
-            RGB::named(rltk::ANTIQUEWHITE4).to_rgba(1.0),
-            RGB::named(rltk::BLACK).to_rgba(0.0),
-            true,
-        ))
-        .with(Viewshed::new(CEILING_LAMP_RANGE, 3, 1.0))
-        .with(Photometry::new())
-        .with(Illuminant::new(
-            intensity,
-            CEILING_LAMP_RANGE,
-            color,
-            PI * 2.0,
-            false,
-        ))
-        .with(Name::new("Ceiling lamp".to_string()))
-        .with(PoweredState::new(true, 10.0))
-        .with(PowerSwitch::new(on))
-        .with(PowerNode::new())
-        .with(Prop::new())
-        .with(Installed::new())
-        .marked::<SimpleMarker<SerializeThis>>()
-        .build()
+    let entity = spawn_named(ecs, "ceiling_lamp", position).expect("Error spawning ceiling lamp");
+
+    configure_lamp(ecs, entity, intensity, color, on);
+
+    entity
+}
+
+//Lamp templates only describe the fixture, the light itself is set by the caller
+fn configure_lamp(ecs: &mut World, entity: Entity, intensity: f32, color: RGBA, on: bool) {
+    if let Some(illuminant) = ecs.write_storage::<Illuminant>().get_mut(entity) {
+        illuminant.intensity = intensity;
+        illuminant.color = color;
+    }
+
+    let _ = ecs
+        .write_storage::<PowerSwitch>()
+        .insert(entity, PowerSwitch::new(on));
 }
 
 pub fn door(
@@ -173,24 +120,38 @@ pub fn door(
 }
 
 pub fn power_source(ecs: &mut World, position: Vector3i, on: bool, power: f32) {
-    ecs.create_entity()
-        .with(position)
-        .with(Renderable::new(
-            char_to_glyph('◘'),
-            char_to_glyph('◘'),
-            RGB::named(rltk::WHITE).to_rgba(1.0),
-            RGB::named(rltk::BLACK).to_rgba(0.0),
-            true,
-        ))
-        .with(Photometry::new())
-        .with(Name::new("Power source".to_string()))
-        .with(PowerSource::new(on, power))
-        .with(PowerSwitch::new(true))
-        .with(PowerNode::new())
-        .with(Prop::new())
-        .with(Installed::new())
-        .marked::<SimpleMarker<SerializeThis>>()
-        .build();
+    let entity = spawn_named(ecs, "power_source", position).expect("Error spawning power source");
+
+    let _ = ecs
+        .write_storage::<PowerSource>()
+        .insert(entity, PowerSource::new(on, power));
+}
+
+//Burns canisters from its hopper, comes loaded with a few to get the ship going
+pub fn fuel_generator(ecs: &mut World, position: Vector3i, canisters: usize) -> Entity {
+    let generator =
+        spawn_named(ecs, "fuel_generator", position).expect("Error spawning fuel generator");
+
+    for _ in 0..canisters {
+        let canister = fuel_canister(ecs, position);
+
+        //No more than the hopper holds
+        if !put_item_in_container(ecs, canister, generator) {
+            let _ = ecs.delete_entity(canister);
+            break;
+        }
+    }
+
+    generator
+}
+
+pub fn fuel_canister(ecs: &mut World, position: Vector3i) -> Entity {
+    spawn_named(ecs, "fuel_canister", position).expect("Error spawning fuel canister")
+}
+
+//Starts full and idle, only feeds the network once generation falls short
+pub fn battery(ecs: &mut World, position: Vector3i) -> Entity {
+    spawn_named(ecs, "battery", position).expect("Error spawning battery")
 }
 
 #[allow(dead_code)]
@@ -579,25 +540,40 @@ pub fn lay_ducting(ecs: &mut World, map: Map, start_position: Vector3i, end_posi
     }
 }
 
-pub fn breaker_box(ecs: &mut World, position: Vector3i) {
-    ecs.create_entity()
-        .with(position)
-        .with(Renderable::new(
-            char_to_glyph('b'),
-            char_to_glyph('b'),
-            RGB::named(rltk::BLACK).to_rgba(1.0),
-            RGB::named(rltk::GRAY).to_rgba(1.0),
-            true,
-        ))
-        .with(Photometry::new())
-        .with(ControlPanel {})
-        .with(Name::new("Control panel".to_string()))
-        .with(PowerSwitch::new(true))
-        .with(PowerNode::new())
-        .with(Prop::new())
-        .with(Installed::new())
-        .marked::<SimpleMarker<SerializeThis>>()
-        .build();
+pub fn crew(ecs: &mut World, position: Vector3i) -> Entity {
+    spawn_named(ecs, "crew", position).expect("Error spawning crew")
+}
+
+pub fn breaker_box(ecs: &mut World, position: Vector3i) -> Entity {
+    spawn_named(ecs, "breaker_box", position).expect("Error spawning breaker box")
+}
+
+pub fn terminal(ecs: &mut World, position: Vector3i) -> Entity {
+    spawn_named(ecs, "terminal", position).expect("Error spawning terminal")
+}
+
+//Sensors, gates and timers, spawned unlinked
+pub fn logic_device(ecs: &mut World, key: &str, position: Vector3i) -> Entity {
+    spawn_named(ecs, key, position).expect("Error spawning logic device")
+}
+
+//The kind of wire lay_wiring puts down along its path
+pub struct WireSpec {
+    pub color: RGBA,
+    pub color_name: String,
+    pub data: bool,
+    pub gauge: WireGauge,
+}
+
+impl WireSpec {
+    pub fn new(color: RGBA, color_name: String, data: bool, gauge: WireGauge) -> Self {
+        Self {
+            color,
+            color_name,
+            data,
+            gauge,
+        }
+    }
 }
 
 pub fn lay_wiring(
@@ -606,10 +582,8 @@ pub fn lay_wiring(
     start_position: Vector3i,
     end_position: Vector3i,
     avoid_positions: &HashSet<Vector3i>,
-    color: RGBA,
-    color_name: String,
+    spec: &WireSpec,
     roof_preferred: bool,
-    data: bool,
 ) {
     let path;
     if start_position.z == end_position.z {
@@ -639,7 +613,7 @@ pub fn lay_wiring(
 
             for (_, _) in (&wires, &positions)
                 .join()
-                .filter(|(wire, x)| wire.color_name == color_name && *x == position)
+                .filter(|(wire, x)| wire.color_name == spec.color_name && *x == position)
             {
                 wire_present = true;
             }
@@ -669,18 +643,21 @@ pub fn lay_wiring(
             char = '.';
         }
 
+        let mut wire = Wire::new(spec.color, spec.color_name.clone(), spec.data);
+        wire.gauge = spec.gauge;
+
         ecs.create_entity()
             .with(*position)
             .with(Renderable::new(
                 char_to_glyph(char),
                 char_to_glyph(char),
-                color,
+                spec.color,
                 RGB::named(rltk::BLACK).to_rgba(0.0),
                 true,
             ))
             .with(Photometry::new())
-            .with(Name::new(format!("Wire ({})", color_name.clone())))
-            .with(Wire::new(color, color_name.clone(), data))
+            .with(Name::new(format!("Wire ({})", spec.color_name)))
+            .with(wire)
             .with(EntityDirection::new(direction))
             .with(PowerNode::new())
             .with(Prop::new())
@@ -692,84 +669,57 @@ pub fn lay_wiring(
     }
 }
 
-#[allow(dead_code)]
 pub fn heater(ecs: &mut World, position: Vector3i, target_temperature: f32, on: bool) -> Entity {
-    ecs.create_entity()
-        .with(position)
-        .with(Renderable::new(
-            char_to_glyph('H'),
-            char_to_glyph('H'),
-            RGB::named(rltk::BLACK).to_rgba(1.0),
-            RGB::named(rltk::GRAY).to_rgba(1.0),
-            true,
-        ))
-        .with(Viewshed::new(2, 1, 1.0))
-        .with(Photometry::new())
-        .with(Illuminant::new(
-            0.5,
-            2,
-            RGB::named(rltk::DARK_ORANGE).to_rgba(1.0),
-            PI * 2.0,
-            true,
-        ))
-        .with(Name::new("Heater".to_string()))
-        .with(PoweredState::new(true, 10.0))
-        .with(PowerSwitch::new(on))
-        .with(PowerNode::new())
-        .with(Prop::new())
-        .with(ElectronicHeater::new(target_temperature, on))
-        .with(Installed::new())
-        .marked::<SimpleMarker<SerializeThis>>()
-        .build()
+    let entity = spawn_named(ecs, "heater", position).expect("Error spawning heater");
+
+    let _ = ecs
+        .write_storage::<ElectronicHeater>()
+        .insert(entity, ElectronicHeater::new(target_temperature, on));
+    let _ = ecs
+        .write_storage::<PowerSwitch>()
+        .insert(entity, PowerSwitch::new(on));
+
+    entity
 }
 
 pub fn test_item(ecs: &mut World, position: Vector3i) -> Entity {
-    ecs.create_entity()
-        .with(position)
-        .with(Renderable::new(
-            char_to_glyph('¡'),
-            char_to_glyph('¡'),
-            RGB::named(rltk::RED).to_rgba(1.0),
-            RGB::named(rltk::BLACK).to_rgba(0.0),
-            true,
-        ))
-        .with(Photometry::new())
-        .with(Name::new("Test tube".to_string()))
-        .with(Item::new(1.0, 0.1))
-        .marked::<SimpleMarker<SerializeThis>>()
-        .build()
+    spawn_named(ecs, "test_item", position).expect("Error spawning test item")
+}
+
+pub fn steel_plating(ecs: &mut World, position: Vector3i) -> Entity {
+    spawn_named(ecs, "steel_plating", position).expect("Error spawning steel plating")
+}
+
+pub fn glass_pane(ecs: &mut World, position: Vector3i) -> Entity {
+    spawn_named(ecs, "glass_pane", position).expect("Error spawning glass pane")
 }
 
 pub fn storage_cabinet(ecs: &mut World, position: Vector3i) -> Entity {
-    ecs.create_entity()
-        .with(Cabinet::new())
-        .with(position)
-        .with(Renderable::new(
-            char_to_glyph('H'),
-            char_to_glyph('H'),
-            RGB::named(rltk::WHITE).to_rgba(1.0),
-            RGB::named(rltk::GRAY5).to_rgba(1.0),
-            true,
-        ))
-        .with(Photometry::new())
-        .with(Name::new("Storage cabinet".to_string()))
-        .with(Installed::new())
-        .with(Container::new(100.0))
-        .with(Blocker::new_all_sides(true))
-        .marked::<SimpleMarker<SerializeThis>>()
-        .build()
+    spawn_named(ecs, "storage_cabinet", position).expect("Error spawning storage cabinet")
 }
 
-pub fn put_item_in_container(ecs: &mut World, item: Entity, container: Entity) {
+//False if the container is missing or has no room, the item is then left where it is
+pub fn put_item_in_container(ecs: &mut World, item: Entity, container: Entity) -> bool {
     let mut positions = ecs.write_storage::<Vector3i>();
     let mut in_container = ecs.write_storage::<InContainer>();
-    let containers = ecs.write_storage::<Container>();
+    let mut containers = ecs.write_storage::<Container>();
+    let items = ecs.read_storage::<Item>();
 
-    positions.remove(item);
+    let container_component = match containers.get_mut(container) {
+        Some(container_component) => container_component,
+        None => return false,
+    };
 
-    if let Some(container_component) = containers.get(container) {
-        let _ = in_container.insert(item, InContainer::new(container_component.id));
+    if let Some(item_component) = items.get(item) {
+        if !container_component.try_insert_item(item_component.volume) {
+            return false;
+        }
     }
+
+    positions.remove(item);
+    let _ = in_container.insert(item, InContainer::new(container_component.id));
+
+    true
 }
 
 fn update_duct_char(sides: &HashSet<Direction>) -> char {
@@ -31,4 +31,8 @@ pub enum RunState {
         id: u32,
         selected_item: Option<Entity>,
     },
+    //Picking a neighbouring tile to build on, then what to build
+    ConstructGUI {
+        target: Option<Vector3i>,
+    },
 }
@@ -5,6 +5,10 @@ use std::collections::{HashMap, HashSet};
 
 use crate::{
     entities::atmospherics::{Gas, R},
+    map::{
+        pressure::PressureForces,
+        zones::{AirtightBlockers, AtmosphereZones},
+    },
     vectors::{
         utils::{get_neighbours, get_neighbours_with_z},
         Vector3i,
@@ -12,8 +16,6 @@ use crate::{
     Blocker, Map,
 };
 
-use super::event_system::check_entity_blocking;
-
 const PRESSURE_THRESHOLD: f32 = 0.00001;
 const DISSIPATION_THRESHOLD: f32 = 0.00001;
 const DIFFUSION_SMOOTHING: f32 = 0.8;
@@ -24,16 +26,28 @@ pub struct AtmosphereSystem {}
 impl<'a> System<'a> for AtmosphereSystem {
     type SystemData = (
         WriteExpect<'a, Map>,
+        WriteExpect<'a, AtmosphereZones>,
+        WriteExpect<'a, PressureForces>,
         ReadStorage<'a, Blocker>,
-        WriteStorage<'a, Vector3i>,
+        ReadStorage<'a, Vector3i>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (mut map, blockers, mut positions) = data;
+        let (mut map, mut zones, mut pressure_forces, blockers, positions) = data;
+
+        pressure_forces.forces.clear();
 
         // let now = std::time::Instant::now();
 
-        let dirty_atmospheres: Vec<Vector3i> = map
+        let airtight_blockers = AirtightBlockers::new(&blockers, &positions);
+
+        zones.check_blockers(&airtight_blockers);
+
+        if zones.dirty {
+            zones.rebuild(&mut map, &airtight_blockers);
+        }
+
+        let changed_atmospheres: Vec<(Vector3i, usize)> = map
             .tiles
             .par_iter_mut()
             .filter(|(_, tile)| tile.atmosphere.dirty)
@@ -41,10 +55,26 @@ impl<'a> System<'a> for AtmosphereSystem {
                 tile.atmosphere.recalculate_temperature();
                 tile.atmosphere.recalculate_pressure();
                 tile.atmosphere.dirty = false;
-                position.clone()
+                (*position, tile.atmosphere.space_id)
             })
             .collect();
 
+        //Settled zones are evened out as a whole, only unsettled ones need tile by tile flow
+        let mut settled_zones = HashSet::new();
+        let mut dirty_atmospheres = Vec::new();
+
+        for (position, space_id) in changed_atmospheres.into_iter() {
+            if zones.is_settled(space_id) {
+                settled_zones.insert(space_id);
+            } else {
+                dirty_atmospheres.push(position);
+            }
+        }
+
+        for space_id in settled_zones.iter() {
+            zones.equalise(&mut map, *space_id);
+        }
+
         //println!("{:?}", now.elapsed());
 
         for position in dirty_atmospheres.iter() {
@@ -52,6 +82,10 @@ impl<'a> System<'a> for AtmosphereSystem {
 
             let mut temperature = 0.0;
             let mut pressure = 0.0;
+            let space_id = match map.tiles.get(position) {
+                Some(tile) => tile.atmosphere.space_id,
+                None => continue,
+            };
 
             let mut neighbour_mol_deltas: HashMap<Gas, HashMap<Vector3i, f32>> = HashMap::new();
             let mut neighbour_count: HashMap<Gas, usize> = HashMap::new();
@@ -73,14 +107,10 @@ impl<'a> System<'a> for AtmosphereSystem {
                 for neighbour in neighbours.iter() {
                     if let Some(neighbour_tile) = map.tiles.get(neighbour) {
                         //Check collisions
-                        if neighbour_tile.airtight {
-                            continue;
-                        } else if check_entity_blocking(
-                            &blockers,
-                            &mut positions,
-                            *position,
-                            *neighbour,
-                        ) {
+                        if neighbour_tile.airtight
+                            || neighbour_tile.atmosphere.space_id != space_id
+                            || airtight_blockers.is_blocked(*position, *neighbour)
+                        {
                             continue;
                         }
 
@@ -118,6 +148,11 @@ impl<'a> System<'a> for AtmosphereSystem {
                             } else if (pressure - neighbour_pressure) > PRESSURE_THRESHOLD {
                                 let delta = pressure - neighbour_pressure;
                                 neighbour_pressure_deltas.insert(*neighbour, delta);
+                                pressure_forces
+                                    .forces
+                                    .entry(*position)
+                                    .or_default()
+                                    .add(*neighbour - *position, delta);
                                 total_delta += delta;
                                 total_pressure += neighbour_pressure;
                             } else {
@@ -173,14 +208,10 @@ impl<'a> System<'a> for AtmosphereSystem {
                 for neighbour in neighbours.iter() {
                     if let Some(neighbour_tile) = map.tiles.get(neighbour) {
                         //Check collisions
-                        if neighbour_tile.airtight {
-                            continue;
-                        } else if check_entity_blocking(
-                            &blockers,
-                            &mut positions,
-                            *position,
-                            *neighbour,
-                        ) {
+                        if neighbour_tile.airtight
+                            || neighbour_tile.atmosphere.space_id != space_id
+                            || airtight_blockers.is_blocked(*position, *neighbour)
+                        {
                             continue;
                         }
 
@@ -258,8 +289,12 @@ impl<'a> System<'a> for AtmosphereSystem {
                 for (neighbour, delta) in gas_deltas.iter() {
                     let proportion = *delta / total_delta;
 
-                    let mols_to_swap =
-                        excess_mols * proportion * (delta / current_mols) * DIFFUSION_SMOOTHING;
+                    //Heavy gasses spread slower than light ones
+                    let mols_to_swap = excess_mols
+                        * proportion
+                        * (delta / current_mols)
+                        * DIFFUSION_SMOOTHING
+                        * gas.diffusion_rate();
 
                     if mols_to_swap < DISSIPATION_THRESHOLD {
                         continue;
@@ -314,6 +349,17 @@ impl<'a> System<'a> for AtmosphereSystem {
                     tile.atmosphere.dirty = false;
                 }
             }
+
+            //Wake up neighbours that should be pushing gas into this tile
+            for neighbour in higher_pressure_neighbours.iter() {
+                if let Some(tile) = map.tiles.get_mut(neighbour) {
+                    tile.atmosphere.dirty = true;
+                }
+            }
+        }
+
+        for space_id in zones.unsettled_zones().into_iter() {
+            zones.update_settled(&mut map, space_id);
         }
 
         /*let mut total_gasses: HashMap<Gas, f32> = HashMap::new();
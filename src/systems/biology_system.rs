@@ -1,6 +1,14 @@
 use specs::prelude::*;
 
-use crate::{entities::biology::Breather, vectors::Vector3i, Map};
+use crate::{
+    entities::{
+        biology::{Breather, VitalState, Vitals},
+        intents::IntentStorages,
+    },
+    gamelog::GameLog,
+    vectors::Vector3i,
+    Health, Map, Name,
+};
 
 
 
@@ -9,23 +17,86 @@ pub struct BiologySystem {}
 impl<'a> System<'a> for BiologySystem {
     type SystemData = (
         WriteExpect<'a, Map>,
+        WriteExpect<'a, GameLog>,
         ReadStorage<'a, Vector3i>,
+        ReadStorage<'a, Name>,
         WriteStorage<'a, Breather>,
+        WriteStorage<'a, Vitals>,
+        WriteStorage<'a, Health>,
+        IntentStorages<'a>,
+        Entities<'a>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
         let (
             mut map,
+            mut game_log,
             positions,
+            names,
             mut breathers,
+            mut vitals,
+            mut healths,
+            mut intents,
+            entities,
         ) = data;
 
-        for (breather, position) in (&mut breathers, &positions).join().filter(|(x, _)| x.trigger_breath) {
-            breather.trigger_breath = false;
+        for (entity, breather, position, name) in (&entities, &mut breathers, &positions, names.maybe()).join() {
+            let entity_vitals = vitals.get_mut(entity);
 
-            if let Some(tile) = map.tiles.get_mut(position) {
-                  breather.breath(&mut tile.atmosphere);
+            //The dead do not breathe
+            if let Some(entity_vitals) = &entity_vitals {
+                if entity_vitals.state == VitalState::Dead {
+                    continue;
+                }
+            }
+
+            let breath = match map.tiles.get_mut(position) {
+                Some(tile) => breather.breath(&mut tile.atmosphere),
+                None => continue,
+            };
+
+            if let Some(name) = name {
+                for gas in breath.toxic_gasses.iter().filter(|gas| !breather.toxic_exposure.contains(gas)) {
+                    game_log.entries.push(format!("{} chokes on the {}", name.name, gas.readable_name()));
+                }
+            }
+            breather.toxic_exposure = breath.toxic_gasses.clone();
+
+            if let Some(entity_vitals) = entity_vitals {
+                entity_vitals.update(&breath);
+
+                let previous_state = entity_vitals.state;
+                entity_vitals.state = entity_vitals.assess();
+
+                if entity_vitals.state != previous_state {
+                    if let Some(name) = name {
+                        game_log.entries.push(vital_state_message(&name.name, previous_state, entity_vitals.state));
+                    }
+
+                    if entity_vitals.state == VitalState::Dead {
+                        if let Some(health) = healths.get_mut(entity) {
+                            health.current = 0.0;
+                        }
+                    }
+                }
+            }
+        }
+
+        //Anyone out cold drops whatever they were doing
+        for (entity, entity_vitals) in (&entities, &vitals).join() {
+            if entity_vitals.is_incapacitated() {
+                intents.remove(entity);
             }
         }
     }
-}
\ No newline at end of file
+}
+
+fn vital_state_message(name: &str, previous_state: VitalState, state: VitalState) -> String {
+    match state {
+        VitalState::Healthy => format!("{} breathes easier", name),
+        VitalState::Dizzy if previous_state > VitalState::Dizzy => format!("{} comes to, dizzy", name),
+        VitalState::Dizzy => format!("{} feels dizzy", name),
+        VitalState::Unconscious => format!("{} passes out", name),
+        VitalState::Dead => format!("{} dies", name),
+    }
+}
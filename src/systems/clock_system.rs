@@ -0,0 +1,106 @@
+use specs::prelude::*;
+
+use crate::{
+    clock::{EventScheduler, GameClock, ScheduledEvent},
+    entities::{
+        components::{AutoClose, DOOR_CLOSE_TURNS},
+        intents::Initiative,
+        power_components::{PowerNode, PowerSwitch},
+    },
+    gamelog::GameLog,
+    vectors::Vector3i,
+    Door,
+};
+
+//Advances game time and fires any scheduled events that have come due
+pub struct ClockSystem {}
+
+impl<'a> System<'a> for ClockSystem {
+    type SystemData = (
+        WriteExpect<'a, GameClock>,
+        WriteExpect<'a, EventScheduler>,
+        WriteExpect<'a, GameLog>,
+        ReadStorage<'a, Vector3i>,
+        WriteStorage<'a, Door>,
+        ReadStorage<'a, AutoClose>,
+        WriteStorage<'a, PowerSwitch>,
+        WriteStorage<'a, PowerNode>,
+        ReadStorage<'a, Initiative>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            mut clock,
+            mut scheduler,
+            mut game_log,
+            positions,
+            mut doors,
+            auto_closes,
+            mut power_switches,
+            mut power_nodes,
+            initiatives,
+        ) = data;
+
+        clock.advance();
+
+        for event in scheduler
+            .take_due_where(clock.turn, ScheduledEvent::is_world_event)
+            .into_iter()
+        {
+            match event {
+                ScheduledEvent::Message(message) => game_log.entries.push(message),
+                ScheduledEvent::CloseDoor(target) => {
+                    //Someone standing in the doorway holds it open for a while longer
+                    if (&initiatives, &positions)
+                        .join()
+                        .any(|(_, position)| *position == target)
+                    {
+                        let turns = (&auto_closes, &positions)
+                            .join()
+                            .find(|(_, position)| **position == target)
+                            .map_or(DOOR_CLOSE_TURNS, |(auto_close, _)| auto_close.turns);
+                        scheduler.schedule_in_turns(
+                            &clock,
+                            turns,
+                            ScheduledEvent::CloseDoor(target),
+                        );
+                        continue;
+                    }
+
+                    //State align picks up the change and blocks the tile again
+                    for (door, _) in (&mut doors, &positions)
+                        .join()
+                        .filter(|(door, position)| door.open && **position == target)
+                    {
+                        door.open_close();
+                    }
+                }
+                ScheduledEvent::SetSwitch {
+                    position: target,
+                    on,
+                } => {
+                    let mut changed = false;
+
+                    for (power_switch, _) in (&mut power_switches, &positions).join().filter(
+                        |(power_switch, position)| power_switch.on != on && **position == target,
+                    ) {
+                        power_switch.toggle();
+                        changed = true;
+                    }
+
+                    //Everything on the tile has to rebuild its power state
+                    if changed {
+                        for (power_node, _) in (&mut power_nodes, &positions)
+                            .join()
+                            .filter(|(_, position)| **position == target)
+                        {
+                            power_node.dirty = true;
+                        }
+                    }
+                }
+                //Taken by the systems they belong to
+                _ => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,456 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use specs::prelude::*;
+
+use crate::{
+    entities::{
+        atmospherics::Gas,
+        biology::Vitals,
+        crew::{CrewAi, CrewGoal},
+        intents::{InteractIntent, MoveIntent, PickUpIntent},
+        power_components::ControlPanel,
+    },
+    gamelog::GameLog,
+    pathfinding::find_character_path,
+    vectors::{
+        utils::{get_cardinal_neighbours, within_reach},
+        Vector3i,
+    },
+    Blocker, Container, Direction, Door, Installed, Item, Map, Name, PowerSwitch,
+};
+
+//Oxygen partial pressure in pascal below which crew go looking for better air
+const SAFE_OXYGEN_PRESSURE: f32 = 16000.0;
+//Air on the far side of an open door thinner than this counts as a breach
+const BREACH_PRESSURE: f32 = 50000.0;
+//How far crew will go out of their way for a loose item
+const FETCH_RANGE: i32 = 8;
+//Most tiles a single path search may look at
+const MAX_PATH_NODES: usize = 2000;
+//Nearest candidates tried before a goal is given up on
+const MAX_CANDIDATES: usize = 3;
+//Turns without reaching the next step before the goal is dropped
+const MAX_STUCK_TURNS: u32 = 3;
+//Turns to wait after giving up before looking for a new goal
+const IDLE_TURNS: u32 = 10;
+
+pub struct CrewAiSystem {}
+
+impl<'a> System<'a> for CrewAiSystem {
+    type SystemData = (
+        ReadExpect<'a, Map>,
+        WriteExpect<'a, GameLog>,
+        Entities<'a>,
+        WriteStorage<'a, CrewAi>,
+        ReadStorage<'a, Vector3i>,
+        ReadStorage<'a, Name>,
+        WriteStorage<'a, MoveIntent>,
+        WriteStorage<'a, InteractIntent>,
+        WriteStorage<'a, PickUpIntent>,
+        ReadStorage<'a, Door>,
+        ReadStorage<'a, PowerSwitch>,
+        ReadStorage<'a, ControlPanel>,
+        ReadStorage<'a, Item>,
+        ReadStorage<'a, Installed>,
+        ReadStorage<'a, Blocker>,
+        ReadStorage<'a, Container>,
+        ReadStorage<'a, Vitals>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            map,
+            mut game_log,
+            entities,
+            mut crew_ais,
+            positions,
+            names,
+            mut move_intents,
+            mut interact_intents,
+            mut pick_up_intents,
+            doors,
+            power_switches,
+            control_panels,
+            items,
+            installed,
+            blockers,
+            containers,
+            vitals,
+        ) = data;
+
+        let mut door_entities: HashMap<Vector3i, Entity> = HashMap::new();
+        for (entity, _, position) in (&entities, &doors, &positions).join() {
+            door_entities.insert(*position, entity);
+        }
+
+        //Closed doors get opened on the way, anything else that blocks is walked around
+        let mut avoid_positions: HashSet<Vector3i> = HashSet::new();
+        for (blocker, position, _) in (&blockers, &positions, !&doors).join() {
+            if blocker.sides.iter().any(|side| {
+                matches!(side, Direction::N | Direction::E | Direction::S | Direction::W)
+            }) {
+                avoid_positions.insert(*position);
+            }
+        }
+
+        let breached_doors: Vec<Vector3i> = (&doors, &positions)
+            .join()
+            .filter(|(door, position)| door.open && is_breached(&map, **position))
+            .map(|(_, position)| *position)
+            .collect();
+
+        let off_breakers: Vec<Vector3i> = (&control_panels, &power_switches, &positions)
+            .join()
+            .filter(|(_, power_switch, _)| !power_switch.on)
+            .map(|(_, _, position)| *position)
+            .collect();
+
+        let mut loose_items: HashMap<Vector3i, Vec<Entity>> = HashMap::new();
+        for (entity, _, position, _) in (&entities, &items, &positions, !&installed).join() {
+            loose_items.entry(*position).or_default().push(entity);
+        }
+
+        for (entity, crew_ai, position) in (&entities, &mut crew_ais, &positions).join() {
+            let position = *position;
+
+            if let Some(entity_vitals) = vitals.get(entity) {
+                if entity_vitals.is_incapacitated() {
+                    continue;
+                }
+            }
+
+            //Still busy with last turn's intent
+            if move_intents.get(entity).is_some()
+                || interact_intents.get(entity).is_some()
+                || pick_up_intents.get(entity).is_some()
+            {
+                continue;
+            }
+
+            let oxygen_pressure = map
+                .tiles
+                .get(&position)
+                .map(|tile| tile.atmosphere.get_partial_pressure(Gas::Oxygen))
+                .unwrap_or(0.0);
+            let needs_air = oxygen_pressure < SAFE_OXYGEN_PRESSURE;
+
+            //Bad air trumps whatever else they were doing
+            if needs_air && crew_ai.goal != Some(CrewGoal::Breathe) {
+                crew_ai.clear_goal(0);
+            }
+
+            //Drop goals that no longer need doing
+            let goal_valid = match (crew_ai.goal, crew_ai.target) {
+                (Some(CrewGoal::Breathe), Some(_)) => true,
+                (Some(CrewGoal::CloseDoor), Some(target)) => breached_doors.contains(&target),
+                (Some(CrewGoal::ResetBreaker), Some(target)) => off_breakers.contains(&target),
+                (Some(CrewGoal::FetchItem), Some(target)) => loose_items.contains_key(&target),
+                _ => false,
+            };
+
+            if !goal_valid {
+                if crew_ai.goal.is_some() {
+                    crew_ai.clear_goal(0);
+                }
+
+                if crew_ai.idle_turns > 0 {
+                    crew_ai.idle_turns -= 1;
+                    continue;
+                }
+
+                let free_volume = containers
+                    .get(entity)
+                    .map(|container| container.remaining_volume)
+                    .unwrap_or(0.0);
+
+                let chosen = if needs_air {
+                    find_breathable_air(&map, position, &avoid_positions)
+                        .map(|(target, path)| (CrewGoal::Breathe, target, path))
+                } else {
+                    let fetchable: Vec<Vector3i> = loose_items
+                        .iter()
+                        .filter(|(item_position, item_entities)| {
+                            item_position.manhattan(position) <= FETCH_RANGE
+                                && item_entities.iter().any(|item_entity| {
+                                    items
+                                        .get(*item_entity)
+                                        .map(|item| item.volume <= free_volume)
+                                        .unwrap_or(false)
+                                })
+                        })
+                        .map(|(item_position, _)| *item_position)
+                        .collect();
+
+                    choose_target(&map, position, &breached_doors, &avoid_positions, true)
+                        .map(|(target, path)| (CrewGoal::CloseDoor, target, path))
+                        .or_else(|| {
+                            choose_target(&map, position, &off_breakers, &avoid_positions, false)
+                                .map(|(target, path)| (CrewGoal::ResetBreaker, target, path))
+                        })
+                        .or_else(|| {
+                            choose_target(&map, position, &fetchable, &avoid_positions, false)
+                                .map(|(target, path)| (CrewGoal::FetchItem, target, path))
+                        })
+                };
+
+                match chosen {
+                    Some((goal, target, path)) => {
+                        crew_ai.set_goal(goal, target, path);
+
+                        if let Some(name) = names.get(entity) {
+                            game_log
+                                .entries
+                                .push(format!("{} {}", name.name, goal.description()));
+                        }
+                    }
+                    None => {
+                        crew_ai.idle_turns = IDLE_TURNS;
+                        continue;
+                    }
+                }
+            }
+
+            let (goal, target) = match (crew_ai.goal, crew_ai.target) {
+                (Some(goal), Some(target)) => (goal, target),
+                _ => continue,
+            };
+
+            //Carry out the goal once close enough
+            match goal {
+                CrewGoal::Breathe => {
+                    if position == target {
+                        crew_ai.clear_goal(0);
+                        continue;
+                    }
+                }
+                CrewGoal::CloseDoor => {
+                    if position != target && within_reach(position, target) {
+                        if let Some(door_entity) = door_entities.get(&target) {
+                            if let Some(door) = doors.get(*door_entity) {
+                                let _ = interact_intents.insert(
+                                    entity,
+                                    InteractIntent::new(
+                                        entity,
+                                        *door_entity,
+                                        door.interaction_id,
+                                        door.interaction_description.clone(),
+                                        door.cost,
+                                    ),
+                                );
+                            }
+                        }
+                        crew_ai.clear_goal(0);
+                        continue;
+                    }
+                }
+                CrewGoal::ResetBreaker => {
+                    if within_reach(position, target) {
+                        for (breaker_entity, _, power_switch, _) in
+                            (&entities, &control_panels, &power_switches, &positions)
+                                .join()
+                                .filter(|(_, _, _, breaker_position)| **breaker_position == target)
+                        {
+                            let _ = interact_intents.insert(
+                                entity,
+                                InteractIntent::new(
+                                    entity,
+                                    breaker_entity,
+                                    power_switch.interaction_id,
+                                    power_switch.interaction_description.clone(),
+                                    power_switch.cost,
+                                ),
+                            );
+                        }
+                        crew_ai.clear_goal(0);
+                        continue;
+                    }
+                }
+                CrewGoal::FetchItem => {
+                    if within_reach(position, target) {
+                        if let Some(item_entity) = loose_items.get(&target).and_then(|item_entities| {
+                            item_entities.first()
+                        }) {
+                            let _ = pick_up_intents
+                                .insert(entity, PickUpIntent::new(entity, *item_entity, 1.0));
+                        }
+                        crew_ai.clear_goal(0);
+                        continue;
+                    }
+                }
+            }
+
+            //Otherwise take the next step along the path
+            if crew_ai.path.first() == Some(&position) {
+                crew_ai.path.remove(0);
+                crew_ai.stuck_turns = 0;
+            }
+
+            let next = match crew_ai.path.first() {
+                Some(next) if next.manhattan(position) == 1 => *next,
+                _ => {
+                    //Knocked off the path, find a new one to the same place
+                    let destination = match crew_ai.path.last() {
+                        Some(destination) => *destination,
+                        None => target,
+                    };
+
+                    match find_character_path(
+                        &map,
+                        position,
+                        destination,
+                        &avoid_positions,
+                        MAX_PATH_NODES,
+                    ) {
+                        Some(path) if path.len() > 1 => {
+                            crew_ai.path = path[1..].to_vec();
+                            crew_ai.path[0]
+                        }
+                        _ => {
+                            crew_ai.clear_goal(IDLE_TURNS);
+                            continue;
+                        }
+                    }
+                }
+            };
+
+            crew_ai.stuck_turns += 1;
+
+            if crew_ai.stuck_turns > MAX_STUCK_TURNS {
+                crew_ai.clear_goal(IDLE_TURNS);
+                continue;
+            }
+
+            //Closed doors along the way are opened rather than walked into
+            if let Some(door_entity) = door_entities.get(&next) {
+                if let Some(door) = doors.get(*door_entity) {
+                    if !door.open {
+                        let _ = interact_intents.insert(
+                            entity,
+                            InteractIntent::new(
+                                entity,
+                                *door_entity,
+                                door.interaction_id,
+                                door.interaction_description.clone(),
+                                door.cost,
+                            ),
+                        );
+                        continue;
+                    }
+                }
+            }
+
+            let _ = move_intents.insert(entity, MoveIntent::new(position, next - position));
+        }
+    }
+}
+
+//An open door with thin air on one side
+fn is_breached(map: &Map, door_position: Vector3i) -> bool {
+    get_cardinal_neighbours(door_position)
+        .iter()
+        .any(|neighbour| match map.tiles.get(neighbour) {
+            Some(tile) => tile.passable && tile.atmosphere.pressure < BREACH_PRESSURE,
+            None => false,
+        })
+}
+
+//Picks the nearest target that can be walked to, returning it and the steps to get there.
+//Doors are approached from the side that still has air
+fn choose_target(
+    map: &Map,
+    position: Vector3i,
+    targets: &[Vector3i],
+    avoid_positions: &HashSet<Vector3i>,
+    approach_from_air: bool,
+) -> Option<(Vector3i, Vec<Vector3i>)> {
+    let mut candidates: Vec<Vector3i> = targets
+        .iter()
+        .filter(|target| target.z == position.z)
+        .cloned()
+        .collect();
+    candidates.sort_by_key(|target| (target.manhattan(position), *target));
+
+    for target in candidates.into_iter().take(MAX_CANDIDATES) {
+        let destination = if approach_from_air {
+            let standing_position = get_cardinal_neighbours(target).into_iter().find(|neighbour| {
+                match map.tiles.get(neighbour) {
+                    Some(tile) => tile.passable && tile.atmosphere.pressure >= BREACH_PRESSURE,
+                    None => false,
+                }
+            });
+
+            match standing_position {
+                Some(standing_position) => standing_position,
+                None => continue,
+            }
+        } else {
+            target
+        };
+
+        if destination == position {
+            return Some((target, Vec::new()));
+        }
+
+        if let Some(path) =
+            find_character_path(map, position, destination, avoid_positions, MAX_PATH_NODES)
+        {
+            return Some((target, path[1..].to_vec()));
+        }
+    }
+    None
+}
+
+//Searches outwards for the closest tile with enough oxygen to breathe
+fn find_breathable_air(
+    map: &Map,
+    position: Vector3i,
+    avoid_positions: &HashSet<Vector3i>,
+) -> Option<(Vector3i, Vec<Vector3i>)> {
+    let mut came_from: HashMap<Vector3i, Vector3i> = HashMap::new();
+    let mut open_set: VecDeque<Vector3i> = VecDeque::new();
+    let mut visited: HashSet<Vector3i> = HashSet::new();
+
+    open_set.push_back(position);
+    visited.insert(position);
+
+    while let Some(current_position) = open_set.pop_front() {
+        if visited.len() > MAX_PATH_NODES {
+            return None;
+        }
+
+        if let Some(tile) = map.tiles.get(&current_position) {
+            if current_position != position
+                && tile.atmosphere.get_partial_pressure(Gas::Oxygen) >= SAFE_OXYGEN_PRESSURE
+            {
+                let mut path = vec![current_position];
+                let mut step = current_position;
+
+                while let Some(previous) = came_from.get(&step) {
+                    if *previous == position {
+                        break;
+                    }
+                    path.insert(0, *previous);
+                    step = *previous;
+                }
+
+                return Some((current_position, path));
+            }
+        }
+
+        for neighbour in get_cardinal_neighbours(current_position) {
+            if visited.contains(&neighbour) || avoid_positions.contains(&neighbour) {
+                continue;
+            }
+
+            match map.tiles.get(&neighbour) {
+                Some(tile) if tile.passable => {}
+                _ => continue,
+            }
+
+            visited.insert(neighbour);
+            came_from.insert(neighbour, current_position);
+            open_set.push_back(neighbour);
+        }
+    }
+    None
+}
@@ -0,0 +1,141 @@
+use specs::prelude::*;
+
+use crate::{
+    entities::intents::{Initiative, MoveIntent},
+    gamelog::GameLog,
+    map::pressure::PressureForces,
+    vectors::Vector3i,
+    Blocker, Dragged, Installed, Item, Map, Name,
+};
+
+use super::event_system::check_entity_blocking;
+
+//Pressure difference in pascal needed to shift a weightless item, heavier items need more
+const ITEM_FORCE_THRESHOLD: f32 = 5000.0;
+const ITEM_FORCE_PER_KG: f32 = 5000.0;
+//Pressure difference needed to drag a character off their feet
+const CHARACTER_FORCE_THRESHOLD: f32 = 20000.0;
+
+pub struct DecompressionSystem {}
+
+impl<'a> System<'a> for DecompressionSystem {
+    type SystemData = (
+        ReadExpect<'a, Map>,
+        ReadExpect<'a, PressureForces>,
+        WriteExpect<'a, GameLog>,
+        WriteStorage<'a, Vector3i>,
+        WriteStorage<'a, MoveIntent>,
+        WriteStorage<'a, Dragged>,
+        ReadStorage<'a, Item>,
+        ReadStorage<'a, Initiative>,
+        ReadStorage<'a, Installed>,
+        ReadStorage<'a, Blocker>,
+        ReadStorage<'a, Name>,
+        Entities<'a>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            map,
+            pressure_forces,
+            mut game_log,
+            mut positions,
+            mut move_intents,
+            mut dragged,
+            items,
+            initiatives,
+            installed,
+            blockers,
+            names,
+            entities,
+        ) = data;
+
+        if pressure_forces.forces.is_empty() {
+            dragged.clear();
+            return;
+        }
+
+        //Items are blown along directly
+        let mut item_moves = Vec::new();
+
+        for (entity, item, position, _) in (&entities, &items, &positions, !&installed).join() {
+            if let Some(force) = pressure_forces.get(position) {
+                if force.horizontal_magnitude()
+                    < ITEM_FORCE_THRESHOLD + ITEM_FORCE_PER_KG * item.weight
+                {
+                    continue;
+                }
+
+                let step = force.horizontal_step();
+                let target = *position + step;
+
+                match map.tiles.get(&target) {
+                    Some(tile) if tile.passable => {}
+                    _ => continue,
+                }
+
+                item_moves.push((entity, *position, target));
+            }
+        }
+
+        let mut moved_items = Vec::new();
+
+        for (entity, position, target) in item_moves.into_iter() {
+            if check_entity_blocking(&blockers, &positions, position, target) {
+                continue;
+            }
+
+            if let Some(item_position) = positions.get_mut(entity) {
+                *item_position = target;
+                moved_items.push(entity);
+            }
+        }
+
+        match moved_items.len() {
+            0 => {}
+            1 => {
+                if let Some(name) = names.get(moved_items[0]) {
+                    game_log
+                        .entries
+                        .push(format!("{} is swept toward the breach", name.name));
+                }
+            }
+            count => game_log
+                .entries
+                .push(format!("{} items are swept toward the breach", count)),
+        }
+
+        //Characters are dragged through the event system so blockers and initiative still apply
+        let mut dragged_now = Vec::new();
+
+        for (entity, position, _, _) in (&entities, &positions, &initiatives, !&installed).join() {
+            if let Some(force) = pressure_forces.get(position) {
+                if force.horizontal_magnitude() < CHARACTER_FORCE_THRESHOLD {
+                    continue;
+                }
+
+                let step = force.horizontal_step();
+
+                if step == Vector3i::new_equi(0) {
+                    continue;
+                }
+
+                let _ = move_intents.insert(entity, MoveIntent::new(*position, step));
+                dragged_now.push(entity);
+
+                if dragged.get(entity).is_none() {
+                    if let Some(name) = names.get(entity) {
+                        game_log
+                            .entries
+                            .push(format!("{} is dragged toward the breach", name.name));
+                    }
+                }
+            }
+        }
+
+        dragged.clear();
+        for entity in dragged_now.into_iter() {
+            let _ = dragged.insert(entity, Dragged {});
+        }
+    }
+}
@@ -7,6 +7,17 @@ use state_align_system::StateAlignSystem;
 use visibility_system::VisibilitySystem;
 use atmosphere_system::AtmosphereSystem;
 use biology_system::BiologySystem;
+use decompression_system::DecompressionSystem;
+use fire_system::FireSystem;
+use thermal_system::ThermalSystem;
+use thermoregulation_system::ThermoregulationSystem;
+use crew_ai_system::CrewAiSystem;
+use clock_system::ClockSystem;
+use hazard_system::HazardSystem;
+use structural_system::StructuralSystem;
+use generator_system::GeneratorSystem;
+use network_system::NetworkSystem;
+use logic_system::LogicSystem;
 
 use super::*;
 
@@ -22,11 +33,22 @@ pub trait UnifiedDispatcher {
 
 construct_dispatcher!(
     //(MapIndexSystem, "map_index", &[]),
+    (ClockSystem, "clock", &[]),
+    (HazardSystem, "hazards", &["clock"]),
     (AtmosphereSystem, "atmosphere", &[]),
+    (DecompressionSystem, "decompression", &["atmosphere"]),
+    (FireSystem, "fire", &["atmosphere"]),
+    (ThermalSystem, "thermal", &["fire"]),
+    (StructuralSystem, "structural", &["hazards", "fire"]),
     (BiologySystem, "biology", &[]),
-    (EventSystem, "events", &[]),
+    (ThermoregulationSystem, "thermoregulation", &["biology"]),
+    (CrewAiSystem, "crew_ai", &["biology"]),
+    (EventSystem, "events", &["crew_ai"]),
+    (LogicSystem, "logic", &["events"]),
     (StateAlignSystem, "state_align", &[]),
+    (GeneratorSystem, "generators", &["events"]),
     (PowerSystem, "power", &[]),
+    (NetworkSystem, "network", &["power"]),
     (VisibilitySystem, "visibility", &[]),
     (LightingSystem, "lighting", &[])
 );
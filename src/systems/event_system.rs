@@ -3,370 +3,127 @@ use std::{cmp::Ordering, collections::BinaryHeap};
 use specs::{
     prelude::*,
     shred::{Fetch, FetchMut},
-    storage::{GenericReadStorage, MaskedStorage},
+    storage::MaskedStorage,
 };
 
 use crate::{
     entities::{
         intents::{
-            DropIntent, Initiative, Intent, InteractIntent, Interactable, MoveIntent, OpenIntent,
-            PickUpIntent,
+            with_interactables, Initiative, Intent, IntentContext, IntentStorages, Interactable,
+            InteractIntent, Interrupt,
         },
-        power_components::ControlPanel,
     },
     gamelog::GameLog,
+    map::pressure::PressureForces,
     states::RunState,
-    update_camera_position,
     vectors::Vector3i,
-    Blocker, Camera, Container, Door, Illuminant, InContainer, Installed, Item, Map, Name,
-    Photometry, PowerNode, PowerSwitch, Viewshed,
+    Blocker, Direction, Name,
 };
 
 pub struct EventSystem {}
 
-const TIME_PER_TURN: f32 = 1.0;
+//Air pushing past harder than this in pascal breaks concentration
+const INTERRUPT_PRESSURE_FORCE: f32 = 2000.0;
 
 impl<'a> System<'a> for EventSystem {
     type SystemData = (
-        Entities<'a>,
-        WriteExpect<'a, Map>,
-        WriteExpect<'a, RunState>,
-        WriteExpect<'a, GameLog>,
-        WriteExpect<'a, Entity>,
-        WriteExpect<'a, Vector3i>,
-        WriteStorage<'a, InteractIntent>,
-        WriteStorage<'a, MoveIntent>,
-        WriteStorage<'a, PowerSwitch>,
-        WriteStorage<'a, PowerNode>,
-        WriteStorage<'a, Initiative>,
-        ReadStorage<'a, Name>,
-        WriteStorage<'a, Door>,
-        WriteStorage<'a, Vector3i>,
-        WriteStorage<'a, Viewshed>,
-        WriteStorage<'a, Photometry>,
-        WriteStorage<'a, Illuminant>,
-        WriteStorage<'a, ControlPanel>,
-        ReadStorage<'a, Blocker>,
-        ReadStorage<'a, Camera>,
-        WriteStorage<'a, PickUpIntent>,
-        WriteStorage<'a, InContainer>,
-        WriteStorage<'a, Container>,
-        WriteStorage<'a, OpenIntent>,
-        WriteStorage<'a, DropIntent>,
-        ReadStorage<'a, Item>,
+        ReadExpect<'a, PressureForces>,
+        ReadStorage<'a, Initiative>,
+        WriteStorage<'a, Interrupt>,
+        IntentStorages<'a>,
+        IntentContext<'a>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (
-            entities,
-            map,
-            mut run_state,
-            mut game_log,
-            player,
-            mut stored_player_position,
-            mut interact_intents,
-            mut move_intents,
-            mut power_switches,
-            mut power_nodes,
-            initiatives,
-            names,
-            mut doors,
-            mut positions,
-            mut viewsheds,
-            mut photometria,
-            mut illuminants,
-            breakers,
-            blockers,
-            cameras,
-            mut pick_up_intents,
-            mut in_container,
-            mut containers,
-            mut open_intents,
-            mut drop_intents,
-            items,
-        ) = data;
-
-        let mut next_state: Option<RunState> = None;
-        //Handle movement intents
-        let mut entities_to_handle = BinaryHeap::new();
+        let (pressure_forces, initiatives, mut interrupts, mut intents, mut context) = data;
+
+        //Intents that switch the game to another screen, such as opening an inventory, change
+        //this while they execute
+        *context.run_state = RunState::Ticking;
 
         //Collect all entities in order of intent
-        for (entity, initiative) in (&entities, &initiatives).join() {
+        let mut entities_to_handle = BinaryHeap::new();
+
+        for (entity, initiative) in (&context.entities, &initiatives).join() {
             entities_to_handle.push(IntentState::new(entity, initiative.current));
         }
 
         let mut queue_empty = true;
 
-        for intent_state in entities_to_handle.iter() {
+        while let Some(intent_state) = entities_to_handle.pop() {
             let entity = intent_state.entity;
-
-            let is_player = entity == *player;
-
-            //Handle possible interactions
-            {
-                if let Some(interact_intent) = interact_intents.get_mut(entity) {
-                    interact_intent.update_remaining_cost(-TIME_PER_TURN);
-
-                    macro_rules! handle_interaction_intent {
-                        ( $( $x:expr ),* ) => {
-                            {
-                                $(
-                                    if let Some(component) = $x.get_mut(interact_intent.target) {
-                                        if component.interaction_id == interact_intent.interaction_id {
-                                            component.interact();
-
-                                            if let Some(name) = names.get(interact_intent.target) {
-                                                game_log.entries.push(format!("{}: {}", name.name, interact_intent.interaction_description.clone()));
-                                            }
-                                            else {
-                                                game_log.entries.push("Invalid intent".to_string());
-                                            }
-                                            $x.remove(entity);
-                                        }
-                                    }
-
-                                    //If the interactable is powered, rebuild power state
-                                    if let Some(power_node) = power_nodes.get_mut(interact_intent.target) {
-                                        power_node.dirty = true;
-                                    }
-
-                                    //If the interactable is a breaker box, rebuild power state of all connected wires
-                                    if let Some(_) = breakers.get(interact_intent.target) {
-                                        if let Some(breaker_position) = positions.get(interact_intent.target) {
-                                            for (power_node, _) in (&mut power_nodes, &positions).join().filter(|(_, x)| *x == breaker_position) {
-                                               power_node.dirty = true;
-                                            }
-                                        }
-                                    }
-
-                                    //If the interaction is a container, open its inventory
-                                    if let Some(_) = containers.get(interact_intent.target) {
-
-                                    }
-
-
-                                )*
-                            }
-                        };
-                    }
-
-                    //If the interaction is finished execute it
-                    if interact_intent.get_remaining_cost() <= 0.0 {
-                        handle_interaction_intent!(power_switches, doors);
-                        interact_intents.remove(entity);
-                    }
-
-                    if is_player {
-                        queue_empty = false;
+            let is_player = entity == *context.player;
+
+            //Stop long actions when asked to or when the air starts rushing out
+            let reason = match interrupts.remove(entity) {
+                Some(interrupt) => Some(interrupt.reason),
+                None => context
+                    .positions
+                    .get(entity)
+                    .and_then(|position| pressure_forces.get(position))
+                    .filter(|force| force.horizontal_magnitude() >= INTERRUPT_PRESSURE_FORCE)
+                    .map(|_| "air rushes past".to_string()),
+            };
+
+            if let Some(reason) = reason {
+                let interrupted = intents.interrupt(entity, is_player);
+
+                if is_player {
+                    for description in interrupted.iter() {
+                        context.game_log.entries.push(format!(
+                            "{} interrupted, {} (C to continue)",
+                            description, reason
+                        ));
                     }
                 }
             }
 
-            //Handle possible movement
-            {
-                if let Some(move_intent) = move_intents.get_mut(entity) {
-                    move_intent.update_remaining_cost(-TIME_PER_TURN);
-
-                    if move_intent.get_remaining_cost() <= 0.0 {
-                        let current_position = move_intent.current_position;
-                        let delta = move_intent.delta;
-                        let mut target_position = Vector3i::new_equi(0);
-
-                        let mut movement_possible = true;
-
-                        let tile = map.tiles.get(&(current_position + delta));
-
-                        //Check tile blockers
-                        match tile {
-                            Some(tile) => {
-                                //TODO: Add exceptions here for if a player might need to move through solid tiles
-                                if !tile.passable {
-                                    movement_possible = false;
-                                }
-                            }
-                            _ => {
-                                movement_possible = false;
-                            }
-                        }
-
-                        if movement_possible {
-                            target_position = current_position + delta;
-                        }
-
-                        //If the movement is diagonal, blocks of four entites must be checked since the player passes through all four
-                        if check_entity_blocking(
-                            &blockers,
-                            &positions,
-                            current_position,
-                            target_position,
-                        ) {
-                            movement_possible = false;
-                        }
-
-                        if movement_possible {
-                            game_log.entries.push(target_position.to_string());
-
-                            //Update position
-                            if let Some(new_position) = positions.get_mut(entity) {
-                                *new_position = target_position;
-                            }
-
-                            if let Some(viewshed) = viewsheds.get_mut(*player) {
-                                viewshed.dirty = true;
-                            }
-
-                            if let Some(photometry) = photometria.get_mut(*player) {
-                                photometry.dirty = true;
-                            }
-
-                            if let Some(illuminant) = illuminants.get_mut(*player) {
-                                illuminant.dirty = true;
-                            }
-
-                            //Update player position tracker
-                            *stored_player_position = target_position;
-
-                            update_camera_position(delta, &cameras, &mut positions);
-                        }
-                        move_intents.remove(entity);
-                    }
+            let has_intent = intents.handle(entity, &mut context);
 
-                    if is_player {
-                        queue_empty = false;
-                    }
-                }
+            if has_intent && is_player {
+                queue_empty = false;
             }
+        }
 
-            //Handle pick up events
-            {
-                if let Some(pick_up_event) = pick_up_intents.get_mut(entity) {
-                    let mut pick_up_valid = true;
-                    let item = items.get(pick_up_event.target).unwrap();
-
-                    //TODO: Check item weight etc
-                    match containers.get(entity) {
-                        Some(container) => {
-                            if container.remaining_volume - item.volume < 0.0 {
-                                //Item is too large
-                                pick_up_valid = false;
-                                game_log.entries.push("Item is too large".to_string());
-                            }
-                        }
-                        None => {
-                            //Entity has no place to put item
-                            //TODO: Change this when clothes etc have storage
-                            pick_up_valid = false;
-                            game_log.entries.push("No place to store item".to_string());
-                        }
-                    }
-
-                    pick_up_event.update_remaining_cost(-TIME_PER_TURN);
-
-                    if pick_up_event.get_remaining_cost() <= 0.0 && pick_up_valid {
-                        let item_entity = pick_up_event.target.clone();
-                        let container = containers.get_mut(pick_up_event.initiator);
-                        //Add to container
-
-                        if let Some(container) = container {
-                            if pick_up_valid {
-                                let _ = in_container
-                                    .insert(pick_up_event.target, InContainer::new(container.id));
-
-                                //Remove position
-                                positions.remove(item_entity);
-
-                                //Remove intent
-                                pick_up_intents.remove(entity);
-
-                                //Update container
-                                container.try_insert_item(item.volume);
-                            }
-                        }
-                    }
-
-                    if !pick_up_valid {
-                        pick_up_intents.remove(entity);
-                    }
-
-                    if is_player {
-                        queue_empty = false;
-                    }
-                }
-            }
-
-            //Handle drop intents
-            {
-                if let Some(drop_event) = drop_intents.get_mut(entity) {
-                    let item = items.get(drop_event.target).unwrap();
-
-                    drop_event.update_remaining_cost(-TIME_PER_TURN);
-
-                    if drop_event.get_remaining_cost() <= 0.0 {
-                        let item_entity = drop_event.target.clone();
-                        let container = containers.get_mut(drop_event.initiator);
-                        //Add to container
-
-                        if let Some(container) = container {
-                            //Add position
-                            if let Some(position) = positions.get(drop_event.initiator) {
-                                let _ = positions.insert(item_entity, position.clone());
-                            }
-
-                            //Remove in container
-                            in_container.remove(item_entity);
+        //If all intents are handled, return to input state
+        if queue_empty && *context.run_state == RunState::Ticking {
+            *context.run_state = RunState::AwaitingInput;
+        }
+    }
+}
 
-                            //Remove intent
-                            drop_intents.remove(entity);
+//The entity's multi-turn action if it has one, as its description, progress and whether it is paused
+pub fn get_action_progress(ecs: &World, entity: Entity) -> Option<(String, f32, bool)> {
+    ecs.system_data::<IntentStorages>().action_progress(entity)
+}
 
-                            //Update container
-                            container.remove_item(item.volume);
-                        }
-                    }
+//Unpauses everything the entity had been interrupted in, returns whether there was anything
+pub fn continue_actions(ecs: &mut World, entity: Entity) -> bool {
+    let continued = ecs.system_data::<IntentStorages>().continue_paused(entity);
 
-                    if is_player {
-                        queue_empty = false;
-                    }
-                }
-            }
+    if !continued {
+        ecs.fetch_mut::<GameLog>()
+            .entries
+            .push("Nothing to continue".to_string());
+    }
+    continued
+}
 
-            //Handle open container events
-            {
-                if let Some(open_event) = open_intents.get_mut(entity) {
-                    open_event.update_remaining_cost(-TIME_PER_TURN);
-
-                    if open_event.get_remaining_cost() <= 0.0 {
-                        next_state = Some(RunState::ShowInventory {
-                            id: open_event.interaction_id,
-                            selected_item: None,
-                        });
-                        //Remove intent
-                        open_intents.remove(entity);
-                    }
+//Queues the intent an interaction picked from a menu stands for on the initiator
+pub type InteractionType = fn(&World, Entity, &InteractionInformation);
 
-                    if is_player {
-                        queue_empty = false;
-                    }
-                }
-            }
+//Turns the interaction into an intent of this type for the initiator
+pub fn queue_intent<I: Intent + Component>(
+    ecs: &World,
+    initiator: Entity,
+    interaction: &InteractionInformation,
+) {
+    let target = ecs.entities().entity(interaction.entity_id);
 
-            //If all intents are handled, return to input state
-            if let Some(state) = next_state.clone() {
-                *run_state = state;
-            } else if queue_empty {
-                *run_state = RunState::AwaitingInput;
-            } else {
-                *run_state = RunState::Ticking;
-            }
-        }
+    if let Some(intent) = I::from_interaction(initiator, target, interaction) {
+        let _ = ecs.write_storage::<I>().insert(initiator, intent);
     }
 }
-#[derive(Clone, Copy)]
-pub enum InteractionType {
-    Component,
-    PickUp,
-    Open,
-    Drop,
-}
 
 #[derive(Clone)]
 pub struct InteractionInformation {
@@ -393,6 +150,11 @@ impl InteractionInformation {
             interaction_type,
         }
     }
+
+    //Queues the intent the interaction stands for on the initiator
+    pub fn queue(&self, ecs: &World, initiator: Entity) {
+        (self.interaction_type)(ecs, initiator, self);
+    }
 }
 
 pub fn get_entity_interactions(ecs: &World, entity: Entity) -> Vec<InteractionInformation> {
@@ -406,7 +168,7 @@ pub fn get_entity_interactions(ecs: &World, entity: Entity) -> Vec<InteractionIn
     }
 
     macro_rules! check_for_interactable {
-        ($($typ:ty), *) => {
+        ($( $storage:ident: $typ:ty ),*) => {
             {
                 $(
                     let storage = ecs.read_storage::<$typ>();
@@ -417,7 +179,7 @@ pub fn get_entity_interactions(ecs: &World, entity: Entity) -> Vec<InteractionIn
                             format!("{} ({}): {}", name, interactable.state_description(), interactable.interaction_description),
                             entity.id(),
                             interactable.get_cost(),
-                            InteractionType::Component,
+                            queue_intent::<InteractIntent>,
                         ));
                     }
                 )*
@@ -425,59 +187,13 @@ pub fn get_entity_interactions(ecs: &World, entity: Entity) -> Vec<InteractionIn
         };
     }
 
-    //TODO: Add any other interactable components
-    check_for_interactable!(PowerSwitch, Door);
+    with_interactables!(check_for_interactable);
 
     interactables
 }
 
 pub fn get_default_interactions(ecs: &World, entity: Entity) -> Vec<InteractionInformation> {
-    let mut interactions = Vec::new();
-    let installed = ecs.read_storage::<Installed>();
-    let containers = ecs.read_storage::<Container>();
-    let in_containers = ecs.read_storage::<InContainer>();
-
-    //Item pickup
-    {
-        //If the item is not installed, allow pickup
-        if installed.get(entity).is_none() {
-            interactions.push(InteractionInformation::new(
-                entity.id(),
-                format!("{}", "Pick up".to_string()),
-                entity.id(),
-                1.0,
-                InteractionType::PickUp,
-            ));
-        }
-    }
-
-    //If item is in a container, give an option to drop it
-    {
-        if in_containers.get(entity).is_some() {
-            interactions.push(InteractionInformation::new(
-                entity.id(),
-                format!("{}", "Drop".to_string()),
-                entity.id(),
-                1.0,
-                InteractionType::Drop,
-            ));
-        }
-    }
-
-    //Open container
-    {
-        if let Some(container) = containers.get(entity) {
-            interactions.push(InteractionInformation::new(
-                container.id,
-                format!("{}", "Open".to_string()),
-                entity.id(),
-                1.0,
-                InteractionType::Open,
-            ));
-        }
-    }
-
-    interactions
+    IntentStorages::interactions(ecs, entity)
 }
 
 //#[derive(Serialize, Deserialize, Clone)]
@@ -681,39 +397,7 @@ fn is_entity_blocked(
         .join()
         .filter(|x| *x.1 == player_position)
     {
-        let delta = (target_position - player_position).normalize_delta();
-
-        if delta == Vector3i::N && blocker.sides.contains(&crate::Direction::N) {
-            return true;
-        } else if delta == Vector3i::NW
-            && (blocker.sides.contains(&crate::Direction::N)
-                || blocker.sides.contains(&crate::Direction::W))
-        {
-            return true;
-        } else if delta == Vector3i::W && blocker.sides.contains(&crate::Direction::W) {
-            return true;
-        } else if delta == Vector3i::SW
-            && (blocker.sides.contains(&crate::Direction::S)
-                || blocker.sides.contains(&crate::Direction::W))
-        {
-            return true;
-        } else if delta == Vector3i::S && blocker.sides.contains(&crate::Direction::S) {
-            return true;
-        } else if delta == Vector3i::SE
-            && (blocker.sides.contains(&crate::Direction::S)
-                || blocker.sides.contains(&crate::Direction::E))
-        {
-            return true;
-        } else if delta == Vector3i::E && blocker.sides.contains(&crate::Direction::E) {
-            return true;
-        } else if delta == Vector3i::NE
-            && (blocker.sides.contains(&crate::Direction::N)
-                || blocker.sides.contains(&crate::Direction::E))
-        {
-            return true;
-        } else if delta == Vector3i::UP && blocker.sides.contains(&crate::Direction::UP) {
-            return true;
-        } else if delta == Vector3i::DOWN && blocker.sides.contains(&crate::Direction::DOWN) {
+        if side_blocks(&blocker.sides, (target_position - player_position).normalize_delta()) {
             return true;
         }
     }
@@ -723,41 +407,36 @@ fn is_entity_blocked(
         .join()
         .filter(|x| *x.1 == target_position)
     {
-        let delta = (player_position - target_position).normalize_delta();
-
-        if delta == Vector3i::N && blocker.sides.contains(&crate::Direction::N) {
-            return true;
-        } else if delta == Vector3i::NW
-            && (blocker.sides.contains(&crate::Direction::N)
-                || blocker.sides.contains(&crate::Direction::W))
-        {
-            return true;
-        } else if delta == Vector3i::W && blocker.sides.contains(&crate::Direction::W) {
-            return true;
-        } else if delta == Vector3i::SW
-            && (blocker.sides.contains(&crate::Direction::S)
-                || blocker.sides.contains(&crate::Direction::W))
-        {
-            return true;
-        } else if delta == Vector3i::S && blocker.sides.contains(&crate::Direction::S) {
-            return true;
-        } else if delta == Vector3i::SE
-            && (blocker.sides.contains(&crate::Direction::S)
-                || blocker.sides.contains(&crate::Direction::E))
-        {
-            return true;
-        } else if delta == Vector3i::E && blocker.sides.contains(&crate::Direction::E) {
-            return true;
-        } else if delta == Vector3i::NE
-            && (blocker.sides.contains(&crate::Direction::N)
-                || blocker.sides.contains(&crate::Direction::E))
-        {
-            return true;
-        } else if delta == Vector3i::UP && blocker.sides.contains(&crate::Direction::UP) {
-            return true;
-        } else if delta == Vector3i::DOWN && blocker.sides.contains(&crate::Direction::DOWN) {
+        if side_blocks(&blocker.sides, (player_position - target_position).normalize_delta()) {
             return true;
         }
     }
-    return false;
+    false
+}
+
+//Whether a blocker with the given sides stops anything leaving its tile in the direction of delta
+pub fn side_blocks(sides: &[Direction], delta: Vector3i) -> bool {
+    if delta == Vector3i::N {
+        sides.contains(&Direction::N)
+    } else if delta == Vector3i::NW {
+        sides.contains(&Direction::N) || sides.contains(&Direction::W)
+    } else if delta == Vector3i::W {
+        sides.contains(&Direction::W)
+    } else if delta == Vector3i::SW {
+        sides.contains(&Direction::S) || sides.contains(&Direction::W)
+    } else if delta == Vector3i::S {
+        sides.contains(&Direction::S)
+    } else if delta == Vector3i::SE {
+        sides.contains(&Direction::S) || sides.contains(&Direction::E)
+    } else if delta == Vector3i::E {
+        sides.contains(&Direction::E)
+    } else if delta == Vector3i::NE {
+        sides.contains(&Direction::N) || sides.contains(&Direction::E)
+    } else if delta == Vector3i::UP {
+        sides.contains(&Direction::UP)
+    } else if delta == Vector3i::DOWN {
+        sides.contains(&Direction::DOWN)
+    } else {
+        false
+    }
 }
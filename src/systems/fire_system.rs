@@ -0,0 +1,325 @@
+use std::collections::{HashMap, HashSet};
+
+use specs::prelude::*;
+
+use crate::{
+    entities::{
+        atmospherics::Gas,
+        biology::{VitalState, Vitals},
+        fire::{Burning, Fire, Flammable},
+    },
+    gamelog::GameLog,
+    map::structure::{DamageQueue, DamageType},
+    raws::spawn_named,
+    vectors::{utils::get_cardinal_neighbours_with_z, Vector3i},
+    Health, Illuminant, Map, Name,
+};
+
+use super::atmosphere_system::get_accessible_neighbours;
+
+//Flammable gasses catch once the tile is this hot
+const GAS_IGNITION_TEMPERATURE: f32 = 573.15;
+//Below this oxygen partial pressure in pascal fires starve
+pub const MIN_OXYGEN_PRESSURE: f32 = 10000.0;
+//Most oxygen a single tile can burn per tick, in mols
+const OXYGEN_BURN_RATE: f32 = 0.2;
+//Share of each flammable gas that can burn per tick, scaled by its flammability
+const GAS_BURN_RATIO: f32 = 0.5;
+//Joules released per mol of oxygen burnt
+pub const HEAT_PER_MOL_OXYGEN: f32 = 400000.0;
+//Share of the released heat that goes into neighbouring tiles
+const SPREAD_HEAT_RATIO: f32 = 0.25;
+//Health lost per tick standing in a fire at full intensity
+const FIRE_DAMAGE: f32 = 5.0;
+//Damage done to each surrounding tile per tick at full intensity, before the material resists it
+const STRUCTURE_FIRE_DAMAGE: f32 = 2.0;
+
+pub struct FireSystem {}
+
+impl<'a> System<'a> for FireSystem {
+    type SystemData = (
+        WriteExpect<'a, Map>,
+        WriteExpect<'a, GameLog>,
+        WriteExpect<'a, DamageQueue>,
+        WriteStorage<'a, Fire>,
+        WriteStorage<'a, Flammable>,
+        WriteStorage<'a, Health>,
+        WriteStorage<'a, Burning>,
+        WriteStorage<'a, Vitals>,
+        WriteStorage<'a, Illuminant>,
+        ReadStorage<'a, Vector3i>,
+        ReadStorage<'a, Name>,
+        Read<'a, LazyUpdate>,
+        Entities<'a>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            mut map,
+            mut game_log,
+            mut damage_queue,
+            mut fires,
+            mut flammables,
+            mut healths,
+            mut burning_entities,
+            mut vitals,
+            mut illuminants,
+            positions,
+            names,
+            lazy_update,
+            entities,
+        ) = data;
+
+        //Fires that went out last tick have had their light cleared and can go
+        let mut fire_entities: HashMap<Vector3i, Entity> = HashMap::new();
+        for (entity, fire, illuminant, position) in
+            (&entities, &fires, &illuminants, &positions).join()
+        {
+            if fire.intensity <= 0.0 && !illuminant.on && !illuminant.dirty {
+                let _ = entities.delete(entity);
+            } else {
+                fire_entities.insert(*position, entity);
+            }
+        }
+
+        let mut flammable_entities: HashMap<Vector3i, Vec<Entity>> = HashMap::new();
+        for (entity, _, position) in (&entities, &flammables, &positions).join() {
+            flammable_entities.entry(*position).or_default().push(entity);
+        }
+
+        //Tiles that are already burning, hot flammable gas, or hot flammable props
+        let mut candidates: HashSet<Vector3i> = fire_entities
+            .iter()
+            .filter(|(_, entity)| {
+                fires
+                    .get(**entity)
+                    .map(|fire| fire.intensity > 0.0)
+                    .unwrap_or(false)
+            })
+            .map(|(position, _)| *position)
+            .collect();
+
+        for (position, tile) in map.tiles.iter() {
+            if tile.atmosphere.temperature >= GAS_IGNITION_TEMPERATURE
+                && tile
+                    .atmosphere
+                    .gasses
+                    .keys()
+                    .any(|gas| gas.properties().flammability > 0.0)
+            {
+                candidates.insert(*position);
+            }
+        }
+
+        for (position, props) in flammable_entities.iter() {
+            if let Some(tile) = map.tiles.get(position) {
+                if props.iter().any(|entity| {
+                    flammables
+                        .get(*entity)
+                        .map(|flammable| {
+                            tile.atmosphere.temperature >= flammable.ignition_temperature
+                        })
+                        .unwrap_or(false)
+                }) {
+                    candidates.insert(*position);
+                }
+            }
+        }
+
+        let mut burning: HashMap<Vector3i, f32> = HashMap::new();
+        let mut burnt_props = Vec::new();
+
+        for position in candidates.into_iter() {
+            let already_burning = fire_entities
+                .get(&position)
+                .and_then(|entity| fires.get(*entity))
+                .map(|fire| fire.intensity > 0.0)
+                .unwrap_or(false);
+
+            let heat = match map.tiles.get_mut(&position) {
+                Some(tile) => {
+                    let atmosphere = &mut tile.atmosphere;
+
+                    if atmosphere.get_partial_pressure(Gas::Oxygen) < MIN_OXYGEN_PRESSURE {
+                        0.0
+                    } else {
+                        let mut oxygen_budget = atmosphere
+                            .gasses
+                            .get(&Gas::Oxygen)
+                            .cloned()
+                            .unwrap_or(0.0)
+                            .min(OXYGEN_BURN_RATE);
+                        let mut oxygen_used = 0.0;
+                        let mut burnt_gasses: HashMap<Gas, f32> = HashMap::new();
+                        let mut products: HashMap<Gas, f32> = HashMap::new();
+
+                        //Flammable gasses burn two mols to one of oxygen
+                        for gas in Gas::ALL.iter() {
+                            let flammability = gas.properties().flammability;
+
+                            if flammability <= 0.0 {
+                                continue;
+                            }
+
+                            let mols = atmosphere.gasses.get(gas).cloned().unwrap_or(0.0);
+                            let burnt = (mols * flammability * GAS_BURN_RATIO)
+                                .min(oxygen_budget * 2.0);
+
+                            if burnt <= 0.0 {
+                                continue;
+                            }
+
+                            oxygen_budget -= burnt / 2.0;
+                            oxygen_used += burnt / 2.0;
+                            burnt_gasses.insert(*gas, burnt);
+
+                            if let Some(product) = gas.combustion_product() {
+                                *products.entry(product).or_insert(0.0) += burnt;
+                            }
+                        }
+
+                        //Props burn one mol of oxygen for each unit of fuel
+                        if let Some(props) = flammable_entities.get(&position) {
+                            for entity in props.iter() {
+                                if let Some(flammable) = flammables.get_mut(*entity) {
+                                    let lit = already_burning
+                                        || oxygen_used > 0.0
+                                        || atmosphere.temperature >= flammable.ignition_temperature;
+
+                                    if !lit || oxygen_budget <= 0.0 {
+                                        continue;
+                                    }
+
+                                    let burnt = flammable.fuel.min(oxygen_budget);
+
+                                    flammable.fuel -= burnt;
+                                    oxygen_budget -= burnt;
+                                    oxygen_used += burnt;
+                                    *products.entry(Gas::CarbonDioxide).or_insert(0.0) += burnt;
+
+                                    if flammable.fuel <= 0.0 {
+                                        burnt_props.push(*entity);
+                                    }
+                                }
+                            }
+                        }
+
+                        for (gas, mols) in burnt_gasses.iter() {
+                            atmosphere.remove_single_gas(*gas, *mols);
+                        }
+                        atmosphere.remove_single_gas(Gas::Oxygen, oxygen_used);
+
+                        let temperature = atmosphere.temperature;
+                        atmosphere.update_gas(&products, temperature);
+
+                        let heat = oxygen_used * HEAT_PER_MOL_OXYGEN;
+                        atmosphere.add_heat(heat * (1.0 - SPREAD_HEAT_RATIO));
+                        atmosphere.dirty = true;
+
+                        heat
+                    }
+                }
+                None => continue,
+            };
+
+            if heat > 0.0 {
+                burning.insert(position, heat);
+            }
+        }
+
+        //Some of the heat spills into the open neighbours, which may catch in turn
+        for (position, heat) in burning.iter() {
+            let neighbours = get_accessible_neighbours(&map, position, false);
+
+            if neighbours.is_empty() {
+                continue;
+            }
+
+            let spread_heat = heat * SPREAD_HEAT_RATIO / neighbours.len() as f32;
+
+            for neighbour in neighbours.iter() {
+                if let Some(tile) = map.tiles.get_mut(neighbour) {
+                    tile.atmosphere.add_heat(spread_heat);
+                    tile.atmosphere.dirty = true;
+                }
+            }
+        }
+
+        //Walls, floors and ceilings around a fire slowly weaken
+        for (position, heat) in burning.iter() {
+            let intensity = (heat / (HEAT_PER_MOL_OXYGEN * OXYGEN_BURN_RATE)).min(1.0);
+
+            for neighbour in get_cardinal_neighbours_with_z(*position).into_iter() {
+                damage_queue.damage(
+                    neighbour,
+                    DamageType::Fire,
+                    STRUCTURE_FIRE_DAMAGE * intensity,
+                );
+            }
+        }
+
+        for entity in burnt_props.into_iter() {
+            if let Some(name) = names.get(entity) {
+                game_log.entries.push(format!("{} burns away", name.name));
+            }
+            let _ = entities.delete(entity);
+        }
+
+        //Keep the fire entities in step with the burning tiles
+        for (position, entity) in fire_entities.iter() {
+            let intensity = burning
+                .get(position)
+                .map(|heat| (heat / (HEAT_PER_MOL_OXYGEN * OXYGEN_BURN_RATE)).min(1.0))
+                .unwrap_or(0.0);
+
+            if let Some(fire) = fires.get_mut(*entity) {
+                fire.intensity = intensity;
+            }
+            if let Some(illuminant) = illuminants.get_mut(*entity) {
+                illuminant.set_state(intensity > 0.0);
+            }
+        }
+
+        for position in burning.keys() {
+            if !fire_entities.contains_key(position) {
+                let position = *position;
+                lazy_update.exec_mut(move |ecs| {
+                    let _ = spawn_named(ecs, "fire", position);
+                });
+            }
+        }
+
+        for (entity, health, position) in (&entities, &mut healths, &positions).join() {
+            let heat = match burning.get(position) {
+                Some(heat) => heat,
+                None => {
+                    burning_entities.remove(entity);
+                    continue;
+                }
+            };
+
+            let intensity = (heat / (HEAT_PER_MOL_OXYGEN * OXYGEN_BURN_RATE)).min(1.0);
+            health.damage(FIRE_DAMAGE * intensity);
+
+            if burning_entities.get(entity).is_none() {
+                let _ = burning_entities.insert(entity, Burning {});
+
+                if let Some(name) = names.get(entity) {
+                    game_log.entries.push(format!("{} is burned", name.name));
+                }
+            }
+
+            if health.current <= 0.0 {
+                if let Some(entity_vitals) = vitals.get_mut(entity) {
+                    if entity_vitals.state != VitalState::Dead {
+                        entity_vitals.state = VitalState::Dead;
+
+                        if let Some(name) = names.get(entity) {
+                            game_log.entries.push(format!("{} dies", name.name));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
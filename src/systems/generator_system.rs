@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+
+use specs::prelude::*;
+
+use crate::{
+    clock::{EventScheduler, GameClock, ScheduledEvent, SECONDS_PER_TURN},
+    entities::{
+        atmospherics::Gas,
+        network::AlarmQueue,
+        power_components::{Fuel, Generator, GeneratorFuel},
+    },
+    gamelog::GameLog,
+    vectors::Vector3i,
+    Container, InContainer, Item, Map, Name, PowerNode, PowerSource, PowerSwitch,
+};
+
+use super::fire_system::{HEAT_PER_MOL_OXYGEN, MIN_OXYGEN_PRESSURE};
+
+//Output has to move by more than this before the power network is solved again
+const OUTPUT_TOLERANCE: f32 = 1.0;
+//Turns a generator takes to spin up once it notices it has been switched on
+pub const SPIN_UP_TURNS: u64 = 5;
+
+//Burns fuel at each generator's throttle and hands the electricity to its power source, the
+//waste heat and exhaust go into the generator's tile
+pub struct GeneratorSystem {}
+
+impl<'a> System<'a> for GeneratorSystem {
+    type SystemData = (
+        WriteExpect<'a, Map>,
+        WriteExpect<'a, GameLog>,
+        WriteExpect<'a, AlarmQueue>,
+        ReadExpect<'a, GameClock>,
+        WriteExpect<'a, EventScheduler>,
+        WriteStorage<'a, Generator>,
+        WriteStorage<'a, PowerSource>,
+        WriteStorage<'a, PowerNode>,
+        ReadStorage<'a, PowerSwitch>,
+        ReadStorage<'a, Fuel>,
+        WriteStorage<'a, InContainer>,
+        WriteStorage<'a, Container>,
+        ReadStorage<'a, Item>,
+        ReadStorage<'a, Vector3i>,
+        ReadStorage<'a, Name>,
+        Entities<'a>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            mut map,
+            mut game_log,
+            mut alarm_queue,
+            clock,
+            mut scheduler,
+            mut generators,
+            mut power_sources,
+            mut nodes,
+            power_switches,
+            fuels,
+            mut in_containers,
+            mut containers,
+            items,
+            positions,
+            names,
+            entities,
+        ) = data;
+
+        let spun_up: Vec<ScheduledEvent> = scheduler
+            .take_due_where(clock.turn, |event| matches!(event, ScheduledEvent::SpinUp(_)));
+
+        for (generator, power_source, node, position, entity) in (
+            &mut generators,
+            &mut power_sources,
+            &mut nodes,
+            &positions,
+            &entities,
+        )
+            .join()
+        {
+            let switched_on = match power_switches.get(entity) {
+                Some(power_switch) => power_switch.on,
+                None => true,
+            };
+            let spin_up = ScheduledEvent::SpinUp(*position);
+
+            if !switched_on {
+                generator.online = false;
+                scheduler.cancel(&spin_up);
+            } else if !generator.online {
+                if spun_up.contains(&spin_up) {
+                    generator.online = true;
+
+                    if let Some(name) = names.get(entity) {
+                        game_log
+                            .entries
+                            .push(format!("{} spins up to speed", name.name));
+                    }
+                } else if scheduler.turn_of(&spin_up).is_none() {
+                    scheduler.schedule_in_turns(&clock, SPIN_UP_TURNS, spin_up);
+                }
+            }
+
+            //Joules of fuel it takes to run at the throttle this tick
+            let wanted = if switched_on && generator.online {
+                generator.target_output() * SECONDS_PER_TURN / generator.efficiency
+            } else {
+                0.0
+            };
+
+            //Feed the next fuel items from the hopper once the last one is nearly spent
+            if generator.fuel == GeneratorFuel::Item && generator.burning < wanted {
+                if let Some(hopper) = containers.get_mut(entity) {
+                    let loaded: Vec<(Entity, f32, f32)> =
+                        (&entities, &fuels, &in_containers, &items)
+                            .join()
+                            .filter(|(_, _, in_container, _)| in_container.owner == hopper.id)
+                            .map(|(fuel_entity, fuel, _, item)| {
+                                (fuel_entity, fuel.energy, item.volume)
+                            })
+                            .collect();
+
+                    for (fuel_entity, energy, volume) in loaded {
+                        if generator.burning >= wanted {
+                            break;
+                        }
+
+                        generator.burning += energy;
+                        hopper.remove_item(volume);
+                        in_containers.remove(fuel_entity);
+                        let _ = entities.delete(fuel_entity);
+                    }
+                }
+            }
+
+            let tile = match map.tiles.get_mut(position) {
+                Some(tile) => tile,
+                None => continue,
+            };
+
+            //Nothing burns without enough oxygen around it
+            let starved = tile.atmosphere.get_partial_pressure(Gas::Oxygen) < MIN_OXYGEN_PRESSURE;
+            let oxygen = tile
+                .atmosphere
+                .gasses
+                .get(&Gas::Oxygen)
+                .copied()
+                .unwrap_or(0.0);
+            let mut burnt = if starved {
+                0.0
+            } else {
+                wanted.min(oxygen * HEAT_PER_MOL_OXYGEN)
+            };
+            let mut exhaust = HashMap::new();
+
+            match generator.fuel {
+                GeneratorFuel::Item => {
+                    burnt = burnt.min(generator.burning);
+                    generator.burning -= burnt;
+                    exhaust.insert(Gas::CarbonDioxide, burnt / HEAT_PER_MOL_OXYGEN);
+                }
+                //Two hydrogen to every oxygen, water comes out
+                GeneratorFuel::Hydrogen => {
+                    let hydrogen = tile
+                        .atmosphere
+                        .gasses
+                        .get(&Gas::Hydrogen)
+                        .copied()
+                        .unwrap_or(0.0);
+                    burnt = burnt.min(hydrogen / 2.0 * HEAT_PER_MOL_OXYGEN);
+
+                    let hydrogen_used = burnt / HEAT_PER_MOL_OXYGEN * 2.0;
+                    tile.atmosphere
+                        .remove_single_gas(Gas::Hydrogen, hydrogen_used);
+                    exhaust.insert(Gas::WaterVapour, hydrogen_used);
+                }
+            }
+
+            if burnt > 0.0 {
+                tile.atmosphere
+                    .remove_single_gas(Gas::Oxygen, burnt / HEAT_PER_MOL_OXYGEN);
+                let temperature = tile.atmosphere.temperature;
+                tile.atmosphere.update_gas(&exhaust, temperature);
+                tile.add_heat(burnt * (1.0 - generator.efficiency));
+            }
+
+            let output = burnt * generator.efficiency / SECONDS_PER_TURN;
+
+            if switched_on && generator.output > 0.0 && output <= 0.0 {
+                let name = match names.get(entity) {
+                    Some(name) => name.name.clone(),
+                    None => "Generator".to_string(),
+                };
+
+                let message = if starved {
+                    format!("{} chokes for lack of oxygen", name)
+                } else {
+                    format!("{} runs out of fuel", name)
+                };
+                alarm_queue.raise(*position, message.clone());
+                game_log.entries.push(message);
+            }
+            generator.output = output;
+
+            //The power network only needs solving again when the output really changed
+            if (power_source.max_wattage - output).abs() > OUTPUT_TOLERANCE
+                || (output == 0.0 && power_source.max_wattage != 0.0)
+            {
+                power_source.max_wattage = output;
+                node.dirty = true;
+            }
+        }
+    }
+}
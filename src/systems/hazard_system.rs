@@ -0,0 +1,239 @@
+use specs::prelude::*;
+
+use crate::{
+    clock::{EventScheduler, GameClock, ScheduledEvent},
+    entities::{
+        atmospherics::Gas,
+        power_components::{
+            Broken, ControlPanel, ElectronicHeater, PowerNode, PowerSwitch, PoweredState,
+        },
+    },
+    gamelog::GameLog,
+    hazards::{Hazard, HazardDirector},
+    map::structure::{DamageQueue, DamageType},
+    rng,
+    vectors::{utils::get_cardinal_neighbours, Vector3i},
+    Illuminant, Map,
+};
+
+//Tiles with less gas than this are not worth breaching or leaking into
+const MIN_ROOM_MOLS: f32 = 1.0;
+//Gas released by a leak before severity scaling
+const LEAK_MOLS: f32 = 20.0;
+//Enough to punch through a steel hull section at the start
+const IMPACT_DAMAGE: f32 = 500.0;
+//Turns between the hull groaning and the strike that breaches it
+pub const BREACH_WARNING_TURNS: u64 = 5;
+const LEAK_GASSES: [Gas; 3] = [Gas::CarbonMonoxide, Gas::Hydrogen, Gas::CarbonDioxide];
+
+//Sets off emergencies picked by the hazard director once they come due
+pub struct HazardSystem {}
+
+impl<'a> System<'a> for HazardSystem {
+    type SystemData = (
+        (
+            WriteExpect<'a, Map>,
+            WriteExpect<'a, DamageQueue>,
+            ReadExpect<'a, GameClock>,
+            WriteExpect<'a, EventScheduler>,
+            ReadExpect<'a, HazardDirector>,
+            WriteExpect<'a, GameLog>,
+        ),
+        ReadStorage<'a, Vector3i>,
+        ReadStorage<'a, ControlPanel>,
+        WriteStorage<'a, PowerSwitch>,
+        WriteStorage<'a, PowerNode>,
+        ReadStorage<'a, Illuminant>,
+        ReadStorage<'a, PoweredState>,
+        ReadStorage<'a, ElectronicHeater>,
+        WriteStorage<'a, Broken>,
+        Entities<'a>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            (mut map, mut damage_queue, clock, mut scheduler, director, mut game_log),
+            positions,
+            control_panels,
+            mut power_switches,
+            mut power_nodes,
+            illuminants,
+            powered_states,
+            electronic_heaters,
+            mut broken,
+            entities,
+        ) = data;
+
+        //Strikes that were on their way land even if the director has been switched off
+        for impact in scheduler.take_due_where(clock.turn, |event| {
+            matches!(event, ScheduledEvent::Impact { .. })
+        }) {
+            if let ScheduledEvent::Impact { position, damage } = impact {
+                damage_queue.damage(position, DamageType::Impact, damage);
+            }
+        }
+
+        if !director.enabled {
+            return;
+        }
+
+        let due = !scheduler
+            .take_due_where(clock.turn, |event| *event == ScheduledEvent::Hazard)
+            .is_empty();
+
+        if !due {
+            //Nothing waiting means the voyage has only just started
+            if scheduler.turn_of(&ScheduledEvent::Hazard).is_none() {
+                scheduler.schedule_at_turn(
+                    HazardDirector::first_turn(clock.turn),
+                    ScheduledEvent::Hazard,
+                );
+            }
+            return;
+        }
+
+        scheduler.schedule_at_turn(
+            HazardDirector::next_turn(clock.turn),
+            ScheduledEvent::Hazard,
+        );
+
+        let hazard = match director.pick() {
+            Some(hazard) => hazard,
+            None => return,
+        };
+
+        //Nothing suitable on the ship means the ship got lucky this time
+        let happened = match hazard {
+            //A micrometeoroid strike after the hull gives a warning, the structural system
+            //decides what is left
+            Hazard::HullBreach => match pick_breach(&map) {
+                Some(position) => {
+                    scheduler.schedule_in_turns(
+                        &clock,
+                        BREACH_WARNING_TURNS,
+                        ScheduledEvent::Impact {
+                            position,
+                            damage: IMPACT_DAMAGE * HazardDirector::severity(clock.turn),
+                        },
+                    );
+                    true
+                }
+                None => false,
+            },
+            Hazard::BreakerTrip => {
+                let candidates: Vec<(Entity, Vector3i)> =
+                    (&entities, &control_panels, &power_switches, &positions)
+                        .join()
+                        .filter(|(_, _, power_switch, _)| power_switch.on)
+                        .map(|(entity, _, _, position)| (entity, *position))
+                        .collect();
+
+                match pick(&candidates) {
+                    Some((entity, breaker_position)) => {
+                        if let Some(power_switch) = power_switches.get_mut(*entity) {
+                            power_switch.toggle();
+                        }
+
+                        for (power_node, _) in (&mut power_nodes, &positions)
+                            .join()
+                            .filter(|(_, position)| *position == breaker_position)
+                        {
+                            power_node.dirty = true;
+                        }
+                        true
+                    }
+                    None => false,
+                }
+            }
+            Hazard::LampFailure => {
+                let candidates: Vec<Entity> = (
+                    &entities,
+                    &illuminants,
+                    &powered_states,
+                    &power_nodes,
+                    !&electronic_heaters,
+                    !&broken,
+                )
+                    .join()
+                    .map(|(entity, ..)| entity)
+                    .collect();
+
+                match pick(&candidates) {
+                    Some(entity) => {
+                        let _ = broken.insert(*entity, Broken {});
+
+                        if let Some(power_node) = power_nodes.get_mut(*entity) {
+                            power_node.dirty = true;
+                        }
+                        true
+                    }
+                    None => false,
+                }
+            }
+            Hazard::GasLeak => {
+                let mut candidates: Vec<Vector3i> = map
+                    .tiles
+                    .iter()
+                    .filter(|(_, tile)| {
+                        tile.passable && tile.atmosphere.get_total_mols() >= MIN_ROOM_MOLS
+                    })
+                    .map(|(position, _)| *position)
+                    .collect();
+                candidates.sort();
+
+                match pick(&candidates) {
+                    Some(position) => {
+                        let gas = LEAK_GASSES[rng::range(0, LEAK_GASSES.len() as i32) as usize];
+                        let mols = LEAK_MOLS * HazardDirector::severity(clock.turn);
+
+                        if let Some(tile) = map.tiles.get_mut(position) {
+                            let temperature = tile.atmosphere.temperature;
+                            tile.atmosphere
+                                .update_gas(&[(gas, mols)].into_iter().collect(), temperature);
+                        }
+                        true
+                    }
+                    None => false,
+                }
+            }
+        };
+
+        if happened {
+            game_log.entries.push(hazard.message().to_string());
+        }
+    }
+}
+
+fn pick<T>(candidates: &[T]) -> Option<&T> {
+    if candidates.is_empty() {
+        return None;
+    }
+
+    candidates.get(rng::range(0, candidates.len() as i32) as usize)
+}
+
+//Outer hull with a pressurised room on one side and space on another
+fn pick_breach(map: &Map) -> Option<Vector3i> {
+    let is_space = |position: &Vector3i| match map.tiles.get(position) {
+        Some(tile) => tile.passable && tile.atmosphere.get_total_mols() < MIN_ROOM_MOLS,
+        None => true,
+    };
+    let is_room = |position: &Vector3i| match map.tiles.get(position) {
+        Some(tile) => tile.passable && tile.atmosphere.get_total_mols() >= MIN_ROOM_MOLS,
+        None => false,
+    };
+
+    let mut candidates: Vec<Vector3i> = map
+        .tiles
+        .iter()
+        .filter(|(_, tile)| !tile.passable && tile.airtight)
+        .map(|(position, _)| *position)
+        .filter(|position| {
+            get_cardinal_neighbours(*position).iter().any(is_room)
+                && get_cardinal_neighbours(*position).iter().any(is_space)
+        })
+        .collect();
+    candidates.sort();
+
+    pick(&candidates).copied()
+}
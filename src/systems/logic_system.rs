@@ -0,0 +1,223 @@
+use std::collections::{HashMap, HashSet};
+
+use specs::prelude::*;
+
+use crate::{
+    clock::{EventScheduler, GameClock, ScheduledEvent},
+    entities::{
+        biology::{VitalState, Vitals},
+        logic::{DelayTimer, LogicGate, Sensor, SensorKind, Signal},
+        network::NetworkAddress,
+        power_graph::PowerGraph,
+    },
+    vectors::Vector3i,
+    Door, Map, PowerNode, PowerSwitch,
+};
+
+//Reads the sensors, works the gates and timers through, then lets every signal that changed
+//drive its target. Signals take a tick to pass through each gate
+pub struct LogicSystem {}
+
+impl<'a> System<'a> for LogicSystem {
+    type SystemData = (
+        ReadExpect<'a, Map>,
+        ReadExpect<'a, PowerGraph>,
+        ReadExpect<'a, GameClock>,
+        WriteExpect<'a, EventScheduler>,
+        WriteStorage<'a, Signal>,
+        WriteStorage<'a, Sensor>,
+        ReadStorage<'a, LogicGate>,
+        WriteStorage<'a, DelayTimer>,
+        ReadStorage<'a, NetworkAddress>,
+        WriteStorage<'a, PowerSwitch>,
+        WriteStorage<'a, PowerNode>,
+        WriteStorage<'a, Door>,
+        ReadStorage<'a, Vitals>,
+        ReadStorage<'a, Vector3i>,
+        Entities<'a>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            map,
+            power_graph,
+            clock,
+            mut scheduler,
+            mut signals,
+            mut sensors,
+            logic_gates,
+            mut delay_timers,
+            addresses,
+            mut power_switches,
+            mut nodes,
+            mut doors,
+            vitals,
+            positions,
+            entities,
+        ) = data;
+
+        let addressed: Vec<(u32, Entity, Vector3i)> = (&addresses, &entities, &positions)
+            .join()
+            .map(|(address, entity, position)| (address.address, entity, *position))
+            .collect();
+
+        //What each output can reach over the data wiring, outputs on the same wiring share a search
+        let mut networks: Vec<HashSet<Vector3i>> = Vec::new();
+
+        for (signal, position, entity) in (&mut signals, &positions, &entities).join() {
+            let index = match networks
+                .iter()
+                .position(|network| network.contains(position))
+            {
+                Some(index) => index,
+                None => {
+                    networks.push(power_graph.data_network(*position));
+                    networks.len() - 1
+                }
+            };
+            let network = &networks[index];
+            let mut reachable: Vec<u32> = if power_graph.has_data(position) {
+                addressed
+                    .iter()
+                    .filter(|(_, other, other_position)| {
+                        *other != entity && network.contains(other_position)
+                    })
+                    .map(|(address, _, _)| *address)
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            reachable.sort();
+
+            if reachable != signal.reachable {
+                signal.set_reachable(reachable);
+            }
+        }
+
+        //Last tick's outputs are this tick's inputs
+        let mut inputs: HashMap<u32, Vec<bool>> = HashMap::new();
+
+        for signal in signals.join() {
+            if let Some(target) = signal.linked_target() {
+                inputs.entry(target).or_default().push(signal.on);
+            }
+        }
+
+        let mut outputs: Vec<(Entity, bool)> = Vec::new();
+
+        for (sensor, position, entity) in (&mut sensors, &positions, &entities).join() {
+            sensor.reading = match sensor.kind {
+                SensorKind::Pressure => match map.tiles.get(position) {
+                    Some(tile) => tile.atmosphere.get_pressure_kpa(),
+                    None => 0.0,
+                },
+                SensorKind::Temperature => match map.tiles.get(position) {
+                    Some(tile) => tile.atmosphere.temperature,
+                    None => 0.0,
+                },
+                SensorKind::Motion => (&vitals, &positions)
+                    .join()
+                    .filter(|(vitals, other)| {
+                        vitals.state != VitalState::Dead
+                            && position.distance_to(**other) <= sensor.threshold
+                    })
+                    .count() as f32,
+            };
+            outputs.push((entity, sensor.triggered()));
+        }
+
+        for (logic_gate, address, entity) in (&logic_gates, &addresses, &entities).join() {
+            let gate_inputs = inputs.get(&address.address).cloned().unwrap_or_default();
+            outputs.push((entity, logic_gate.evaluate(&gate_inputs)));
+        }
+
+        //A timer schedules the switch when its input changes and drops it if the input goes back,
+        //the delay counts the tick the change was seen on
+        let mut timer_inputs: Vec<(Entity, Vector3i, bool)> = Vec::new();
+
+        for (delay_timer, signal, address, position, entity) in
+            (&mut delay_timers, &signals, &addresses, &positions, &entities).join()
+        {
+            let input = inputs
+                .get(&address.address)
+                .map(|inputs| inputs.iter().any(|input| *input))
+                .unwrap_or(false);
+            let switch = ScheduledEvent::TimerElapsed {
+                position: *position,
+                on: input,
+            };
+
+            if input == signal.on {
+                if delay_timer.waiting {
+                    scheduler.cancel(&ScheduledEvent::TimerElapsed {
+                        position: *position,
+                        on: !input,
+                    });
+                    delay_timer.waiting = false;
+                }
+            } else if !delay_timer.waiting {
+                scheduler.cancel(&switch);
+                scheduler.schedule_in_turns(
+                    &clock,
+                    delay_timer.delay.saturating_sub(1) as u64,
+                    switch,
+                );
+                delay_timer.waiting = true;
+            }
+            timer_inputs.push((entity, *position, input));
+        }
+
+        let elapsed = scheduler.take_due_where(clock.turn, |event| {
+            matches!(event, ScheduledEvent::TimerElapsed { .. })
+        });
+
+        for (entity, position, input) in timer_inputs {
+            if elapsed.contains(&ScheduledEvent::TimerElapsed {
+                position,
+                on: input,
+            }) {
+                if let Some(delay_timer) = delay_timers.get_mut(entity) {
+                    delay_timer.waiting = false;
+                }
+                outputs.push((entity, input));
+            }
+        }
+
+        //Only a change drives the target, so whatever it is can still be worked by hand
+        for (entity, output) in outputs {
+            let target = match signals.get_mut(entity) {
+                Some(signal) if signal.on != output => {
+                    signal.on = output;
+                    signal.linked_target()
+                }
+                _ => None,
+            };
+
+            let target = match target.and_then(|target| {
+                addressed
+                    .iter()
+                    .find(|(address, _, _)| *address == target)
+                    .map(|(_, target, _)| *target)
+            }) {
+                Some(target) => target,
+                None => continue,
+            };
+
+            if let Some(power_switch) = power_switches.get_mut(target) {
+                if power_switch.on != output {
+                    power_switch.toggle();
+
+                    if let Some(node) = nodes.get_mut(target) {
+                        node.dirty = true;
+                    }
+                }
+            }
+
+            if let Some(door) = doors.get_mut(target) {
+                if door.open != output {
+                    door.open_close();
+                }
+            }
+        }
+    }
+}
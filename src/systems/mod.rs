@@ -2,14 +2,25 @@ pub use dispatcher::UnifiedDispatcher;
 
 mod dispatcher;
 
-mod visibility_system;
-mod lighting_system;
-mod map_index_system;
+pub mod visibility_system;
+pub mod lighting_system;
+pub mod map_index_system;
 pub mod power_system;
 pub mod event_system;
-mod state_align_system;
-mod atmosphere_system;
-mod biology_system;
+pub mod state_align_system;
+pub mod atmosphere_system;
+pub mod biology_system;
+pub mod decompression_system;
+pub mod fire_system;
+pub mod thermal_system;
+pub mod thermoregulation_system;
+pub mod crew_ai_system;
+pub mod clock_system;
+pub mod hazard_system;
+pub mod structural_system;
+pub mod generator_system;
+pub mod network_system;
+pub mod logic_system;
 
 pub fn build() -> Box<dyn UnifiedDispatcher + 'static> {
     dispatcher::new()
@@ -0,0 +1,156 @@
+use std::collections::HashSet;
+
+use specs::prelude::*;
+
+use crate::{
+    entities::{
+        logic::Signal,
+        network::{AlarmQueue, NetworkAddress, Terminal},
+        power_components::{Battery, Generator},
+        power_graph::PowerGraph,
+    },
+    vectors::Vector3i,
+    Door, PowerSwitch, PoweredState,
+};
+
+use super::event_system::{get_entity_interactions, InteractionInformation};
+
+//Hands out addresses to devices that turn up on the data wiring and passes alarms on to every
+//powered terminal that can reach where they were raised
+pub struct NetworkSystem {}
+
+impl<'a> System<'a> for NetworkSystem {
+    type SystemData = (
+        ReadExpect<'a, PowerGraph>,
+        WriteExpect<'a, AlarmQueue>,
+        WriteStorage<'a, NetworkAddress>,
+        WriteStorage<'a, Terminal>,
+        ReadStorage<'a, PoweredState>,
+        ReadStorage<'a, PowerSwitch>,
+        ReadStorage<'a, Door>,
+        ReadStorage<'a, Battery>,
+        ReadStorage<'a, Generator>,
+        ReadStorage<'a, Signal>,
+        ReadStorage<'a, Vector3i>,
+        Entities<'a>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            power_graph,
+            mut alarm_queue,
+            mut addresses,
+            mut terminals,
+            power_states,
+            power_switches,
+            doors,
+            batteries,
+            generators,
+            signals,
+            positions,
+            entities,
+        ) = data;
+
+        //Anything that can be worked from a terminal gets an address once it is wired in
+        let unaddressed: Vec<Entity> = (&entities, &positions, !&addresses)
+            .join()
+            .filter(|(entity, position, _)| {
+                power_graph.has_data(position)
+                    && (power_switches.get(*entity).is_some()
+                        || doors.get(*entity).is_some()
+                        || batteries.get(*entity).is_some()
+                        || generators.get(*entity).is_some()
+                        || terminals.get(*entity).is_some()
+                        || signals.get(*entity).is_some())
+            })
+            .map(|(entity, _, _)| entity)
+            .collect();
+
+        if !unaddressed.is_empty() {
+            let mut next_address = addresses
+                .join()
+                .map(|address| address.address)
+                .max()
+                .unwrap_or(0);
+
+            for entity in unaddressed {
+                next_address += 1;
+                let _ = addresses.insert(entity, NetworkAddress::new(next_address));
+            }
+        }
+
+        if alarm_queue.pending.is_empty() {
+            return;
+        }
+
+        let mut listening: Vec<(&mut Terminal, HashSet<Vector3i>)> = Vec::new();
+
+        for (terminal, position, entity) in (&mut terminals, &positions, &entities).join() {
+            if is_powered(power_states.get(entity)) {
+                listening.push((terminal, power_graph.data_network(*position)));
+            }
+        }
+
+        for alarm in alarm_queue.pending.drain(..) {
+            //Name the alarm after whatever device raised it, if that has an address
+            let source = (&addresses, &positions)
+                .join()
+                .filter(|(_, position)| **position == alarm.position)
+                .map(|(address, _)| address.address)
+                .min();
+            let message = match source {
+                Some(address) => {
+                    format!("{} {}", NetworkAddress::new(address).label(), alarm.message)
+                }
+                None => alarm.message,
+            };
+
+            for (terminal, network) in listening.iter_mut() {
+                if network.contains(&alarm.position) {
+                    terminal.receive(message.clone());
+                }
+            }
+        }
+    }
+}
+
+fn is_powered(power_state: Option<&PoweredState>) -> bool {
+    matches!(power_state, Some(power_state) if power_state.on && power_state.available_wattage > 0.0)
+}
+
+//Everything a terminal can work over the data network, labelled with its address
+pub fn get_devices_on_data_network(ecs: &World, terminal: Entity) -> Vec<InteractionInformation> {
+    let power_graph = ecs.fetch::<PowerGraph>();
+    let power_states = ecs.read_storage::<PoweredState>();
+    let addresses = ecs.read_storage::<NetworkAddress>();
+    let positions = ecs.read_storage::<Vector3i>();
+    let entities = ecs.entities();
+
+    let mut interactables = Vec::new();
+
+    let position = match positions.get(terminal) {
+        Some(position) if is_powered(power_states.get(terminal)) => *position,
+        _ => return interactables,
+    };
+    let network = power_graph.data_network(position);
+
+    let mut devices: Vec<(u32, Entity)> = (&entities, &addresses, &positions)
+        .join()
+        .filter(|(entity, _, position)| *entity != terminal && network.contains(position))
+        .map(|(entity, address, _)| (address.address, entity))
+        .collect();
+    devices.sort_by_key(|(address, _)| *address);
+
+    for (address, entity) in devices {
+        for mut interaction in get_entity_interactions(ecs, entity) {
+            interaction.description = format!(
+                "{} {}",
+                NetworkAddress::new(address).label(),
+                interaction.description
+            );
+            interactables.push(interaction);
+        }
+    }
+
+    interactables
+}
@@ -1,26 +1,43 @@
-use std::{collections::HashSet, time};
+use std::{collections::HashMap, time};
 
-use fnv::FnvHashSet;
-use rltk::RandomNumberGenerator;
 use specs::prelude::*;
 
 use crate::{
-    entities::power_components::{ControlPanel, ElectronicHeater},
+    clock::SECONDS_PER_TURN,
+    entities::{
+        power_components::{
+            Battery, Broken, ControlPanel, ElectronicHeater, BREAKER_TRIP_TICKS, WIRE_BURN_OUT,
+        },
+        network::AlarmQueue,
+        power_graph::PowerGraph,
+    },
+    gamelog::GameLog,
     vectors::{utils::get_cardinal_neighbours_with_z, Vector3i},
-    Illuminant, Photometry, PowerNode, PowerSource, PowerSwitch, PoweredState, Wire,
+    Illuminant, Map, Photometry, PowerNode, PowerSource, PowerSwitch, PoweredState, Wire,
 };
 
 use crate::entities::intents::Interactable;
 
 use super::event_system::{get_entity_interactions, InteractionInformation};
 
+//Joules an overloaded wire sheds into its tile per watt over its rating
+const WIRE_HEAT_PER_WATT: f32 = 1.0;
+//Overheat a wire loses each tick it is within its rating
+const WIRE_COOLING: f32 = 0.1;
+//The flash of a wire burning out, enough to light anything flammable on the tile
+const WIRE_BURN_OUT_HEAT: f32 = 250000.0;
+
 pub struct PowerSystem {}
 
 impl<'a> System<'a> for PowerSystem {
     type SystemData = (
+        WriteExpect<'a, Map>,
+        WriteExpect<'a, GameLog>,
+        WriteExpect<'a, PowerGraph>,
+        WriteExpect<'a, AlarmQueue>,
         WriteStorage<'a, PoweredState>,
         WriteStorage<'a, PowerSource>,
-        ReadStorage<'a, PowerSwitch>,
+        WriteStorage<'a, PowerSwitch>,
         WriteStorage<'a, Illuminant>,
         WriteStorage<'a, Photometry>,
         ReadStorage<'a, Vector3i>,
@@ -28,28 +45,36 @@ impl<'a> System<'a> for PowerSystem {
         WriteStorage<'a, PowerNode>,
         WriteStorage<'a, ControlPanel>,
         WriteStorage<'a, ElectronicHeater>,
+        ReadStorage<'a, Broken>,
+        WriteStorage<'a, Battery>,
         Entities<'a>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
         let (
+            mut map,
+            mut game_log,
+            mut power_graph,
+            mut alarm_queue,
             mut power_states,
             mut power_sources,
-            power_switches,
+            mut power_switches,
             mut illuminants,
             mut photometrics,
             positions,
             mut wires,
             mut nodes,
-            control_panels,
+            mut control_panels,
             mut electronic_heaters,
+            broken,
+            mut batteries,
             entities,
         ) = data;
 
-        let mut random = RandomNumberGenerator::new();
-
-        //Align powered on state with switches
-        for (power_switch, entity, _) in (&power_switches, &entities, &nodes).join() {
+        //Align powered on state with switches, heaters are left to their thermostat
+        for (power_switch, entity, _, _) in
+            (&power_switches, &entities, &nodes, !&electronic_heaters).join()
+        {
             if let Some(power_state) = power_states.get_mut(entity) {
                 power_state.on = power_switch.on;
             }
@@ -59,324 +84,200 @@ impl<'a> System<'a> for PowerSystem {
             }
         }
 
-        //Check if heaters must turn on
-        /* for (electronic_heater, power_state, position, node) in (
+        //Thermostats switch their heaters on and off around the target temperature
+        for (electronic_heater, power_state, position, node, entity) in (
             &mut electronic_heaters,
             &mut power_states,
             &positions,
             &mut nodes,
+            &entities,
         )
             .join()
         {
-            if let Some(tile) = map.tiles.get_mut(position) {
-                let on = electronic_heater.check_status(tile.atmosphere.temperature);
-
-                let prev_state = power_state.on;
-
-                if on {
-                    //Produce heat
-
-                    power_state.on = true;
-                    tile.atmosphere.update_temperature(
-                        (2.0 * power_state.wattage) / (3.0 * tile.atmosphere.get_total_mols() * R),
-                    );
-                } else {
-                    power_state.on = false;
-                }
-
-                if power_state.on != prev_state {
+            if let Some(tile) = map.tiles.get(position) {
+                let switched_on = match power_switches.get(entity) {
+                    Some(power_switch) => power_switch.on,
+                    None => true,
+                };
+                let heating =
+                    electronic_heater.check_status(tile.atmosphere.temperature) && switched_on;
+
+                if power_state.on != heating {
+                    power_state.on = heating;
                     node.dirty = true;
                 }
             }
-        }*/
-        let dirty_networks: HashSet<usize> = (&mut nodes, &entities)
+        }
+
+        //Place changed nodes in the graph again, or everything for a new or loaded game
+        let rebuild = power_graph.dirty;
+        let changed: Vec<Entity> = (&mut nodes, &entities)
             .join()
-            .filter_map(|(node, _)| {
-                if node.dirty {
+            .filter_map(|(node, entity)| {
+                if node.dirty || rebuild {
                     node.dirty = false;
-                    Some(node.network_id)
+                    Some(entity)
                 } else {
                     None
                 }
             })
             .collect();
 
-        for network_id in dirty_networks.iter() {
-            //Rebuild wire network
-            let mut start_position = Vector3i::new_equi(0);
-            let mut start_wire = Wire::new(
-                rltk::RGB::named(rltk::WHITE).to_rgba(1.0),
-                "invalid".to_string(),
-                false,
-            );
-
-            {
-                if let Some((wire, _, position)) = (&wires, &nodes, &positions)
-                    .join()
-                    .filter(|(_, node, _)| node.network_id == *network_id)
-                    .next()
-                {
-                    start_position = *position;
-                    start_wire = wire.clone();
-                }
-            }
-
-            if start_wire.color_name == "invalid" {
-                continue;
-            }
+        if rebuild {
+            power_graph.clear();
+            power_graph.dirty = false;
+        }
 
-            let mut unchanged_wires = vec![start_position];
+        power_graph.prune(&entities);
 
-            let mut load_wires = std::collections::HashMap::new();
-            let mut prev_colors = HashSet::new();
-            prev_colors.insert(start_wire.color_name);
+        for entity in changed.iter() {
+            power_graph.remove(*entity);
 
-            let mut network_wires: HashSet<u32> = HashSet::default();
+            if let Some(position) = positions.get(*entity) {
+                if let Some(wire) = wires.get(*entity) {
+                    power_graph.insert_wire(
+                        *entity,
+                        *position,
+                        wire.color_name.clone(),
+                        wire.data,
+                        wire.gauge.carries_power(),
+                    );
+                }
 
-            while let Some(wire_position) = unchanged_wires.pop() {
-                //For every node, set the id
-                let mut is_load_wire = false;
-                for (_, node, entity) in (&positions, &mut nodes, &entities)
-                    .join()
-                    .filter(|(x, _, _)| **x == wire_position)
+                if let (Some(_), Some(power_switch)) =
+                    (control_panels.get(*entity), power_switches.get(*entity))
                 {
-                    node.network_id = *network_id;
+                    power_graph.insert_breaker(*entity, *position, power_switch.on);
+                }
 
-                    if let Some(_) = power_states.get(entity) {
-                        is_load_wire = true;
-                    }
+                if power_states.get(*entity).is_some() || power_sources.get(*entity).is_some() {
+                    power_graph.insert_device(*entity, *position);
                 }
+            }
+        }
 
-                //Add all wires on the current position
-                for (_, _, entity) in
-                    (&mut wires, &positions, &entities)
-                        .join()
-                        .filter(|(wire, x, _)| {
-                            *x == &wire_position && prev_colors.contains(&wire.color_name.clone())
-                        })
-                {
-                    network_wires.insert(entity.id());
+        //Only circuits something changed on are solved again, the rest keep what they had
+        let mut circuits = power_graph.update_circuits();
 
-                    if is_load_wire {
-                        load_wires.insert(entity, wire_position);
-                    }
-                }
+        //Batteries soak up what their network does not use and cover what it can not generate
+        let mut balances: HashMap<usize, f32> = HashMap::new();
 
-                let neighbours = get_cardinal_neighbours_with_z(wire_position);
-
-                for neighbour in neighbours.into_iter() {
-                    for (wire, position, _, entity) in (&wires, &positions, &nodes, &entities)
-                        .join()
-                        .filter(|(_, x, _, _)| **x == neighbour)
-                    {
-                        if !network_wires.contains(&entity.id()) {
-                            if let Some((_, _, _)) = (&control_panels, &power_switches, &positions)
-                                .join()
-                                .filter(|(_, _, x)| *x == position)
-                                .next()
-                            {
-                                // if switch.on {
-                                unchanged_wires.push(neighbour);
-                                prev_colors.insert(wire.color_name.clone());
-                                //}
-                            } else if prev_colors.contains(&wire.color_name) {
-                                unchanged_wires.push(neighbour);
-                            }
-                        }
-                    }
-                }
+        for (power_source, entity, _) in (&power_sources, &entities, !&batteries).join() {
+            if power_source.on {
+                *balances.entry(power_graph.circuit(entity)).or_default() +=
+                    power_source.max_wattage;
+            }
+        }
+
+        for (power_state, entity) in (&power_states, &entities).join() {
+            if power_state.on && broken.get(entity).is_none() {
+                *balances.entry(power_graph.circuit(entity)).or_default() -= power_state.wattage;
             }
+        }
 
-            //Get all wires no longer in the network
-            for (_, _, node) in (&wires, &entities, &mut nodes)
-                .join()
-                .filter(|(_, x, node)| {
-                    node.network_id == *network_id && !network_wires.contains(&x.id())
-                })
-            {
-                node.network_id = random.rand();
-                node.dirty = true;
+        for (battery, power_source, entity) in
+            (&mut batteries, &mut power_sources, &entities).join()
+        {
+            let circuit = power_graph.circuit(entity);
+            let balance = balances.entry(circuit).or_default();
+            let previous_flow = battery.flow;
+            battery.flow = 0.0;
+
+            if battery.connected && *balance > 0.0 {
+                battery.flow = balance
+                    .min(battery.max_charge_rate)
+                    .min((battery.capacity - battery.charge) / SECONDS_PER_TURN);
+            } else if battery.connected && *balance < 0.0 {
+                battery.flow = -(-*balance)
+                    .min(power_source.max_wattage)
+                    .min(battery.charge / SECONDS_PER_TURN);
             }
 
-            //Calculate power draw of wire segments
-            //Reset power draw
-            for (power_source, _) in (&mut power_sources, &nodes)
-                .join()
-                .filter(|(_, node)| node.network_id == *network_id)
-            {
-                power_source.available_wattage = power_source.max_wattage;
+            battery.charge =
+                (battery.charge + battery.flow * SECONDS_PER_TURN).clamp(0.0, battery.capacity);
+            //Whatever one battery takes or gives is not there for the next one
+            *balance -= battery.flow;
+
+            let discharging = battery.flow < 0.0;
+
+            //What it feeds the network changed, so its circuit needs solving again
+            if power_source.on != discharging || (discharging && battery.flow != previous_flow) {
+                power_source.on = discharging;
+                circuits.insert(circuit);
             }
+        }
 
-            for wire in network_wires.iter() {
-                if let Some(wire) = wires.get_mut(entities.entity(*wire)) {
-                    wire.power_load = 0.0;
-                    wire.available_wattage = 0.0;
-                }
+        //A discharging battery only gives what its network is short of
+        let output = |power_source: &PowerSource, entity: Entity| match batteries.get(entity) {
+            Some(battery) => (-battery.flow).max(0.0),
+            None => power_source.max_wattage,
+        };
+
+        //What the batteries could give on top of that still counts as available on their network
+        let mut spare: HashMap<usize, f32> = HashMap::new();
+
+        for (battery, power_source, entity) in (&batteries, &power_sources, &entities).join() {
+            if battery.connected {
+                let most = power_source
+                    .max_wattage
+                    .min(battery.charge / SECONDS_PER_TURN);
+                *spare.entry(power_graph.circuit(entity)).or_default() +=
+                    (most - output(power_source, entity)).max(0.0);
             }
+        }
+        let spare_on = |circuit: usize| spare.get(&circuit).copied().unwrap_or(0.0);
 
-            //Calculate power loads
-            let mut visited_load_wires = HashSet::new();
+        if !circuits.is_empty() {
+            let sources: HashMap<Entity, f32> = (&power_sources, &entities)
+                .join()
+                .filter(|(power_source, _)| power_source.on)
+                .map(|(power_source, entity)| (entity, output(power_source, entity)))
+                .collect();
 
-            for (start_wire_entity, position) in load_wires.iter() {
-                if visited_load_wires.contains(position) {
-                    continue;
-                }
+            //Only devices that are switched on draw from the network
+            let loads: HashMap<Entity, f32> = (&power_states, &entities)
+                .join()
+                .filter(|(power_state, entity)| power_state.on && broken.get(*entity).is_none())
+                .map(|(power_state, entity)| (entity, power_state.wattage))
+                .collect();
 
-                if let Some(start_wire) = wires.get(*start_wire_entity) {
-                    let mut unchanged_wires = vec![*position];
-                    let mut wire_entities: FnvHashSet<u32> = FnvHashSet::with_capacity_and_hasher(
-                        network_wires.len(),
-                        Default::default(),
-                    );
+            let solution = power_graph.solve(&circuits, &sources, &loads);
+            let solved = |entity: &Entity| solution.circuits.contains_key(entity);
 
-                    let mut total_draw = 0.0;
-
-                    let mut prev_colors = Vec::new();
-                    prev_colors.push(start_wire.color_name.clone());
-
-                    while let Some(wire_position) = unchanged_wires.pop() {
-                        for (_, current_position, entity) in (&wires, &positions, &entities)
-                            .join()
-                            .filter(|(wire, x, _)| {
-                                **x == wire_position && prev_colors.contains(&wire.color_name)
-                            })
-                        {
-                            if !wire_entities.contains(&entity.id()) {
-                                for (power_state, _, node) in
-                                    (&mut power_states, &positions, &mut nodes)
-                                        .join()
-                                        .filter(|(_, x, _)| *x == current_position)
-                                {
-                                    total_draw += power_state.wattage;
-                                    node.network_id = *network_id;
-                                    visited_load_wires.insert(wire_position);
-                                }
-                                wire_entities.insert(entity.id());
-                            }
-                        }
-
-                        let neighbours = get_cardinal_neighbours_with_z(wire_position);
-
-                        for neighbour in neighbours.into_iter() {
-                            for (wire, position, entity) in (&wires, &positions, &entities)
-                                .join()
-                                .filter(|(_, x, _)| **x == neighbour)
-                            {
-                                if !wire_entities.contains(&entity.id()) {
-                                    if let Some((_, switch, _)) =
-                                        (&control_panels, &power_switches, &positions)
-                                            .join()
-                                            .filter(|(_, _, x)| *x == position)
-                                            .next()
-                                    {
-                                        if switch.on {
-                                            unchanged_wires.push(neighbour);
-                                            prev_colors.push(wire.color_name.clone());
-                                        }
-                                    } else if prev_colors.contains(&wire.color_name) {
-                                        unchanged_wires.push(neighbour);
-                                    }
-                                }
-                            }
-                        }
-                    }
+            for (node, entity) in (&mut nodes, &entities).join().filter(|(_, e)| solved(e)) {
+                node.network_id = solution.circuit(entity);
+            }
 
-                    for (wire, _, _) in (&mut wires, &positions, &entities)
-                        .join()
-                        .filter(|(_, _, entity)| wire_entities.contains(&entity.id()))
-                    {
-                        wire.power_load = total_draw;
-                    }
-                }
+            for (wire, entity) in (&mut wires, &entities).join().filter(|(_, e)| solved(e)) {
+                wire.power_load = solution.wire_loads.get(&entity).copied().unwrap_or(0.0);
+                wire.available_wattage =
+                    solution.available(entity) + spare_on(solution.circuit(entity));
             }
 
-            //Align powered state with power sources
-            for (power_source, position, _) in (&mut power_sources, &positions, &nodes)
-                .join()
-                .filter(|(_, _, node)| node.network_id == *network_id)
+            for (control_panel, entity) in
+                (&mut control_panels, &entities).join().filter(|(_, e)| solved(e))
             {
-                if power_source.on {
-                    if let Some((start_wire, position, _)) = (&wires, &positions, &entities)
-                        .join()
-                        .filter(|(_, x, _)| *x == position)
-                        .next()
-                    {
-                        let mut unchanged_wires = vec![*position];
-                        let mut wire_entities = HashSet::new();
-
-                        let mut prev_colors = HashSet::new();
-                        prev_colors.insert(start_wire.color_name.clone());
-
-                        while let Some(wire_position) = unchanged_wires.pop() {
-                            for (_, _, entity) in
-                                (&wires, &positions, &entities)
-                                    .join()
-                                    .filter(|(wire, x, _)| {
-                                        **x == wire_position
-                                            && prev_colors.contains(&wire.color_name)
-                                    })
-                            {
-                                if !wire_entities.contains(&entity) {
-                                    wire_entities.insert(entity);
-                                }
-                            }
-
-                            let neighbours = get_cardinal_neighbours_with_z(wire_position);
-
-                            for neighbour in neighbours.into_iter() {
-                                for (wire, position, entity) in (&wires, &positions, &entities)
-                                    .join()
-                                    .filter(|(_, x, _)| **x == neighbour)
-                                {
-                                    if !wire_entities.contains(&entity) {
-                                        if let Some((_, switch, _)) =
-                                            (&control_panels, &power_switches, &positions)
-                                                .join()
-                                                .filter(|(_, _, x)| *x == position)
-                                                .next()
-                                        {
-                                            if switch.on {
-                                                unchanged_wires.push(neighbour);
-                                                prev_colors.insert(wire.color_name.clone());
-                                            }
-                                        } else if prev_colors.contains(&wire.color_name) {
-                                            unchanged_wires.push(neighbour);
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        power_source.available_wattage =
-                            power_source.available_wattage - start_wire.power_load;
-
-                        for (wire, _) in (&mut wires, &entities)
-                            .join()
-                            .filter(|(_, x)| wire_entities.contains(x))
-                        {
-                            wire.available_wattage += power_source.available_wattage;
-                        }
-                    }
-                }
+                control_panel.load = solution.breaker_loads.get(&entity).copied().unwrap_or(0.0);
             }
 
-            for (power_state, position, _) in (&mut power_states, &positions, &mut nodes)
-                .join()
-                .filter(|(_, _, node)| node.network_id == *network_id)
+            for (power_source, entity) in
+                (&mut power_sources, &entities).join().filter(|(_, e)| solved(e))
             {
-                for (wire, _) in (&mut wires, &positions)
-                    .join()
-                    .filter(|(_, x)| *x == position)
-                {
-                    power_state.available_wattage = wire.available_wattage;
-                }
+                power_source.available_wattage = match solution.source_available.get(&entity) {
+                    Some(available) if power_source.on => *available,
+                    _ => output(power_source, entity),
+                };
             }
 
             //Align powered components with powered state
-            for (power, entity, _, _) in (&mut power_states, &entities, &nodes, &positions)
-                .join()
-                .filter(|(_, _, node, _)| node.network_id == *network_id)
+            for (power, entity) in
+                (&mut power_states, &entities).join().filter(|(_, e)| solved(e))
             {
-                let power_state = power.on && (power.available_wattage > 0.0);
+                power.available_wattage =
+                    solution.available(entity) + spare_on(solution.circuit(entity));
+
+                let power_state =
+                    power.on && (power.available_wattage > 0.0) && broken.get(entity).is_none();
                 //Illuminant
                 if let Some(illuminant) = illuminants.get_mut(entity) {
                     illuminant.set_state(power_state);
@@ -392,88 +293,158 @@ impl<'a> System<'a> for PowerSystem {
                 //TODO: Add any other powered systems here
             }
         }
-    }
-}
 
-pub fn get_devices_on_subnetwork(
-    ecs: &World,
-    network_entity: Entity,
-) -> Vec<InteractionInformation> {
-    let names = ecs.read_storage::<crate::Name>();
-    let nodes = ecs.read_storage::<crate::PowerNode>();
-    let positions = ecs.read_storage::<crate::Vector3i>();
-    let wires = ecs.read_storage::<crate::Wire>();
-    let entities = ecs.entities();
+        //The network decides if a heater actually got power this tick
+        for (_, power_state, position, entity) in
+            (&electronic_heaters, &power_states, &positions, &entities).join()
+        {
+            if power_state.on && power_state.available_wattage > 0.0 && broken.get(entity).is_none()
+            {
+                if let Some(tile) = map.tiles.get_mut(position) {
+                    tile.add_heat(power_state.wattage * SECONDS_PER_TURN);
+                }
+            }
+        }
 
-    let mut interactables = Vec::new();
+        //Breakers trip on their own rating, or the busiest one when the sources fall short
+        let mut supplies: HashMap<usize, f32> = HashMap::new();
 
-    let mut start_position = Vector3i::new_equi(0);
+        for (power_source, node) in (&power_sources, &nodes).join() {
+            if power_source.on {
+                *supplies.entry(node.network_id).or_default() += power_source.available_wattage;
+            }
+        }
 
-    if let Some(position) = positions.get(network_entity) {
-        start_position = *position;
-    }
-    let mut unchanged_wires = vec![start_position];
+        for (network_id, spare_wattage) in spare.iter() {
+            *supplies.entry(*network_id).or_default() += spare_wattage;
+        }
 
-    let mut start_wire = Wire::new(
-        rltk::RGB::named(rltk::WHITE).to_rgba(1.0),
-        "invalid".to_string(),
-        false,
-    );
+        let mut busiest: HashMap<usize, (Entity, f32)> = HashMap::new();
 
-    for (wire, _, _) in (&wires, &positions, &nodes)
-        .join()
-        .filter(|(_, x, _)| **x == start_position)
-    {
-        start_wire = wire.clone();
-    }
+        for (control_panel, power_switch, node, entity) in
+            (&control_panels, &power_switches, &nodes, &entities).join()
+        {
+            let overdrawn = matches!(supplies.get(&node.network_id), Some(supply) if *supply < 0.0);
+
+            if power_switch.on && overdrawn && control_panel.load > 0.0 {
+                let current = busiest.entry(node.network_id).or_insert((entity, 0.0));
 
-    let mut prev_colors = HashSet::new();
-    prev_colors.insert(start_wire.color_name);
+                if control_panel.load > current.1 {
+                    *current = (entity, control_panel.load);
+                }
+            }
+        }
 
-    let mut network_wires: HashSet<u32> = HashSet::default();
+        let mut tripped = Vec::new();
 
-    while let Some(wire_position) = unchanged_wires.pop() {
-        //Add all wires on the current position
-        for (_, _, entity) in (&wires, &positions, &entities)
+        for (control_panel, power_switch, node, position, entity) in (
+            &mut control_panels,
+            &mut power_switches,
+            &nodes,
+            &positions,
+            &entities,
+        )
             .join()
-            .filter(|(wire, x, _)| {
-                *x == &wire_position && prev_colors.contains(&wire.color_name.clone())
-            })
         {
-            network_wires.insert(entity.id());
+            let overdrawn =
+                matches!(busiest.get(&node.network_id), Some((busiest, _)) if *busiest == entity);
+
+            if power_switch.on && (control_panel.is_overloaded() || overdrawn) {
+                control_panel.overload_ticks += 1;
+            } else {
+                control_panel.overload_ticks = 0;
+            }
+
+            if control_panel.overload_ticks >= BREAKER_TRIP_TICKS {
+                control_panel.overload_ticks = 0;
+                power_switch.toggle();
+                tripped.push(*position);
+
+                let message = if control_panel.is_overloaded() {
+                    format!(
+                        "A breaker trips: {:.0} W on a {:.0} W circuit",
+                        control_panel.load, control_panel.rated_wattage
+                    )
+                } else {
+                    "A breaker trips: the generators can not keep up".to_string()
+                };
+                alarm_queue.raise(*position, message.clone());
+                game_log.entries.push(message);
+            }
         }
 
-        let neighbours = get_cardinal_neighbours_with_z(wire_position);
+        //Overloaded wires heat up their tile and burn out if it goes on for too long
+        let mut burnt_out = Vec::new();
 
-        for neighbour in neighbours.into_iter() {
-            for (wire, _, _, entity) in (&wires, &positions, &nodes, &entities)
-                .join()
-                .filter(|(wire, x, _, _)| wire.data && **x == neighbour)
-            {
-                if !network_wires.contains(&entity.id()) {
-                    if prev_colors.contains(&wire.color_name) {
-                        unchanged_wires.push(neighbour);
-                    }
+        for (wire, position, entity) in (&mut wires, &positions, &entities).join() {
+            let rated_wattage = wire.gauge.rated_wattage();
+
+            if wire.is_overloaded() {
+                wire.overheat += wire.power_load / rated_wattage - 1.0;
+
+                if let Some(tile) = map.tiles.get_mut(position) {
+                    tile.add_heat(
+                        (wire.power_load - rated_wattage) * WIRE_HEAT_PER_WATT * SECONDS_PER_TURN,
+                    );
                 }
+            } else {
+                wire.overheat = (wire.overheat - WIRE_COOLING).max(0.0);
+            }
+
+            if wire.overheat >= WIRE_BURN_OUT {
+                burnt_out.push((entity, *position));
             }
         }
-    }
-    let mut network_wires: Vec<u32> = network_wires.into_iter().collect();
-    network_wires.sort();
 
-    for entity_id in network_wires {
-        let entity = entities.entity(entity_id);
-        let mut current_position = Vector3i::new_equi(0);
+        for (entity, position) in burnt_out.iter() {
+            let _ = entities.delete(*entity);
+            power_graph.remove(*entity);
 
-        if let Some(position) = positions.get(entity) {
-            current_position = position.clone();
+            if let Some(tile) = map.tiles.get_mut(position) {
+                tile.add_heat(WIRE_BURN_OUT_HEAT);
+            }
+            alarm_queue.raise(*position, "Wire burnt out".to_string());
+            game_log
+                .entries
+                .push("A wire burns out in a shower of sparks".to_string());
         }
-        for (entity, _) in (&entities, &positions)
-            .join()
-            .filter(|(entity, x)| **x == current_position && *entity != network_entity)
-        {
-            interactables.append(&mut get_entity_interactions(ecs, entity));
+
+        //What was on the other side of a breaker or a burnt wire has to find its network again
+        for (node, position) in (&mut nodes, &positions).join() {
+            let affected = tripped.contains(position)
+                || burnt_out.iter().any(|(_, burnt_position)| {
+                    burnt_position == position
+                        || get_cardinal_neighbours_with_z(*burnt_position).contains(position)
+                });
+
+            if affected {
+                node.dirty = true;
+            }
         }
     }
+}
+
+pub fn get_devices_on_subnetwork(
+    ecs: &World,
+    network_entity: Entity,
+) -> Vec<InteractionInformation> {
+    let power_graph = ecs.fetch::<PowerGraph>();
+    let positions = ecs.read_storage::<crate::Vector3i>();
+    let entities = ecs.entities();
+
+    let mut interactables = Vec::new();
+
+    let start_position = match positions.get(network_entity) {
+        Some(position) => *position,
+        None => return interactables,
+    };
+    let run = power_graph.data_run(start_position);
+
+    for (entity, _) in (&entities, &positions)
+        .join()
+        .filter(|(entity, position)| *entity != network_entity && run.contains(position))
+    {
+        interactables.append(&mut get_entity_interactions(ecs, entity));
+    }
     interactables
 }
@@ -0,0 +1,139 @@
+use std::collections::HashSet;
+
+use specs::prelude::*;
+
+use crate::{
+    entities::{construction::refresh_around, crew::CrewAi},
+    gamelog::GameLog,
+    map::{structure::DamageQueue, tile_blueprints, zones::AtmosphereZones},
+    vectors::{utils::get_cardinal_neighbours_with_z, Vector3i},
+    Map, Tile, Viewshed,
+};
+
+//Applies queued damage to tiles and replaces the ones that give way
+pub struct StructuralSystem {}
+
+impl<'a> System<'a> for StructuralSystem {
+    type SystemData = (
+        WriteExpect<'a, Map>,
+        WriteExpect<'a, DamageQueue>,
+        WriteExpect<'a, AtmosphereZones>,
+        WriteExpect<'a, GameLog>,
+        ReadStorage<'a, Vector3i>,
+        WriteStorage<'a, Viewshed>,
+        WriteStorage<'a, CrewAi>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            mut map,
+            mut damage_queue,
+            mut zones,
+            mut game_log,
+            positions,
+            mut viewsheds,
+            mut crew_ais,
+        ) = data;
+
+        if damage_queue.pending.is_empty() {
+            return;
+        }
+
+        let mut destroyed = Vec::new();
+
+        for damage in damage_queue.pending.drain(..) {
+            if let Some(tile) = map.tiles.get_mut(&damage.position) {
+                if tile.structure.damage(damage.damage_type, damage.amount) {
+                    destroyed.push(damage.position);
+                }
+            }
+        }
+
+        if destroyed.is_empty() {
+            return;
+        }
+
+        let mut messages = HashSet::new();
+
+        for position in destroyed.iter() {
+            let old_tile = match map.tiles.get(position) {
+                Some(tile) => tile.clone(),
+                None => continue,
+            };
+
+            replace_tile(&mut map, *position, destroyed_tile(&old_tile));
+            messages.insert(format!("The {} gives way", old_tile.name.to_lowercase()));
+        }
+
+        //Anything that could see or walk through the tiles has to look again, lights included
+        for position in destroyed.iter() {
+            refresh_around(
+                *position,
+                &mut map,
+                &mut zones,
+                &positions,
+                &mut viewsheds,
+                &mut crew_ais,
+            );
+        }
+
+        let mut messages: Vec<String> = messages.into_iter().collect();
+        messages.sort();
+        game_log.entries.extend(messages);
+    }
+}
+
+//What is left of the tile, open space if the blueprint is missing
+fn destroyed_tile(old_tile: &Tile) -> Tile {
+    old_tile
+        .structure
+        .destroyed_into
+        .as_ref()
+        .and_then(|key| tile_blueprints::get_tile(key).ok())
+        .unwrap_or_else(Tile::new_vacuume)
+}
+
+//Puts a new tile in place of the old one and lets the neighbours settle again
+pub fn replace_tile(map: &mut Map, position: Vector3i, mut tile: Tile) {
+    if let Some(old_tile) = map.tiles.get(&position) {
+        //Whatever gas was in the tile stays, otherwise the tile keeps its heat
+        if tile.passable && old_tile.passable {
+            tile.atmosphere = old_tile.atmosphere.clone();
+        } else {
+            tile.atmosphere.temperature = old_tile.temperature();
+
+            //Gas trapped in a wall comes out when it gives way
+            if tile.passable {
+                tile.atmosphere
+                    .update_gas(&old_tile.atmosphere.gasses, old_tile.temperature());
+            }
+        }
+
+        //Walling a tile off squeezes its gas out into the first open neighbour
+        if old_tile.passable && !tile.passable {
+            let gasses = old_tile.atmosphere.gasses.clone();
+            let temperature = old_tile.atmosphere.temperature;
+
+            match get_cardinal_neighbours_with_z(position)
+                .into_iter()
+                .find(|neighbour| matches!(map.tiles.get(neighbour), Some(tile) if tile.passable))
+            {
+                Some(neighbour) => {
+                    if let Some(neighbour_tile) = map.tiles.get_mut(&neighbour) {
+                        neighbour_tile.atmosphere.update_gas(&gasses, temperature);
+                    }
+                }
+                //Nowhere to go, the gas stays trapped in the new tile
+                None => tile.atmosphere.update_gas(&gasses, temperature),
+            }
+        }
+    }
+    tile.atmosphere.dirty = true;
+    map.tiles.insert(position, tile);
+
+    for neighbour in get_cardinal_neighbours_with_z(position).into_iter() {
+        if let Some(tile) = map.tiles.get_mut(&neighbour) {
+            tile.atmosphere.dirty = true;
+        }
+    }
+}
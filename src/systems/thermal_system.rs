@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+
+use specs::prelude::*;
+
+use crate::{
+    clock::SECONDS_PER_TURN,
+    vectors::{utils::get_cardinal_neighbours_with_z, Vector3i},
+    Map, Tile,
+};
+
+//Stefan-Boltzmann constant, W/(m^2 K^4)
+const STEFAN_BOLTZMANN: f32 = 5.670374e-8;
+//Cosmic background
+const SPACE_TEMPERATURE: f32 = 2.7;
+//Tiles with less gas than this count as open space for radiation
+const VACUUM_MOLS: f32 = 0.001;
+//Share of the temperature difference between two tiles that can even out in a tick,
+//keeps high conductivities from overshooting
+const MAX_EXCHANGE_RATIO: f32 = 0.5;
+//Heat below this in joules is not worth moving
+const MIN_HEAT: f32 = 0.001;
+
+pub struct ThermalSystem {}
+
+impl<'a> System<'a> for ThermalSystem {
+    type SystemData = (WriteExpect<'a, Map>,);
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (mut map,) = data;
+
+        let mut heat_deltas: HashMap<Vector3i, f32> = HashMap::new();
+
+        for (position, tile) in map.tiles.iter() {
+            let heat_capacity = tile.heat_capacity();
+
+            if heat_capacity <= 0.0 {
+                continue;
+            }
+
+            let mut exposed_faces = 0;
+
+            for neighbour in get_cardinal_neighbours_with_z(*position).into_iter() {
+                let neighbour_tile = match map.tiles.get(&neighbour) {
+                    Some(neighbour_tile) => neighbour_tile,
+                    None => {
+                        exposed_faces += 1;
+                        continue;
+                    }
+                };
+
+                if is_open_space(neighbour_tile) {
+                    exposed_faces += 1;
+                    continue;
+                }
+
+                //Each pair is handled once, from the lower position
+                if neighbour < *position {
+                    continue;
+                }
+
+                let heat = conducted_heat(tile, neighbour_tile);
+
+                if heat.abs() < MIN_HEAT {
+                    continue;
+                }
+
+                *heat_deltas.entry(*position).or_insert(0.0) -= heat;
+                *heat_deltas.entry(neighbour).or_insert(0.0) += heat;
+            }
+
+            //Faces open to space radiate, a tile can not drop below the background this way
+            if exposed_faces > 0 && tile.thermal.emissivity > 0.0 {
+                let temperature = tile.temperature();
+                let radiated = tile.thermal.emissivity
+                    * STEFAN_BOLTZMANN
+                    * exposed_faces as f32
+                    * (temperature.powi(4) - SPACE_TEMPERATURE.powi(4))
+                    * SECONDS_PER_TURN;
+                let max_radiated =
+                    (temperature - SPACE_TEMPERATURE).max(0.0) * heat_capacity * MAX_EXCHANGE_RATIO;
+
+                *heat_deltas.entry(*position).or_insert(0.0) -= radiated.min(max_radiated);
+            }
+        }
+
+        for (position, heat) in heat_deltas.into_iter() {
+            if heat.abs() < MIN_HEAT {
+                continue;
+            }
+
+            if let Some(tile) = map.tiles.get_mut(&position) {
+                tile.add_heat(heat);
+            }
+        }
+    }
+}
+
+fn is_open_space(tile: &Tile) -> bool {
+    tile.passable && tile.thermal.mass <= 0.0 && tile.atmosphere.get_total_mols() < VACUUM_MOLS
+}
+
+//Heat flowing from tile to neighbour this tick, negative if it flows the other way
+fn conducted_heat(tile: &Tile, neighbour: &Tile) -> f32 {
+    let conductivity = tile.thermal.conductivity;
+    let neighbour_conductivity = neighbour.thermal.conductivity;
+
+    if conductivity <= 0.0 || neighbour_conductivity <= 0.0 {
+        return 0.0;
+    }
+
+    let heat_capacity = tile.heat_capacity();
+    let neighbour_heat_capacity = neighbour.heat_capacity();
+
+    if neighbour_heat_capacity <= 0.0 {
+        return 0.0;
+    }
+
+    //Two materials in series
+    let conductance =
+        (conductivity * neighbour_conductivity) / (conductivity + neighbour_conductivity);
+    let delta_t = tile.temperature() - neighbour.temperature();
+
+    let heat = conductance * delta_t * SECONDS_PER_TURN;
+    let max_heat = delta_t.abs() * MAX_EXCHANGE_RATIO * (heat_capacity * neighbour_heat_capacity)
+        / (heat_capacity + neighbour_heat_capacity);
+
+    heat.clamp(-max_heat, max_heat)
+}
@@ -0,0 +1,120 @@
+use specs::prelude::*;
+
+use crate::{
+    clock::SECONDS_PER_TURN,
+    entities::biology::{
+        BodyTemperatureState, Breather, Thermoregulation, VitalState, Vitals, ONE_ATMOSPHERE,
+    },
+    gamelog::GameLog,
+    vectors::Vector3i,
+    Health, Map, Name,
+};
+
+//Health lost per tick while hypothermic or hyperthermic
+const EXPOSURE_DAMAGE: f32 = 0.5;
+
+pub struct ThermoregulationSystem {}
+
+impl<'a> System<'a> for ThermoregulationSystem {
+    type SystemData = (
+        WriteExpect<'a, Map>,
+        WriteExpect<'a, GameLog>,
+        ReadStorage<'a, Vector3i>,
+        ReadStorage<'a, Name>,
+        WriteStorage<'a, Thermoregulation>,
+        WriteStorage<'a, Breather>,
+        WriteStorage<'a, Vitals>,
+        WriteStorage<'a, Health>,
+        Entities<'a>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            mut map,
+            mut game_log,
+            positions,
+            names,
+            mut thermoregulations,
+            mut breathers,
+            mut vitals,
+            mut healths,
+            entities,
+        ) = data;
+
+        for (entity, body, position) in (&entities, &mut thermoregulations, &positions).join() {
+            //Without vitals to mark them dead, bodies out of health are dead
+            let alive = match vitals.get(entity) {
+                Some(entity_vitals) => entity_vitals.state != VitalState::Dead,
+                None => healths.get(entity).is_none_or(|health| health.current > 0.0),
+            };
+
+            let tile = match map.tiles.get_mut(position) {
+                Some(tile) => tile,
+                None => continue,
+            };
+
+            //Thin air carries less heat away
+            let pressure_factor = (tile.atmosphere.pressure / ONE_ATMOSPHERE).max(0.0).sqrt();
+            let lost_heat = body.effective_conductance()
+                * pressure_factor
+                * (body.temperature - tile.atmosphere.temperature)
+                * SECONDS_PER_TURN;
+            let produced_heat = if alive {
+                body.metabolism() * SECONDS_PER_TURN
+            } else {
+                0.0
+            };
+
+            body.temperature += (produced_heat - lost_heat) / body.heat_capacity;
+
+            if lost_heat != 0.0 && tile.atmosphere.get_total_mols() > 0.0 {
+                tile.add_heat(lost_heat);
+            }
+
+            //Breath leaves at body temperature
+            if let Some(breather) = breathers.get_mut(entity) {
+                breather.temperature = body.temperature;
+            }
+
+            if !alive {
+                continue;
+            }
+
+            let previous_state = body.state;
+            body.state = body.assess();
+
+            if body.state != previous_state {
+                if let Some(name) = names.get(entity) {
+                    game_log
+                        .entries
+                        .push(body_temperature_message(&name.name, body.state));
+                }
+            }
+
+            if body.is_harmful() {
+                if let Some(health) = healths.get_mut(entity) {
+                    health.damage(EXPOSURE_DAMAGE);
+
+                    if health.current <= 0.0 {
+                        if let Some(entity_vitals) = vitals.get_mut(entity) {
+                            entity_vitals.state = VitalState::Dead;
+                        }
+                        if let Some(name) = names.get(entity) {
+                            game_log.entries.push(format!("{} dies", name.name));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn body_temperature_message(name: &str, state: BodyTemperatureState) -> String {
+    match state {
+        BodyTemperatureState::Normal => format!("{} feels comfortable again", name),
+        BodyTemperatureState::Cold => format!("{} shivers", name),
+        BodyTemperatureState::Hypothermic => format!("{} is freezing", name),
+        BodyTemperatureState::Hot => format!("{} is sweating", name),
+        BodyTemperatureState::Hyperthermic => format!("{} is overheating", name),
+    }
+}
@@ -50,6 +50,12 @@ pub fn get_cardinal_neighbours_with_z(position: Vector3i) -> Vec<Vector3i> {
     ]
 }
 
+//On the same tile or any tile around it, one level up and down included
+pub fn within_reach(position: Vector3i, target: Vector3i) -> bool {
+    let delta = target - position;
+    delta.x.abs() <= 1 && delta.y.abs() <= 1 && delta.z.abs() <= 1
+}
+
 #[allow(dead_code)]
 pub fn position_in_over_under(position: Vector3i, target: Vector3i) -> bool {
     position == target
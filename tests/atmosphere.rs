@@ -0,0 +1,186 @@
+mod common;
+
+use std::collections::HashMap;
+
+use rltk::RGB;
+use specs::prelude::*;
+use weirdark::{
+    entities::{
+        atmospherics::{Atmosphere, Gas},
+        components::{Blocker, Door},
+    },
+    graphics::char_to_glyph,
+    map::{zones::AtmosphereZones, Map},
+    spawner,
+    systems::atmosphere_system::AtmosphereSystem,
+    vectors::Vector3i,
+};
+
+const DOOR_X: i32 = 3;
+
+fn split_corridor(ecs: &mut World, door_open: bool) {
+    //Left of the door is pressurised, right of it is vacuum
+    let map = common::corridor_map(7, |x| {
+        if x < DOOR_X {
+            Atmosphere::new_stp()
+        } else {
+            Atmosphere::new_vacuume()
+        }
+    });
+    common::set_map(ecs, map);
+
+    spawner::door(
+        ecs,
+        Vector3i::new(DOOR_X, 0, 0),
+        door_open,
+        RGB::named(rltk::GRAY).to_rgba(1.0),
+        char_to_glyph('/'),
+        char_to_glyph('+'),
+    );
+}
+
+fn mols_at(ecs: &World, x: i32) -> f32 {
+    ecs.fetch::<Map>().tiles[&Vector3i::new(x, 0, 0)]
+        .atmosphere
+        .get_total_mols()
+}
+
+#[test]
+fn mols_are_conserved_across_open_door() {
+    let mut ecs = common::test_world();
+    split_corridor(&mut ecs, true);
+
+    let mols_before = common::total_mols(&ecs.fetch::<Map>());
+
+    common::run_system(&mut ecs, &mut AtmosphereSystem {}, 50);
+
+    let mols_after = common::total_mols(&ecs.fetch::<Map>());
+
+    assert!(
+        (mols_before - mols_after).abs() / mols_before < 1e-3,
+        "mols before {} and after {}",
+        mols_before,
+        mols_after
+    );
+    assert!(mols_at(&ecs, 6) > 0.0, "gas never reached the far side");
+}
+
+#[test]
+fn closed_door_holds_pressure() {
+    let mut ecs = common::test_world();
+    split_corridor(&mut ecs, false);
+
+    common::run_system(&mut ecs, &mut AtmosphereSystem {}, 50);
+
+    for x in DOOR_X..7 {
+        assert_eq!(mols_at(&ecs, x), 0.0, "gas leaked to x = {}", x);
+    }
+}
+
+#[test]
+fn closed_door_splits_zones() {
+    let mut ecs = common::test_world();
+    split_corridor(&mut ecs, false);
+
+    common::run_system(&mut ecs, &mut AtmosphereSystem {}, 1);
+
+    let zones = ecs.fetch::<AtmosphereZones>();
+    let map = ecs.fetch::<Map>();
+    let left = map.tiles[&Vector3i::new(0, 0, 0)].atmosphere.space_id;
+    let right = map.tiles[&Vector3i::new(6, 0, 0)].atmosphere.space_id;
+
+    //The door tile is sealed in by its own blocker
+    assert_eq!(zones.zone_count(), 3);
+    assert_ne!(left, right);
+    assert!(zones.is_settled(left));
+    assert!(zones.is_settled(right));
+}
+
+#[test]
+fn opening_door_merges_zones_and_settles() {
+    let mut ecs = common::test_world();
+    split_corridor(&mut ecs, false);
+
+    common::run_system(&mut ecs, &mut AtmosphereSystem {}, 1);
+
+    //Opening the door removes its blocker, which is what StateAlignSystem does
+    let door = {
+        let doors = ecs.read_storage::<Door>();
+        let entities = ecs.entities();
+        (&entities, &doors).join().next().unwrap().0
+    };
+    ecs.write_storage::<Blocker>().remove(door);
+
+    common::run_system(&mut ecs, &mut AtmosphereSystem {}, 1);
+
+    let space_id = ecs.fetch::<Map>().tiles[&Vector3i::new(0, 0, 0)]
+        .atmosphere
+        .space_id;
+    assert_eq!(ecs.fetch::<AtmosphereZones>().zone_count(), 1);
+    assert!(!ecs.fetch::<AtmosphereZones>().is_settled(space_id));
+
+    common::run_system(&mut ecs, &mut AtmosphereSystem {}, 500);
+
+    assert!(ecs.fetch::<AtmosphereZones>().is_settled(space_id));
+    assert!((mols_at(&ecs, 0) - mols_at(&ecs, 6)).abs() < 1e-3);
+}
+
+#[test]
+fn settled_zone_equalises_as_one_pool() {
+    let mut ecs = common::test_world();
+    common::set_map(&mut ecs, common::corridor_map(7, |_| Atmosphere::new_stp()));
+
+    common::run_system(&mut ecs, &mut AtmosphereSystem {}, 1);
+
+    let mols_before = common::total_mols(&ecs.fetch::<Map>());
+
+    //Take gas out of one end, the whole room should drop at once
+    ecs.fetch_mut::<Map>()
+        .tiles
+        .get_mut(&Vector3i::new(0, 0, 0))
+        .unwrap()
+        .atmosphere
+        .remove_gas(7.0);
+
+    common::run_system(&mut ecs, &mut AtmosphereSystem {}, 1);
+
+    let expected = (mols_before - 7.0) / 7.0;
+    for x in 0..7 {
+        assert!((mols_at(&ecs, x) - expected).abs() < 1e-3);
+    }
+}
+
+#[test]
+fn mixing_temperature_is_heat_capacity_weighted() {
+    //Equal mols of hot hydrogen and cold carbon dioxide, CO2 holds more heat per mol
+    let mut atmosphere = Atmosphere::new_vacuume();
+    atmosphere.set_gasses(&HashMap::from([(Gas::CarbonDioxide, 10.0)]), 200.0);
+    atmosphere.update_gas(&HashMap::from([(Gas::Hydrogen, 10.0)]), 400.0);
+
+    let co2 = Gas::CarbonDioxide.properties().heat_capacity;
+    let hydrogen = Gas::Hydrogen.properties().heat_capacity;
+    let expected = (co2 * 200.0 + hydrogen * 400.0) / (co2 + hydrogen);
+
+    assert!((atmosphere.temperature - expected).abs() < 0.01);
+    assert!(atmosphere.temperature < 300.0);
+}
+
+#[test]
+fn added_heat_raises_temperature() {
+    let mut atmosphere = Atmosphere::new_stp();
+    let start = atmosphere.temperature;
+    let heat_capacity = atmosphere.get_heat_capacity();
+
+    atmosphere.add_heat(heat_capacity * 10.0);
+
+    assert!((atmosphere.temperature - (start + 10.0)).abs() < 0.01);
+}
+
+#[test]
+fn toxic_gasses_are_found_by_partial_pressure() {
+    let mut atmosphere = Atmosphere::new_stp();
+    assert!(atmosphere.get_toxic_gasses().is_empty());
+
+    atmosphere.update_gas(&HashMap::from([(Gas::CarbonMonoxide, 0.5)]), atmosphere.temperature);
+    assert_eq!(atmosphere.get_toxic_gasses(), vec![Gas::CarbonMonoxide]);
+}
@@ -0,0 +1,87 @@
+mod common;
+
+use std::collections::HashMap;
+
+use specs::prelude::*;
+use weirdark::{
+    entities::{
+        atmospherics::{Atmosphere, Gas},
+        biology::{VitalState, Vitals},
+        intents::MoveIntent,
+    },
+    raws::spawn_named,
+    systems::biology_system::BiologySystem,
+    vectors::Vector3i,
+};
+
+fn breather_in(ecs: &mut World, atmosphere: Atmosphere) -> Entity {
+    let map = common::corridor_map(1, move |_| atmosphere.clone());
+    common::set_map(ecs, map);
+
+    spawn_named(ecs, "player", Vector3i::new(0, 0, 0)).unwrap()
+}
+
+fn state_of(ecs: &World, entity: Entity) -> VitalState {
+    ecs.read_storage::<Vitals>().get(entity).unwrap().state
+}
+
+#[test]
+fn breathing_uses_oxygen_every_turn() {
+    let mut ecs = common::test_world();
+    breather_in(&mut ecs, Atmosphere::new_stp());
+    let oxygen = common::gas_at(&ecs, Vector3i::new(0, 0, 0), Gas::Oxygen);
+
+    common::run_system(&mut ecs, &mut BiologySystem {}, 10);
+
+    assert!(common::gas_at(&ecs, Vector3i::new(0, 0, 0), Gas::Oxygen) < oxygen);
+    assert!(common::gas_at(&ecs, Vector3i::new(0, 0, 0), Gas::CarbonDioxide) > 0.0);
+}
+
+#[test]
+fn healthy_air_keeps_breather_healthy() {
+    let mut ecs = common::test_world();
+    let player = breather_in(&mut ecs, Atmosphere::new_stp());
+
+    common::run_system(&mut ecs, &mut BiologySystem {}, 20);
+
+    assert_eq!(state_of(&ecs, player), VitalState::Healthy);
+}
+
+#[test]
+fn vacuum_suffocates_and_drops_intents() {
+    let mut ecs = common::test_world();
+    let player = breather_in(&mut ecs, Atmosphere::new_vacuume());
+
+    let mut passed_out = false;
+    for _ in 0..100 {
+        common::run_system(&mut ecs, &mut BiologySystem {}, 1);
+
+        if state_of(&ecs, player) == VitalState::Unconscious {
+            passed_out = true;
+            let _ = ecs
+                .write_storage::<MoveIntent>()
+                .insert(player, MoveIntent::new(Vector3i::new(0, 0, 0), Vector3i::E));
+            common::run_system(&mut ecs, &mut BiologySystem {}, 1);
+            assert!(ecs.read_storage::<MoveIntent>().get(player).is_none());
+        }
+    }
+
+    assert!(passed_out);
+    assert_eq!(state_of(&ecs, player), VitalState::Dead);
+    assert!(common::logged(&ecs, "Player feels dizzy"));
+    assert!(common::logged(&ecs, "Player passes out"));
+    assert!(common::logged(&ecs, "Player dies"));
+}
+
+#[test]
+fn carbon_dioxide_makes_breather_dizzy() {
+    let mut ecs = common::test_world();
+    let mut atmosphere = Atmosphere::new_stp();
+    atmosphere.update_gas(&HashMap::from([(Gas::CarbonDioxide, 3.0)]), atmosphere.temperature);
+    let player = breather_in(&mut ecs, atmosphere);
+
+    common::run_system(&mut ecs, &mut BiologySystem {}, 30);
+
+    assert_eq!(state_of(&ecs, player), VitalState::Dizzy);
+    assert!(common::logged(&ecs, "Player chokes on the carbon dioxide"));
+}
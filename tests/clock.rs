@@ -0,0 +1,182 @@
+mod common;
+
+use rltk::RGBA;
+use specs::prelude::*;
+use weirdark::{
+    clock::{EventScheduler, GameClock, ScheduledEvent},
+    entities::{
+        atmospherics::Atmosphere,
+        components::{AutoClose, Door, DOOR_CLOSE_TURNS},
+        intents::{Initiative, InteractIntent},
+        power_components::PowerSwitch,
+    },
+    gamelog::GameLog,
+    raws::spawn_named,
+    spawner,
+    systems::{clock_system::ClockSystem, event_system::EventSystem},
+    vectors::Vector3i,
+};
+
+fn schedule_in_turns(ecs: &mut World, turns: u64, event: ScheduledEvent) {
+    let clock = ecs.fetch::<GameClock>().clone();
+    ecs.fetch_mut::<EventScheduler>()
+        .schedule_in_turns(&clock, turns, event);
+}
+
+#[test]
+fn clock_counts_turns_and_ship_time() {
+    let mut ecs = common::test_world();
+
+    common::run_system(&mut ecs, &mut ClockSystem {}, 3725);
+
+    let clock = ecs.fetch::<GameClock>();
+    assert_eq!(clock.turn, 3725);
+    assert_eq!(clock.ship_time(), "Day 1 01:02:05");
+}
+
+#[test]
+fn scheduled_door_closes_on_its_turn() {
+    let mut ecs = common::test_world();
+    common::set_map(&mut ecs, common::corridor_map(4, |_| Atmosphere::new_stp()));
+    let door = spawner::door(
+        &mut ecs,
+        Vector3i::new(2, 0, 0),
+        true,
+        RGBA::from_f32(0.5, 0.5, 0.5, 1.0),
+        0,
+        0,
+    );
+    schedule_in_turns(
+        &mut ecs,
+        10,
+        ScheduledEvent::CloseDoor(Vector3i::new(2, 0, 0)),
+    );
+
+    common::run_system(&mut ecs, &mut ClockSystem {}, 9);
+    assert!(ecs.read_storage::<Door>().get(door).unwrap().open);
+
+    common::run_system(&mut ecs, &mut ClockSystem {}, 1);
+    assert!(!ecs.read_storage::<Door>().get(door).unwrap().open);
+    assert!(ecs.fetch::<EventScheduler>().is_empty());
+}
+
+//A closed door next to the player, opened by hand on the first run of the event system
+fn door_opened_by_hand(ecs: &mut World, auto_close: Option<u64>) -> Entity {
+    common::set_map(ecs, common::corridor_map(4, |_| Atmosphere::new_stp()));
+    let door = spawner::door(
+        ecs,
+        Vector3i::new(2, 0, 0),
+        false,
+        RGBA::from_f32(0.5, 0.5, 0.5, 1.0),
+        0,
+        0,
+    );
+    if let Some(turns) = auto_close {
+        let _ = ecs
+            .write_storage::<AutoClose>()
+            .insert(door, AutoClose::new(turns));
+    }
+    let position = Vector3i::new(1, 0, 0);
+    let player = spawn_named(ecs, "player", position).unwrap();
+    common::set_player(ecs, player, position);
+
+    let interaction_id = ecs.read_storage::<Door>().get(door).unwrap().interaction_id;
+    let _ = ecs.write_storage::<InteractIntent>().insert(
+        player,
+        InteractIntent::new(player, door, interaction_id, "Open".to_string(), 1.0),
+    );
+    common::run_system(ecs, &mut EventSystem {}, 1);
+    assert!(ecs.read_storage::<Door>().get(door).unwrap().open);
+
+    door
+}
+
+#[test]
+fn auto_closing_door_swings_shut() {
+    let mut ecs = common::test_world();
+    let door = door_opened_by_hand(&mut ecs, Some(10));
+
+    common::run_system(&mut ecs, &mut ClockSystem {}, 9);
+    assert!(ecs.read_storage::<Door>().get(door).unwrap().open);
+
+    common::run_system(&mut ecs, &mut ClockSystem {}, 1);
+    assert!(!ecs.read_storage::<Door>().get(door).unwrap().open);
+}
+
+#[test]
+fn blocked_door_waits_its_own_auto_close_turns() {
+    let mut ecs = common::test_world();
+    let door = door_opened_by_hand(&mut ecs, Some(5));
+    let blocker = ecs
+        .create_entity()
+        .with(Vector3i::new(2, 0, 0))
+        .with(Initiative::new(0.0))
+        .build();
+
+    common::run_system(&mut ecs, &mut ClockSystem {}, 5);
+    assert!(ecs.read_storage::<Door>().get(door).unwrap().open);
+
+    ecs.delete_entity(blocker).unwrap();
+    ecs.maintain();
+
+    common::run_system(&mut ecs, &mut ClockSystem {}, 4);
+    assert!(ecs.read_storage::<Door>().get(door).unwrap().open);
+
+    common::run_system(&mut ecs, &mut ClockSystem {}, 1);
+    assert!(!ecs.read_storage::<Door>().get(door).unwrap().open);
+}
+
+#[test]
+fn plain_door_stays_open() {
+    let mut ecs = common::test_world();
+    let door = door_opened_by_hand(&mut ecs, None);
+
+    common::run_system(&mut ecs, &mut ClockSystem {}, DOOR_CLOSE_TURNS as usize * 2);
+    assert!(ecs.read_storage::<Door>().get(door).unwrap().open);
+    assert!(ecs.fetch::<EventScheduler>().is_empty());
+}
+
+#[test]
+fn scheduled_switch_and_messages_fire_in_order() {
+    let mut ecs = common::test_world();
+    common::set_map(&mut ecs, common::corridor_map(4, |_| Atmosphere::new_stp()));
+    let breaker = spawn_named(&mut ecs, "breaker_box", Vector3i::new(1, 0, 0)).unwrap();
+    ecs.write_storage::<PowerSwitch>()
+        .get_mut(breaker)
+        .unwrap()
+        .on = false;
+
+    {
+        let clock = ecs.fetch::<GameClock>().clone();
+        let mut scheduler = ecs.fetch_mut::<EventScheduler>();
+        scheduler.schedule_in_seconds(&clock, 2.5, ScheduledEvent::Message("second".to_string()));
+        scheduler.schedule_in_turns(&clock, 1, ScheduledEvent::Message("first".to_string()));
+        scheduler.schedule_in_seconds(
+            &clock,
+            30.0,
+            ScheduledEvent::SetSwitch {
+                position: Vector3i::new(1, 0, 0),
+                on: true,
+            },
+        );
+    }
+
+    common::run_system(&mut ecs, &mut ClockSystem {}, 3);
+    assert_eq!(ecs.fetch::<GameLog>().entries, vec!["first", "second"]);
+    assert!(!ecs.read_storage::<PowerSwitch>().get(breaker).unwrap().on);
+
+    common::run_system(&mut ecs, &mut ClockSystem {}, 27);
+    assert!(ecs.read_storage::<PowerSwitch>().get(breaker).unwrap().on);
+}
+
+#[test]
+fn overdue_events_fire_on_next_tick() {
+    let mut ecs = common::test_world();
+    common::run_system(&mut ecs, &mut ClockSystem {}, 5);
+    ecs.fetch_mut::<EventScheduler>()
+        .schedule_at_turn(2, ScheduledEvent::Message("late".to_string()));
+
+    common::run_system(&mut ecs, &mut ClockSystem {}, 1);
+
+    assert_eq!(ecs.fetch::<GameLog>().entries, vec!["late"]);
+}
@@ -0,0 +1,155 @@
+#![allow(dead_code)]
+
+use rltk::RGB;
+use specs::{prelude::*, saveload::SimpleMarkerAllocator};
+use weirdark::{
+    clock::{EventScheduler, GameClock},
+    entities::{
+        atmospherics::{Atmosphere, Gas},
+        components::SerializeThis,
+        network::AlarmQueue,
+        power_components::{PowerNode, Wire, WireGauge},
+        power_graph::PowerGraph,
+    },
+    gamelog::GameLog,
+    hazards::HazardDirector,
+    map::{
+        components::Tile, pressure::PressureForces, structure::DamageQueue, tile_blueprints,
+        zones::AtmosphereZones, Map,
+    },
+    raws,
+    register_components,
+    states::RunState,
+    vectors::Vector3i,
+};
+
+//Builds an empty world with every component registered and the resources the systems expect,
+//the caller adds the map through set_map
+pub fn test_world() -> World {
+    tile_blueprints::load_blueprints().expect("Error loading tile blueprints");
+    raws::load_raws().expect("Error loading raws");
+
+    let mut ecs = World::new();
+    register_components(&mut ecs);
+
+    ecs.insert(SimpleMarkerAllocator::<SerializeThis>::new());
+    ecs.insert(Map::new());
+    ecs.insert(AtmosphereZones::new());
+    ecs.insert(PressureForces::new());
+    ecs.insert(DamageQueue::new());
+    ecs.insert(PowerGraph::new());
+    ecs.insert(AlarmQueue::new());
+    ecs.insert(GameClock::new());
+    ecs.insert(EventScheduler::new());
+    ecs.insert(HazardDirector::new());
+    ecs.insert(GameLog {
+        entries: Vec::new(),
+    });
+    ecs.insert(RunState::Ticking);
+    ecs.insert(Vector3i::new_equi(0));
+
+    //Systems fetch the player entity, tests that need a real player replace it
+    let placeholder = ecs.create_entity().build();
+    ecs.insert(placeholder);
+
+    ecs
+}
+
+pub fn set_map(ecs: &mut World, map: Map) {
+    ecs.insert(map);
+    ecs.insert(AtmosphereZones::new());
+}
+
+pub fn set_player(ecs: &mut World, player: Entity, position: Vector3i) {
+    ecs.insert(player);
+    ecs.insert(position);
+}
+
+//A straight east-west corridor on z = 0, closed by hull at both ends
+pub fn corridor_map(length: i32, atmosphere: impl Fn(i32) -> Atmosphere) -> Map {
+    let mut map = Map::new();
+    let floor = tile_blueprints::get_tile("breathable_atmosphere").expect("Missing blueprint");
+    let hull = tile_blueprints::get_tile("hull").expect("Missing blueprint");
+
+    for x in 0..length {
+        let mut tile = floor.clone();
+        tile.atmosphere = atmosphere(x);
+        map.tiles.insert(Vector3i::new(x, 0, 0), tile);
+    }
+
+    map.tiles.insert(Vector3i::new(-1, 0, 0), hull.clone());
+    map.tiles.insert(Vector3i::new(length, 0, 0), hull);
+
+    map
+}
+
+pub fn run_system<S>(ecs: &mut World, system: &mut S, ticks: usize)
+where
+    S: for<'a> RunNow<'a>,
+{
+    for _ in 0..ticks {
+        system.run_now(ecs);
+        ecs.maintain();
+    }
+}
+
+pub fn total_mols(map: &Map) -> f32 {
+    map.tiles
+        .values()
+        .map(|tile: &Tile| tile.atmosphere.get_total_mols())
+        .sum()
+}
+
+//Red wire on every position, runs are told apart by the colour name they are given rather than
+//by the colour they are drawn in
+pub fn lay_wire_at(
+    ecs: &mut World,
+    positions: &[Vector3i],
+    color_name: &str,
+    gauge: WireGauge,
+    data: bool,
+) {
+    for position in positions.iter() {
+        let mut wire = Wire::new(
+            RGB::named(rltk::RED).to_rgba(1.0),
+            color_name.to_string(),
+            data,
+        );
+        wire.gauge = gauge;
+
+        ecs.create_entity()
+            .with(*position)
+            .with(wire)
+            .with(PowerNode::new())
+            .build();
+    }
+}
+
+//Standard red wire along the corridor from x = from to x = to
+pub fn lay_wire(ecs: &mut World, from: i32, to: i32, data: bool) {
+    let positions: Vec<Vector3i> = (from..=to).map(|x| Vector3i::new(x, 0, 0)).collect();
+    lay_wire_at(ecs, &positions, "RED", WireGauge::Standard, data);
+}
+
+pub fn gas_at(ecs: &World, position: Vector3i, gas: Gas) -> f32 {
+    let map = ecs.fetch::<Map>();
+    let tile = map.tiles.get(&position).unwrap();
+    tile.atmosphere.gasses.get(&gas).cloned().unwrap_or(0.0)
+}
+
+pub fn position_of(ecs: &World, entity: Entity) -> Vector3i {
+    *ecs.read_storage::<Vector3i>().get(entity).unwrap()
+}
+
+//Whether any game log entry contains the text
+pub fn log_mentions(ecs: &World, text: &str) -> bool {
+    ecs.fetch::<GameLog>()
+        .entries
+        .iter()
+        .any(|entry| entry.contains(text))
+}
+
+//Whether the exact message was written to the game log
+pub fn logged(ecs: &World, message: &str) -> bool {
+    ecs.fetch::<GameLog>().entries.iter().any(|entry| entry == message)
+}
@@ -0,0 +1,177 @@
+mod common;
+
+use specs::prelude::*;
+use weirdark::{
+    entities::{
+        atmospherics::Atmosphere,
+        components::{Container, Door, InContainer},
+        construction::{Construction, ConstructionMaterial},
+        intents::{BuildIntent, DeconstructIntent},
+    },
+    gamelog::GameLog,
+    map::{structure::Material, tile_blueprints, zones::AtmosphereZones, Map},
+    raws::spawn_named,
+    spawner,
+    systems::event_system::EventSystem,
+    vectors::Vector3i,
+};
+
+const BREACH: Vector3i = Vector3i { x: -1, y: 0, z: 0 };
+
+//A builder standing in a corridor with the given stock in their pockets
+fn builder(ecs: &mut World, position: Vector3i, plating: usize) -> Entity {
+    let entity = spawn_named(ecs, "player", position).unwrap();
+
+    for _ in 0..plating {
+        let item = spawner::steel_plating(ecs, position);
+        assert!(spawner::put_item_in_container(ecs, item, entity));
+    }
+
+    common::set_player(ecs, entity, position);
+    entity
+}
+
+fn carried(ecs: &World, entity: Entity, material: Material) -> usize {
+    let container_id = ecs.read_storage::<Container>().get(entity).unwrap().id;
+
+    (
+        &ecs.read_storage::<ConstructionMaterial>(),
+        &ecs.read_storage::<InContainer>(),
+    )
+        .join()
+        .filter(|(construction_material, in_container)| {
+            construction_material.material == material && in_container.owner == container_id
+        })
+        .count()
+}
+
+#[test]
+fn plating_patches_a_breach() {
+    let mut ecs = common::test_world();
+    let mut map = common::corridor_map(4, |_| Atmosphere::new_stp());
+    map.tiles
+        .insert(BREACH, tile_blueprints::get_tile("vacuume").unwrap());
+    common::set_map(&mut ecs, map);
+    let entity = builder(&mut ecs, Vector3i::new(0, 0, 0), 2);
+    ecs.fetch_mut::<AtmosphereZones>().dirty = false;
+
+    let _ = ecs
+        .write_storage::<BuildIntent>()
+        .insert(entity, BuildIntent::new(entity, BREACH, Construction::Hull));
+
+    //Nothing changes until the work is done
+    common::run_system(&mut ecs, &mut EventSystem {}, 9);
+    assert!(ecs.fetch::<Map>().tiles.get(&BREACH).unwrap().passable);
+
+    common::run_system(&mut ecs, &mut EventSystem {}, 1);
+
+    {
+        let map = ecs.fetch::<Map>();
+        let tile = map.tiles.get(&BREACH).unwrap();
+        assert!(!tile.passable && tile.airtight);
+        assert_eq!(tile.structure.material, Material::Steel);
+    }
+    assert_eq!(carried(&ecs, entity, Material::Steel), 1);
+    assert!(ecs.fetch::<AtmosphereZones>().dirty);
+    assert!(ecs.read_storage::<BuildIntent>().get(entity).is_none());
+}
+
+#[test]
+fn building_without_materials_is_refused() {
+    let mut ecs = common::test_world();
+    common::set_map(&mut ecs, common::corridor_map(4, |_| Atmosphere::new_stp()));
+    let entity = builder(&mut ecs, Vector3i::new(1, 0, 0), 1);
+
+    let _ = ecs.write_storage::<BuildIntent>().insert(
+        entity,
+        BuildIntent::new(entity, Vector3i::new(2, 0, 0), Construction::GlassHull),
+    );
+
+    common::run_system(&mut ecs, &mut EventSystem {}, 1);
+
+    assert!(ecs.read_storage::<BuildIntent>().get(entity).is_none());
+    assert!(
+        ecs.fetch::<Map>()
+            .tiles
+            .get(&Vector3i::new(2, 0, 0))
+            .unwrap()
+            .passable
+    );
+    assert_eq!(carried(&ecs, entity, Material::Steel), 1);
+    assert_eq!(ecs.fetch::<GameLog>().entries.len(), 1);
+}
+
+#[test]
+fn deconstructed_hull_leaves_plating_and_open_space() {
+    let mut ecs = common::test_world();
+    common::set_map(&mut ecs, common::corridor_map(4, |_| Atmosphere::new_stp()));
+    let position = Vector3i::new(3, 0, 0);
+    let entity = builder(&mut ecs, position, 0);
+    let hull = Vector3i::new(4, 0, 0);
+
+    let _ = ecs
+        .write_storage::<DeconstructIntent>()
+        .insert(entity, DeconstructIntent::new(entity, hull));
+
+    common::run_system(&mut ecs, &mut EventSystem {}, 8);
+
+    assert!(ecs.fetch::<Map>().tiles.get(&hull).unwrap().passable);
+
+    let salvage = (
+        &ecs.read_storage::<ConstructionMaterial>(),
+        &ecs.read_storage::<Vector3i>(),
+    )
+        .join()
+        .filter(|(construction_material, item_position)| {
+            construction_material.material == Material::Steel && **item_position == position
+        })
+        .count();
+    assert_eq!(salvage, 1);
+}
+
+#[test]
+fn doors_are_built_and_taken_apart() {
+    let mut ecs = common::test_world();
+    common::set_map(&mut ecs, common::corridor_map(4, |_| Atmosphere::new_stp()));
+    let entity = builder(&mut ecs, Vector3i::new(1, 0, 0), 1);
+    let doorway = Vector3i::new(2, 0, 0);
+
+    let _ = ecs.write_storage::<BuildIntent>().insert(
+        entity,
+        BuildIntent::new(entity, doorway, Construction::Door),
+    );
+    common::run_system(&mut ecs, &mut EventSystem {}, 6);
+
+    let doors = |ecs: &World| {
+        (&ecs.read_storage::<Door>(), &ecs.read_storage::<Vector3i>())
+            .join()
+            .filter(|(_, position)| **position == doorway)
+            .count()
+    };
+    assert_eq!(doors(&ecs), 1);
+    assert_eq!(carried(&ecs, entity, Material::Steel), 0);
+
+    let _ = ecs
+        .write_storage::<DeconstructIntent>()
+        .insert(entity, DeconstructIntent::new(entity, doorway));
+    common::run_system(&mut ecs, &mut EventSystem {}, 8);
+
+    //The door is gone and the tile under it untouched
+    assert_eq!(doors(&ecs), 0);
+    assert!(ecs.fetch::<Map>().tiles.get(&doorway).unwrap().passable);
+    assert!(ecs.fetch::<AtmosphereZones>().dirty);
+}
+
+#[test]
+fn plating_that_does_not_fit_stays_on_the_floor() {
+    let mut ecs = common::test_world();
+    common::set_map(&mut ecs, common::corridor_map(3, |_| Atmosphere::new_stp()));
+    let position = Vector3i::new(1, 0, 0);
+    let entity = builder(&mut ecs, position, 4);
+
+    let spare = spawner::steel_plating(&mut ecs, position);
+
+    assert!(!spawner::put_item_in_container(&mut ecs, spare, entity));
+    assert_eq!(carried(&ecs, entity, Material::Steel), 4);
+    assert_eq!(*ecs.read_storage::<Vector3i>().get(spare).unwrap(), position);
+}
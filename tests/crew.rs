@@ -0,0 +1,108 @@
+mod common;
+
+use rltk::RGBA;
+use specs::prelude::*;
+use weirdark::{
+    entities::{
+        atmospherics::Atmosphere,
+        components::{Door, InContainer},
+        crew::CrewAi,
+        power_components::PowerSwitch,
+    },
+    raws::spawn_named,
+    spawner,
+    systems::{
+        crew_ai_system::CrewAiSystem, event_system::EventSystem,
+        state_align_system::StateAlignSystem,
+    },
+    vectors::Vector3i,
+};
+
+fn run_crew(ecs: &mut World, ticks: usize) {
+    for _ in 0..ticks {
+        common::run_system(ecs, &mut CrewAiSystem {}, 1);
+        common::run_system(ecs, &mut EventSystem {}, 1);
+        common::run_system(ecs, &mut StateAlignSystem {}, 1);
+    }
+}
+
+fn door(ecs: &mut World, position: Vector3i, open: bool) -> Entity {
+    spawner::door(
+        ecs,
+        position,
+        open,
+        RGBA::from_f32(0.5, 0.5, 0.5, 1.0),
+        0,
+        0,
+    )
+}
+
+#[test]
+fn crew_walks_to_breaker_and_resets_it() {
+    let mut ecs = common::test_world();
+    common::set_map(&mut ecs, common::corridor_map(8, |_| Atmosphere::new_stp()));
+    let crew = spawner::crew(&mut ecs, Vector3i::new(0, 0, 0));
+    let breaker = spawn_named(&mut ecs, "breaker_box", Vector3i::new(6, 0, 0)).unwrap();
+    ecs.write_storage::<PowerSwitch>().get_mut(breaker).unwrap().on = false;
+
+    run_crew(&mut ecs, 10);
+
+    assert!(ecs.read_storage::<PowerSwitch>().get(breaker).unwrap().on);
+    assert!(common::position_of(&ecs, crew).x >= 5);
+    assert!(ecs.read_storage::<CrewAi>().get(crew).unwrap().goal.is_none());
+}
+
+#[test]
+fn crew_closes_door_to_breach() {
+    let mut ecs = common::test_world();
+    common::set_map(
+        &mut ecs,
+        common::corridor_map(6, |x| {
+            if x < 4 {
+                Atmosphere::new_stp()
+            } else {
+                Atmosphere::new_vacuume()
+            }
+        }),
+    );
+    let crew = spawner::crew(&mut ecs, Vector3i::new(0, 0, 0));
+    let breached_door = door(&mut ecs, Vector3i::new(3, 0, 0), true);
+
+    run_crew(&mut ecs, 10);
+
+    assert!(!ecs.read_storage::<Door>().get(breached_door).unwrap().open);
+    assert_eq!(common::position_of(&ecs, crew), Vector3i::new(2, 0, 0));
+}
+
+#[test]
+fn crew_opens_doors_to_fetch_items() {
+    let mut ecs = common::test_world();
+    common::set_map(&mut ecs, common::corridor_map(6, |_| Atmosphere::new_stp()));
+    let crew = spawner::crew(&mut ecs, Vector3i::new(0, 0, 0));
+    let closed_door = door(&mut ecs, Vector3i::new(2, 0, 0), false);
+    let item = spawner::test_item(&mut ecs, Vector3i::new(4, 0, 0));
+
+    run_crew(&mut ecs, 12);
+
+    assert!(ecs.read_storage::<Door>().get(closed_door).unwrap().open);
+    assert!(ecs.read_storage::<InContainer>().get(item).is_some());
+    assert!(ecs.read_storage::<Vector3i>().get(item).is_none());
+    assert!(common::position_of(&ecs, crew).x >= 3);
+}
+
+#[test]
+fn crew_movement_leaves_player_tracking_alone() {
+    let mut ecs = common::test_world();
+    common::set_map(&mut ecs, common::corridor_map(8, |_| Atmosphere::new_stp()));
+    let player = spawn_named(&mut ecs, "player", Vector3i::new(7, 0, 0)).unwrap();
+    common::set_player(&mut ecs, player, Vector3i::new(7, 0, 0));
+    let crew = spawner::crew(&mut ecs, Vector3i::new(0, 0, 0));
+    spawner::test_item(&mut ecs, Vector3i::new(3, 0, 0));
+
+    run_crew(&mut ecs, 5);
+
+    assert_ne!(common::position_of(&ecs, crew), Vector3i::new(0, 0, 0));
+    assert_eq!(*ecs.fetch::<Vector3i>(), Vector3i::new(7, 0, 0));
+    assert_eq!(common::position_of(&ecs, player), Vector3i::new(7, 0, 0));
+}
+
@@ -0,0 +1,96 @@
+mod common;
+
+use specs::prelude::*;
+use weirdark::{
+    entities::{
+        atmospherics::Atmosphere,
+        components::Name,
+        intents::{Initiative, MoveIntent},
+    },
+    gamelog::GameLog,
+    spawner,
+    systems::{atmosphere_system::AtmosphereSystem, decompression_system::DecompressionSystem},
+    vectors::Vector3i,
+};
+
+//Pressurised on the west side, open to vacuum on the east side
+fn breached_corridor(ecs: &mut World) {
+    let map = common::corridor_map(8, |x| {
+        if x < 4 {
+            Atmosphere::new_stp()
+        } else {
+            Atmosphere::new_vacuume()
+        }
+    });
+    common::set_map(ecs, map);
+}
+
+fn tick(ecs: &mut World) {
+    common::run_system(ecs, &mut AtmosphereSystem {}, 1);
+    common::run_system(ecs, &mut DecompressionSystem {}, 1);
+}
+
+#[test]
+fn items_are_swept_toward_breach() {
+    let mut ecs = common::test_world();
+    breached_corridor(&mut ecs);
+    let item = spawner::test_item(&mut ecs, Vector3i::new(3, 0, 0));
+
+    for _ in 0..5 {
+        tick(&mut ecs);
+    }
+
+    let position = *ecs.read_storage::<Vector3i>().get(item).unwrap();
+    assert!(position.x > 3, "item stayed at {:?}", position);
+    assert!(ecs
+        .fetch::<GameLog>()
+        .entries
+        .iter()
+        .any(|entry| entry.contains("swept toward the breach")));
+}
+
+#[test]
+fn characters_are_dragged_toward_breach() {
+    let mut ecs = common::test_world();
+    breached_corridor(&mut ecs);
+
+    let character = ecs
+        .create_entity()
+        .with(Vector3i::new(3, 0, 0))
+        .with(Initiative::new(0.0))
+        .with(Name::new("Crew".to_string()))
+        .build();
+
+    for _ in 0..3 {
+        tick(&mut ecs);
+    }
+
+    let move_intents = ecs.read_storage::<MoveIntent>();
+    let intent = move_intents.get(character).expect("no move intent was given");
+    assert_eq!(intent.delta, Vector3i::E);
+    //Announced when the pull starts, not every tick it lasts
+    assert_eq!(
+        ecs.fetch::<GameLog>()
+            .entries
+            .iter()
+            .filter(|entry| *entry == "Crew is dragged toward the breach")
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn sealed_room_does_not_push() {
+    let mut ecs = common::test_world();
+    common::set_map(&mut ecs, common::corridor_map(8, |_| Atmosphere::new_stp()));
+    let item = spawner::test_item(&mut ecs, Vector3i::new(3, 0, 0));
+
+    for _ in 0..5 {
+        tick(&mut ecs);
+    }
+
+    assert_eq!(
+        *ecs.read_storage::<Vector3i>().get(item).unwrap(),
+        Vector3i::new(3, 0, 0)
+    );
+}
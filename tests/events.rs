@@ -0,0 +1,183 @@
+mod common;
+
+use specs::prelude::*;
+use weirdark::{
+    entities::{
+        atmospherics::Atmosphere,
+        components::InContainer,
+        intents::{Initiative, Interrupt, MoveIntent, PickUpIntent},
+    },
+    map::pressure::{PressureForce, PressureForces},
+    player::continue_last_action,
+    raws::spawn_named,
+    spawner,
+    systems::event_system::{get_action_progress, get_default_interactions, EventSystem},
+    vectors::Vector3i,
+    states::RunState,
+};
+
+fn walker(ecs: &mut World, position: Vector3i, delta: Vector3i) -> Entity {
+    common::set_map(ecs, common::corridor_map(5, |_| Atmosphere::new_stp()));
+
+    let entity = ecs
+        .create_entity()
+        .with(position)
+        .with(Initiative::new(0.0))
+        .with(MoveIntent::new(position, delta))
+        .build();
+
+    common::set_player(ecs, entity, position);
+
+    entity
+}
+
+//A move that takes three turns, long enough to be interrupted
+fn slow_walker(ecs: &mut World) -> Entity {
+    let entity = walker(ecs, Vector3i::new(1, 0, 0), Vector3i::E);
+
+    {
+        let mut move_intents = ecs.write_storage::<MoveIntent>();
+        let intent = move_intents.get_mut(entity).unwrap();
+        intent.progress.cost = 3.0;
+        intent.progress.remaining_cost = 3.0;
+    }
+
+    entity
+}
+
+#[test]
+fn move_into_open_tile_succeeds() {
+    let mut ecs = common::test_world();
+    let entity = walker(&mut ecs, Vector3i::new(1, 0, 0), Vector3i::E);
+
+    common::run_system(&mut ecs, &mut EventSystem {}, 1);
+
+    assert_eq!(common::position_of(&ecs, entity), Vector3i::new(2, 0, 0));
+    assert!(ecs.read_storage::<MoveIntent>().get(entity).is_none());
+}
+
+#[test]
+fn move_into_blocker_is_rejected() {
+    let mut ecs = common::test_world();
+    let entity = walker(&mut ecs, Vector3i::new(1, 0, 0), Vector3i::E);
+    spawner::storage_cabinet(&mut ecs, Vector3i::new(2, 0, 0));
+
+    common::run_system(&mut ecs, &mut EventSystem {}, 1);
+
+    assert_eq!(common::position_of(&ecs, entity), Vector3i::new(1, 0, 0));
+    assert!(ecs.read_storage::<MoveIntent>().get(entity).is_none());
+}
+
+#[test]
+fn move_into_hull_is_rejected() {
+    let mut ecs = common::test_world();
+    let entity = walker(&mut ecs, Vector3i::new(0, 0, 0), Vector3i::W);
+
+    common::run_system(&mut ecs, &mut EventSystem {}, 1);
+
+    assert_eq!(common::position_of(&ecs, entity), Vector3i::new(0, 0, 0));
+}
+
+#[test]
+fn slow_intent_waits_until_paid() {
+    let mut ecs = common::test_world();
+    let entity = walker(&mut ecs, Vector3i::new(1, 0, 0), Vector3i::E);
+    ecs.write_storage::<MoveIntent>()
+        .get_mut(entity)
+        .unwrap()
+        .progress
+        .remaining_cost = 2.0;
+
+    common::run_system(&mut ecs, &mut EventSystem {}, 1);
+    assert_eq!(common::position_of(&ecs, entity), Vector3i::new(1, 0, 0));
+
+    common::run_system(&mut ecs, &mut EventSystem {}, 1);
+    assert_eq!(common::position_of(&ecs, entity), Vector3i::new(2, 0, 0));
+}
+
+#[test]
+fn pick_up_of_taken_item_is_dropped() {
+    let mut ecs = common::test_world();
+    common::set_map(&mut ecs, common::corridor_map(5, |_| Atmosphere::new_stp()));
+    let first = spawn_named(&mut ecs, "crew", Vector3i::new(1, 0, 0)).unwrap();
+    let second = spawn_named(&mut ecs, "crew", Vector3i::new(3, 0, 0)).unwrap();
+    let item = spawner::test_item(&mut ecs, Vector3i::new(2, 0, 0));
+
+    {
+        let mut pick_up_intents = ecs.write_storage::<PickUpIntent>();
+        let _ = pick_up_intents.insert(first, PickUpIntent::new(first, item, 1.0));
+        let _ = pick_up_intents.insert(second, PickUpIntent::new(second, item, 1.0));
+    }
+
+    common::run_system(&mut ecs, &mut EventSystem {}, 1);
+
+    assert!(ecs.read_storage::<InContainer>().get(item).is_some());
+    assert!(ecs.read_storage::<PickUpIntent>().get(first).is_none());
+    assert!(ecs.read_storage::<PickUpIntent>().get(second).is_none());
+}
+
+#[test]
+fn picked_interaction_queues_its_intent() {
+    let mut ecs = common::test_world();
+    common::set_map(&mut ecs, common::corridor_map(5, |_| Atmosphere::new_stp()));
+    let crew = spawn_named(&mut ecs, "crew", Vector3i::new(1, 0, 0)).unwrap();
+    let item = spawner::test_item(&mut ecs, Vector3i::new(2, 0, 0));
+
+    let interactions = get_default_interactions(&ecs, item);
+    let pick_up = interactions
+        .iter()
+        .find(|interaction| interaction.description == "Pick up")
+        .unwrap();
+    pick_up.queue(&ecs, crew);
+
+    assert!(ecs.read_storage::<PickUpIntent>().get(crew).is_some());
+
+    common::run_system(&mut ecs, &mut EventSystem {}, 1);
+
+    assert!(ecs.read_storage::<InContainer>().get(item).is_some());
+}
+
+#[test]
+fn interrupted_action_pauses_and_continues() {
+    let mut ecs = common::test_world();
+    let entity = slow_walker(&mut ecs);
+
+    common::run_system(&mut ecs, &mut EventSystem {}, 1);
+    let _ = ecs
+        .write_storage::<Interrupt>()
+        .insert(entity, Interrupt::new("cancelled".to_string()));
+    common::run_system(&mut ecs, &mut EventSystem {}, 3);
+
+    let (_, progress, paused) = get_action_progress(&ecs, entity).unwrap();
+    assert!(paused);
+    assert!((progress - 1.0 / 3.0).abs() < 0.01);
+    assert_eq!(common::position_of(&ecs, entity), Vector3i::new(1, 0, 0));
+    assert!(*ecs.fetch::<RunState>() == RunState::AwaitingInput);
+
+    assert!(continue_last_action(&mut ecs) == RunState::Ticking);
+    common::run_system(&mut ecs, &mut EventSystem {}, 2);
+
+    assert_eq!(common::position_of(&ecs, entity), Vector3i::new(2, 0, 0));
+    assert!(get_action_progress(&ecs, entity).is_none());
+}
+
+#[test]
+fn rushing_air_interrupts_action() {
+    let mut ecs = common::test_world();
+    let entity = slow_walker(&mut ecs);
+    ecs.fetch_mut::<PressureForces>().forces.insert(
+        Vector3i::new(1, 0, 0),
+        PressureForce {
+            x: 5000.0,
+            y: 0.0,
+            z: 0.0,
+        },
+    );
+
+    common::run_system(&mut ecs, &mut EventSystem {}, 3);
+
+    let (_, progress, paused) = get_action_progress(&ecs, entity).unwrap();
+    assert!(paused);
+    assert_eq!(progress, 0.0);
+    assert_eq!(common::position_of(&ecs, entity), Vector3i::new(1, 0, 0));
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use std::collections::HashMap;
+
+use specs::prelude::*;
+use weirdark::{
+    entities::{
+        atmospherics::{Atmosphere, Gas},
+        biology::{VitalState, Vitals},
+        components::{Health, Illuminant},
+        fire::{Fire, Flammable},
+    },
+    gamelog::GameLog,
+    map::Map,
+    raws::spawn_named,
+    systems::fire_system::FireSystem,
+    vectors::Vector3i,
+};
+
+const HOT_X: i32 = 2;
+
+//Breathable corridor with one tile heated well past the ignition point
+fn hot_corridor(ecs: &mut World, hydrogen: f32, oxygenated: bool) {
+    let map = common::corridor_map(5, |x| {
+        let mut atmosphere = if oxygenated {
+            Atmosphere::new_stp()
+        } else {
+            let mut atmosphere = Atmosphere::new_vacuume();
+            atmosphere.set_gasses(&HashMap::from([(Gas::Nitrogen, 42.0)]), 288.15);
+            atmosphere
+        };
+
+        if x == HOT_X {
+            if hydrogen > 0.0 {
+                atmosphere.update_gas(&HashMap::from([(Gas::Hydrogen, hydrogen)]), 288.15);
+            }
+            let temperature = atmosphere.temperature;
+            atmosphere.update_temperature(700.0 - temperature);
+        }
+
+        atmosphere
+    });
+    common::set_map(ecs, map);
+}
+
+#[test]
+fn hot_hydrogen_burns_into_water_and_lights_the_tile() {
+    let mut ecs = common::test_world();
+    hot_corridor(&mut ecs, 2.0, true);
+    let oxygen_before = common::gas_at(&ecs, Vector3i::new(HOT_X, 0, 0), Gas::Oxygen);
+
+    common::run_system(&mut ecs, &mut FireSystem {}, 1);
+
+    assert!(common::gas_at(&ecs, Vector3i::new(HOT_X, 0, 0), Gas::Oxygen) < oxygen_before);
+    assert!(common::gas_at(&ecs, Vector3i::new(HOT_X, 0, 0), Gas::WaterVapour) > 0.0);
+
+    let fires = ecs.read_storage::<Fire>();
+    let illuminants = ecs.read_storage::<Illuminant>();
+    let positions = ecs.read_storage::<Vector3i>();
+    let lit = (&fires, &illuminants, &positions)
+        .join()
+        .any(|(_, illuminant, position)| illuminant.on && position.x == HOT_X);
+    assert!(lit);
+}
+
+#[test]
+fn fire_spreads_heat_to_neighbours() {
+    let mut ecs = common::test_world();
+    hot_corridor(&mut ecs, 2.0, true);
+
+    common::run_system(&mut ecs, &mut FireSystem {}, 1);
+
+    let map = ecs.fetch::<Map>();
+    let neighbour = map.tiles.get(&Vector3i::new(HOT_X + 1, 0, 0)).unwrap();
+    assert!(neighbour.atmosphere.temperature > 288.15);
+}
+
+#[test]
+fn fire_needs_oxygen() {
+    let mut ecs = common::test_world();
+    hot_corridor(&mut ecs, 2.0, false);
+
+    common::run_system(&mut ecs, &mut FireSystem {}, 1);
+
+    assert_eq!(common::gas_at(&ecs, Vector3i::new(HOT_X, 0, 0), Gas::WaterVapour), 0.0);
+    assert_eq!(ecs.read_storage::<Fire>().count(), 0);
+}
+
+#[test]
+fn flammable_props_burn_away_and_hurt_bystanders() {
+    let mut ecs = common::test_world();
+    hot_corridor(&mut ecs, 0.0, true);
+    let position = Vector3i::new(HOT_X, 0, 0);
+    let manifest = spawn_named(&mut ecs, "paper_manifest", position).unwrap();
+    let player = spawn_named(&mut ecs, "player", position).unwrap();
+
+    let fuel = ecs.read_storage::<Flammable>().get(manifest).unwrap().fuel;
+    common::run_system(&mut ecs, &mut FireSystem {}, 20);
+
+    assert!(!ecs.is_alive(manifest), "{} fuel never burnt", fuel);
+
+    let health = ecs.read_storage::<Health>();
+    let health = health.get(player).unwrap();
+    assert!(health.current < health.max);
+
+    let log = ecs.fetch::<GameLog>();
+    assert!(log.entries.iter().any(|entry| entry == "Cargo manifest burns away"));
+    assert_eq!(
+        log.entries
+            .iter()
+            .filter(|entry| *entry == "Player is burned")
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn burning_to_no_health_kills() {
+    let mut ecs = common::test_world();
+    hot_corridor(&mut ecs, 2.0, true);
+    let player = spawn_named(&mut ecs, "player", Vector3i::new(HOT_X, 0, 0)).unwrap();
+    ecs.write_storage::<Health>().get_mut(player).unwrap().current = 1.0;
+
+    common::run_system(&mut ecs, &mut FireSystem {}, 1);
+
+    assert_eq!(ecs.read_storage::<Health>().get(player).unwrap().current, 0.0);
+    assert!(ecs.read_storage::<Vitals>().get(player).unwrap().state == VitalState::Dead);
+    assert!(ecs
+        .fetch::<GameLog>()
+        .entries
+        .iter()
+        .any(|entry| entry == "Player dies"));
+}
@@ -0,0 +1,226 @@
+mod common;
+
+use rltk::RGB;
+use specs::prelude::*;
+use weirdark::{
+    entities::{
+        atmospherics::{Atmosphere, Gas},
+        components::{Container, Illuminant, InContainer},
+        intents::{Interactable, RefuelIntent},
+        power_components::{Generator, PowerSource, PowerSwitch},
+    },
+    map::Map,
+    raws::spawn_named,
+    spawner,
+    systems::{
+        clock_system::ClockSystem,
+        event_system::EventSystem,
+        generator_system::{GeneratorSystem, SPIN_UP_TURNS},
+        power_system::PowerSystem,
+    },
+    vectors::Vector3i,
+};
+
+const LAMP_X: i32 = 2;
+
+fn output(ecs: &World, generator: Entity) -> f32 {
+    ecs.read_storage::<Generator>()
+        .get(generator)
+        .unwrap()
+        .output
+}
+
+fn hopper_count(ecs: &World, generator: Entity) -> usize {
+    let container_id = ecs.read_storage::<Container>().get(generator).unwrap().id;
+
+    ecs.read_storage::<InContainer>()
+        .join()
+        .filter(|in_container| in_container.owner == container_id)
+        .count()
+}
+
+//A fuel generator wired to a lamp along an air filled corridor
+fn wired_generator(ecs: &mut World, canisters: usize) -> (Entity, Entity) {
+    common::set_map(
+        ecs,
+        common::corridor_map(LAMP_X + 1, |_| Atmosphere::new_stp()),
+    );
+
+    let generator = spawner::fuel_generator(ecs, Vector3i::new(0, 0, 0), canisters);
+
+    common::lay_wire(ecs, 0, LAMP_X, false);
+
+    let lamp = spawner::ceiling_lamp(
+        ecs,
+        Vector3i::new(LAMP_X, 0, 0),
+        1.0,
+        RGB::named(rltk::WHITE).to_rgba(1.0),
+        true,
+    );
+
+    (generator, lamp)
+}
+
+fn run(ecs: &mut World, ticks: usize) {
+    for _ in 0..ticks {
+        common::run_system(ecs, &mut GeneratorSystem {}, 1);
+        common::run_system(ecs, &mut PowerSystem {}, 1);
+    }
+}
+
+#[test]
+fn generator_burns_fuel_to_light_a_lamp() {
+    let mut ecs = common::test_world();
+    let (generator, lamp) = wired_generator(&mut ecs, 2);
+    let origin = Vector3i::new(0, 0, 0);
+    let temperature = ecs
+        .fetch::<Map>()
+        .tiles
+        .get(&origin)
+        .unwrap()
+        .atmosphere
+        .temperature;
+    let oxygen = common::gas_at(&ecs, origin, Gas::Oxygen);
+
+    run(&mut ecs, 2);
+
+    //One canister goes in at a time
+    assert_eq!(hopper_count(&ecs, generator), 1);
+    assert!(output(&ecs, generator) > 0.0);
+    assert_eq!(
+        ecs.read_storage::<PowerSource>()
+            .get(generator)
+            .unwrap()
+            .max_wattage,
+        output(&ecs, generator)
+    );
+    assert!(ecs.read_storage::<Illuminant>().get(lamp).unwrap().on);
+
+    let map = ecs.fetch::<Map>();
+    let tile = map.tiles.get(&origin).unwrap();
+    assert!(tile.atmosphere.temperature > temperature);
+    assert!(common::gas_at(&ecs, origin, Gas::Oxygen) < oxygen);
+    assert!(common::gas_at(&ecs, origin, Gas::CarbonDioxide) > 0.0);
+}
+
+#[test]
+fn empty_generator_leaves_the_lamp_dark() {
+    let mut ecs = common::test_world();
+    let (generator, lamp) = wired_generator(&mut ecs, 0);
+
+    run(&mut ecs, 2);
+
+    assert_eq!(output(&ecs, generator), 0.0);
+    assert!(!ecs.read_storage::<Illuminant>().get(lamp).unwrap().on);
+}
+
+#[test]
+fn throttling_down_cuts_the_output() {
+    let mut ecs = common::test_world();
+    let (generator, _) = wired_generator(&mut ecs, 1);
+
+    run(&mut ecs, 1);
+    let full = output(&ecs, generator);
+
+    //Full throttle cycles round to idle
+    ecs.write_storage::<Generator>()
+        .get_mut(generator)
+        .unwrap()
+        .interact();
+    run(&mut ecs, 1);
+
+    let idle = output(&ecs, generator);
+    assert!(idle > 0.0 && idle < full);
+}
+
+#[test]
+fn switched_on_generator_spins_up_first() {
+    let mut ecs = common::test_world();
+    let (generator, lamp) = wired_generator(&mut ecs, 2);
+    ecs.write_storage::<PowerSwitch>()
+        .get_mut(generator)
+        .unwrap()
+        .on = false;
+    run(&mut ecs, 1);
+    assert!(!ecs.read_storage::<Generator>().get(generator).unwrap().online);
+
+    ecs.write_storage::<PowerSwitch>()
+        .get_mut(generator)
+        .unwrap()
+        .on = true;
+    for _ in 0..SPIN_UP_TURNS {
+        common::run_system(&mut ecs, &mut ClockSystem {}, 1);
+        run(&mut ecs, 1);
+        assert_eq!(output(&ecs, generator), 0.0);
+    }
+
+    common::run_system(&mut ecs, &mut ClockSystem {}, 1);
+    run(&mut ecs, 1);
+    assert!(ecs.read_storage::<Generator>().get(generator).unwrap().online);
+    assert!(output(&ecs, generator) > 0.0);
+    assert!(ecs.read_storage::<Illuminant>().get(lamp).unwrap().on);
+}
+
+#[test]
+fn hydrogen_generator_makes_water() {
+    let mut ecs = common::test_world();
+    let origin = Vector3i::new(0, 0, 0);
+    common::set_map(
+        &mut ecs,
+        common::corridor_map(1, |_| {
+            let mut atmosphere = Atmosphere::new_stp();
+            let temperature = atmosphere.temperature;
+            atmosphere.set_gas(&Gas::Hydrogen, 10.0, temperature);
+            atmosphere
+        }),
+    );
+    let generator = spawn_named(&mut ecs, "hydrogen_generator", origin).unwrap();
+
+    common::run_system(&mut ecs, &mut GeneratorSystem {}, 1);
+
+    assert!(output(&ecs, generator) > 0.0);
+    assert!(common::gas_at(&ecs, origin, Gas::Hydrogen) < 10.0);
+    assert!(common::gas_at(&ecs, origin, Gas::WaterVapour) > 0.0);
+}
+
+#[test]
+fn refuelling_loads_a_carried_canister() {
+    let mut ecs = common::test_world();
+    let (generator, _) = wired_generator(&mut ecs, 0);
+    let position = Vector3i::new(1, 0, 0);
+    let player = spawn_named(&mut ecs, "player", position).unwrap();
+    let canister = spawner::fuel_canister(&mut ecs, position);
+    assert!(spawner::put_item_in_container(&mut ecs, canister, player));
+    common::set_player(&mut ecs, player, position);
+
+    let _ = ecs
+        .write_storage::<RefuelIntent>()
+        .insert(player, RefuelIntent::new(player, generator));
+    common::run_system(&mut ecs, &mut EventSystem {}, 2);
+
+    assert_eq!(hopper_count(&ecs, generator), 1);
+    assert!(ecs.read_storage::<RefuelIntent>().get(player).is_none());
+
+    run(&mut ecs, 1);
+    assert!(output(&ecs, generator) > 0.0);
+}
+
+#[test]
+fn refuelling_without_a_hopper_fails_quietly() {
+    let mut ecs = common::test_world();
+    let (generator, _) = wired_generator(&mut ecs, 0);
+    ecs.write_storage::<Container>().remove(generator);
+    let position = Vector3i::new(1, 0, 0);
+    let player = spawn_named(&mut ecs, "player", position).unwrap();
+    let canister = spawner::fuel_canister(&mut ecs, position);
+    assert!(spawner::put_item_in_container(&mut ecs, canister, player));
+    common::set_player(&mut ecs, player, position);
+
+    let _ = ecs
+        .write_storage::<RefuelIntent>()
+        .insert(player, RefuelIntent::new(player, generator));
+    common::run_system(&mut ecs, &mut EventSystem {}, 2);
+
+    assert!(ecs.read_storage::<RefuelIntent>().get(player).is_none());
+    assert!(!common::log_mentions(&ecs, "hopper"));
+}
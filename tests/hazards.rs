@@ -0,0 +1,141 @@
+mod common;
+
+use rltk::RGB;
+use specs::prelude::*;
+use weirdark::{
+    clock::{EventScheduler, ScheduledEvent},
+    entities::{
+        atmospherics::Atmosphere,
+        components::Illuminant,
+        intents::{RepairIntent, REPAIR_COST},
+        power_components::{Broken, PowerNode, PowerSwitch},
+    },
+    gamelog::GameLog,
+    hazards::{Hazard, HazardDirector},
+    map::{zones::AtmosphereZones, Map},
+    raws::spawn_named,
+    spawner,
+    systems::{
+        clock_system::ClockSystem,
+        event_system::EventSystem,
+        hazard_system::{HazardSystem, BREACH_WARNING_TURNS},
+        power_system::PowerSystem,
+        structural_system::StructuralSystem,
+    },
+    vectors::Vector3i,
+};
+
+//Makes the director fire the given hazard on the next run
+fn force_hazard(ecs: &mut World, hazard: Hazard) {
+    ecs.fetch_mut::<HazardDirector>().table = vec![(hazard, 1)];
+    ecs.fetch_mut::<EventScheduler>()
+        .schedule_at_turn(0, ScheduledEvent::Hazard);
+}
+
+//Hull sections at either end of the corridor that have given way
+fn breached(ecs: &World) -> usize {
+    let map = ecs.fetch::<Map>();
+
+    [Vector3i::new(-1, 0, 0), Vector3i::new(4, 0, 0)]
+        .iter()
+        .filter(|position| map.tiles.get(position).unwrap().passable)
+        .count()
+}
+
+#[test]
+fn hull_breach_opens_outer_hull_to_space() {
+    let mut ecs = common::test_world();
+    common::set_map(&mut ecs, common::corridor_map(4, |_| Atmosphere::new_stp()));
+    ecs.fetch_mut::<AtmosphereZones>().dirty = false;
+    force_hazard(&mut ecs, Hazard::HullBreach);
+
+    //The hull only gives a warning at first
+    common::run_system(&mut ecs, &mut HazardSystem {}, 1);
+    common::run_system(&mut ecs, &mut StructuralSystem {}, 1);
+    assert_eq!(breached(&ecs), 0);
+
+    for _ in 0..BREACH_WARNING_TURNS {
+        common::run_system(&mut ecs, &mut ClockSystem {}, 1);
+        common::run_system(&mut ecs, &mut HazardSystem {}, 1);
+    }
+    common::run_system(&mut ecs, &mut StructuralSystem {}, 1);
+
+    assert_eq!(breached(&ecs), 1);
+    assert!(ecs.fetch::<AtmosphereZones>().dirty);
+    //The hazard message and the hull giving way
+    assert_eq!(ecs.fetch::<GameLog>().entries.len(), 2);
+}
+
+#[test]
+fn breaker_trip_switches_panel_off() {
+    let mut ecs = common::test_world();
+    common::set_map(&mut ecs, common::corridor_map(4, |_| Atmosphere::new_stp()));
+    let breaker = spawn_named(&mut ecs, "breaker_box", Vector3i::new(1, 0, 0)).unwrap();
+    ecs.write_storage::<PowerNode>()
+        .get_mut(breaker)
+        .unwrap()
+        .dirty = false;
+    force_hazard(&mut ecs, Hazard::BreakerTrip);
+
+    common::run_system(&mut ecs, &mut HazardSystem {}, 1);
+
+    assert!(!ecs.read_storage::<PowerSwitch>().get(breaker).unwrap().on);
+    assert!(ecs.read_storage::<PowerNode>().get(breaker).unwrap().dirty);
+}
+
+#[test]
+fn failed_lamp_stays_dark_until_repaired() {
+    let mut ecs = common::test_world();
+    common::set_map(&mut ecs, Map::new());
+    spawner::power_source(&mut ecs, Vector3i::new(0, 0, 0), true, 1000.0);
+    common::lay_wire(&mut ecs, 0, 2, false);
+    let lamp = spawner::ceiling_lamp(
+        &mut ecs,
+        Vector3i::new(2, 0, 0),
+        1.0,
+        RGB::named(rltk::WHITE).to_rgba(1.0),
+        true,
+    );
+    common::run_system(&mut ecs, &mut PowerSystem {}, 1);
+    assert!(ecs.read_storage::<Illuminant>().get(lamp).unwrap().on);
+
+    force_hazard(&mut ecs, Hazard::LampFailure);
+    common::run_system(&mut ecs, &mut HazardSystem {}, 1);
+    common::run_system(&mut ecs, &mut PowerSystem {}, 1);
+
+    assert!(ecs.read_storage::<Broken>().get(lamp).is_some());
+    assert!(!ecs.read_storage::<Illuminant>().get(lamp).unwrap().on);
+
+    let position = Vector3i::new(1, 0, 0);
+    let player = spawn_named(&mut ecs, "player", position).unwrap();
+    common::set_player(&mut ecs, player, position);
+    let _ = ecs
+        .write_storage::<RepairIntent>()
+        .insert(player, RepairIntent::new(player, lamp));
+    common::run_system(&mut ecs, &mut EventSystem {}, REPAIR_COST as usize);
+    common::run_system(&mut ecs, &mut PowerSystem {}, 1);
+
+    assert!(ecs.read_storage::<Broken>().get(lamp).is_none());
+    assert!(ecs.read_storage::<Illuminant>().get(lamp).unwrap().on);
+}
+
+#[test]
+fn gas_leak_adds_gas_and_director_waits_between_hazards() {
+    let mut ecs = common::test_world();
+    common::set_map(&mut ecs, common::corridor_map(4, |_| Atmosphere::new_stp()));
+    let mols_before = common::total_mols(&ecs.fetch::<Map>());
+    force_hazard(&mut ecs, Hazard::GasLeak);
+
+    common::run_system(&mut ecs, &mut HazardSystem {}, 5);
+
+    //A single leak of the base size, the clock has not moved so there is no scaling
+    let leaked = common::total_mols(&ecs.fetch::<Map>()) - mols_before;
+    assert!((leaked - 20.0).abs() < 0.1);
+    assert_eq!(ecs.fetch::<GameLog>().entries.len(), 1);
+    let next_turn = ecs
+        .fetch::<EventScheduler>()
+        .turn_of(&ScheduledEvent::Hazard)
+        .unwrap();
+    assert!(next_turn >= HazardDirector::interval(0) / 2);
+    assert!(HazardDirector::interval(10000) < HazardDirector::interval(0));
+}
@@ -0,0 +1,171 @@
+mod common;
+
+use rltk::RGB;
+use specs::prelude::*;
+use weirdark::{
+    clock::EventScheduler,
+    entities::{
+        atmospherics::Atmosphere,
+        components::Door,
+        intents::Interactable,
+        logic::{DelayTimer, GateKind, LogicGate, Signal},
+        network::NetworkAddress,
+        power_components::{PowerNode, PowerSwitch},
+    },
+    graphics::char_to_glyph,
+    map::Map,
+    raws::spawn_named,
+    spawner,
+    systems::{
+        clock_system::ClockSystem, logic_system::LogicSystem, network_system::NetworkSystem,
+        power_system::PowerSystem,
+    },
+    vectors::Vector3i,
+};
+
+const LENGTH: i32 = 8;
+
+//A powered corridor with data wiring along its whole length
+fn wired_corridor(ecs: &mut World) {
+    common::set_map(ecs, common::corridor_map(LENGTH, |_| Atmosphere::new_stp()));
+
+    spawner::power_source(ecs, Vector3i::new(0, 0, 0), true, 1000.0);
+
+    common::lay_wire(ecs, 0, LENGTH - 1, true);
+}
+
+//Lets the network hand out addresses and the logic system find them
+fn settle(ecs: &mut World) {
+    common::run_system(ecs, &mut PowerSystem {}, 1);
+    common::run_system(ecs, &mut NetworkSystem {}, 1);
+    common::run_system(ecs, &mut LogicSystem {}, 1);
+}
+
+//Timers wait on the event scheduler, so the clock has to move along with the logic
+fn tick(ecs: &mut World, ticks: usize) {
+    for _ in 0..ticks {
+        common::run_system(ecs, &mut ClockSystem {}, 1);
+        common::run_system(ecs, &mut LogicSystem {}, 1);
+    }
+}
+
+//Steps the output through what it can reach until it drives the target
+fn link(ecs: &mut World, source: Entity, target: Entity) {
+    let address = ecs
+        .read_storage::<NetworkAddress>()
+        .get(target)
+        .unwrap()
+        .address;
+    let mut signals = ecs.write_storage::<Signal>();
+    let signal = signals.get_mut(source).unwrap();
+
+    for _ in 0..=signal.reachable.len() {
+        if signal.linked_target() == Some(address) {
+            return;
+        }
+        signal.interact();
+    }
+
+    panic!("Target not reachable from the signal");
+}
+
+#[test]
+fn pressure_sensor_shuts_door_on_decompression() {
+    let mut ecs = common::test_world();
+    wired_corridor(&mut ecs);
+
+    let sensor_position = Vector3i::new(2, 0, 0);
+    let sensor = spawn_named(&mut ecs, "pressure_sensor", sensor_position).unwrap();
+    let door = spawner::door(
+        &mut ecs,
+        Vector3i::new(6, 0, 0),
+        true,
+        RGB::named(rltk::GRAY).to_rgba(1.0),
+        char_to_glyph('/'),
+        char_to_glyph('+'),
+    );
+    settle(&mut ecs);
+    link(&mut ecs, sensor, door);
+
+    //Still pressurised, the door is left as it is
+    common::run_system(&mut ecs, &mut LogicSystem {}, 2);
+    assert!(ecs.read_storage::<Door>().get(door).unwrap().open);
+
+    ecs.fetch_mut::<Map>()
+        .tiles
+        .get_mut(&sensor_position)
+        .unwrap()
+        .atmosphere = Atmosphere::new_vacuume();
+    common::run_system(&mut ecs, &mut LogicSystem {}, 1);
+
+    assert!(!ecs.read_storage::<Door>().get(door).unwrap().open);
+    assert!(!ecs.read_storage::<Signal>().get(sensor).unwrap().on);
+}
+
+#[test]
+fn motion_sensor_switches_lamp_on() {
+    let mut ecs = common::test_world();
+    wired_corridor(&mut ecs);
+
+    let sensor = spawn_named(&mut ecs, "motion_sensor", Vector3i::new(1, 0, 0)).unwrap();
+    let lamp = spawner::ceiling_lamp(
+        &mut ecs,
+        Vector3i::new(5, 0, 0),
+        1.0,
+        RGB::named(rltk::WHITE).to_rgba(1.0),
+        false,
+    );
+    let position = Vector3i::new(7, 0, 0);
+    let player = spawn_named(&mut ecs, "player", position).unwrap();
+    common::set_player(&mut ecs, player, position);
+    settle(&mut ecs);
+    link(&mut ecs, sensor, lamp);
+
+    common::run_system(&mut ecs, &mut LogicSystem {}, 1);
+    assert!(!ecs.read_storage::<PowerSwitch>().get(lamp).unwrap().on);
+
+    let _ = ecs
+        .write_storage::<Vector3i>()
+        .insert(player, Vector3i::new(2, 0, 0));
+    common::run_system(&mut ecs, &mut LogicSystem {}, 1);
+
+    assert!(ecs.read_storage::<PowerSwitch>().get(lamp).unwrap().on);
+    assert!(ecs.read_storage::<PowerNode>().get(lamp).unwrap().dirty);
+}
+
+#[test]
+fn gates_follow_their_truth_tables() {
+    let and = LogicGate::new(GateKind::And);
+    let or = LogicGate::new(GateKind::Or);
+    let not = LogicGate::new(GateKind::Not);
+
+    assert!(and.evaluate(&[true, true]));
+    assert!(!and.evaluate(&[true, false]));
+    assert!(!and.evaluate(&[]));
+    assert!(or.evaluate(&[false, true]));
+    assert!(!or.evaluate(&[false, false]));
+    assert!(not.evaluate(&[]));
+    assert!(not.evaluate(&[false]));
+    assert!(!not.evaluate(&[true]));
+}
+
+#[test]
+fn delay_timer_waits_out_its_delay() {
+    let mut ecs = common::test_world();
+    wired_corridor(&mut ecs);
+
+    let sensor = spawn_named(&mut ecs, "pressure_sensor", Vector3i::new(1, 0, 0)).unwrap();
+    let timer = spawn_named(&mut ecs, "delay_timer", Vector3i::new(3, 0, 0)).unwrap();
+    settle(&mut ecs);
+    link(&mut ecs, sensor, timer);
+    let delay = ecs.read_storage::<DelayTimer>().get(timer).unwrap().delay;
+
+    //The sensor is already on, so the timer holds off until the last tick of its delay
+    tick(&mut ecs, delay as usize - 1);
+    assert!(!ecs.read_storage::<Signal>().get(timer).unwrap().on);
+    assert!(ecs.read_storage::<DelayTimer>().get(timer).unwrap().waiting);
+
+    tick(&mut ecs, 1);
+    assert!(ecs.read_storage::<Signal>().get(timer).unwrap().on);
+    assert!(ecs.fetch::<EventScheduler>().is_empty());
+}
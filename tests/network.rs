@@ -0,0 +1,179 @@
+mod common;
+
+use rltk::RGB;
+use specs::prelude::*;
+use weirdark::{
+    entities::{
+        components::Door,
+        intents::InteractIntent,
+        network::{AlarmQueue, NetworkAddress, Terminal},
+        power_components::{PowerNode, PowerSwitch, WireGauge},
+    },
+    graphics::char_to_glyph,
+    map::Map,
+    raws::spawn_named,
+    spawner,
+    systems::{
+        event_system::{EventSystem, InteractionInformation},
+        network_system::{get_devices_on_data_network, NetworkSystem},
+        power_system::{get_devices_on_subnetwork, PowerSystem},
+    },
+    vectors::Vector3i,
+};
+
+const BREAKER: Vector3i = Vector3i { x: 3, y: 0, z: 0 };
+const LAMP: Vector3i = Vector3i { x: 6, y: 0, z: 0 };
+const DOOR: Vector3i = Vector3i { x: 6, y: 1, z: 0 };
+const TERMINAL: Vector3i = Vector3i { x: 1, y: 0, z: 0 };
+
+struct Ship {
+    terminal: Entity,
+    breaker: Entity,
+    lamp: Entity,
+    door: Entity,
+}
+
+//A terminal on one circuit, a lamp on another behind a breaker and a door on signal cable only
+fn networked_ship(ecs: &mut World) -> Ship {
+    common::set_map(ecs, Map::new());
+
+    spawner::power_source(ecs, Vector3i::new(0, 0, 0), true, 1000.0);
+    let run: Vec<Vector3i> = (0..=3).map(|x| Vector3i::new(x, 0, 0)).collect();
+    common::lay_wire_at(ecs, &run, "RED", WireGauge::Standard, true);
+    let run: Vec<Vector3i> = (3..=6).map(|x| Vector3i::new(x, 0, 0)).collect();
+    common::lay_wire_at(ecs, &run, "BLUE", WireGauge::Standard, true);
+    let mut run: Vec<Vector3i> = (3..=6).map(|x| Vector3i::new(x, 1, 0)).collect();
+    run.push(BREAKER);
+    common::lay_wire_at(ecs, &run, "CYAN", WireGauge::Signal, true);
+
+    let breaker = spawner::breaker_box(ecs, BREAKER);
+    let lamp = spawner::ceiling_lamp(ecs, LAMP, 1.0, RGB::named(rltk::WHITE).to_rgba(1.0), true);
+    let door = spawner::door(
+        ecs,
+        DOOR,
+        false,
+        RGB::named(rltk::GRAY).to_rgba(1.0),
+        char_to_glyph('/'),
+        char_to_glyph('+'),
+    );
+    let terminal = spawner::terminal(ecs, TERMINAL);
+
+    common::run_system(ecs, &mut PowerSystem {}, 1);
+    common::run_system(ecs, &mut NetworkSystem {}, 1);
+
+    Ship {
+        terminal,
+        breaker,
+        lamp,
+        door,
+    }
+}
+
+fn listed(interactions: &[InteractionInformation]) -> Vec<u32> {
+    interactions
+        .iter()
+        .map(|interaction| interaction.entity_id)
+        .collect()
+}
+
+#[test]
+fn terminal_reaches_devices_through_breakers() {
+    let mut ecs = common::test_world();
+    let ship = networked_ship(&mut ecs);
+
+    let devices = listed(&get_devices_on_data_network(&ecs, ship.terminal));
+
+    assert!(devices.contains(&ship.lamp.id()));
+    assert!(devices.contains(&ship.breaker.id()));
+    assert!(devices.contains(&ship.door.id()));
+    assert!(!devices.contains(&ship.terminal.id()));
+
+    //Everything listed has an address of its own
+    let addresses = ecs.read_storage::<NetworkAddress>();
+    assert!(addresses.get(ship.door).is_some());
+    assert_ne!(
+        addresses.get(ship.door).unwrap().address,
+        addresses.get(ship.lamp).unwrap().address
+    );
+}
+
+#[test]
+fn breaker_box_leaves_out_signal_cable() {
+    let mut ecs = common::test_world();
+    let ship = networked_ship(&mut ecs);
+
+    let devices = listed(&get_devices_on_subnetwork(&ecs, ship.breaker));
+
+    assert!(devices.contains(&ship.lamp.id()));
+    assert!(!devices.contains(&ship.door.id()));
+}
+
+#[test]
+fn alarms_only_reach_powered_terminals() {
+    let mut ecs = common::test_world();
+    let ship = networked_ship(&mut ecs);
+
+    ecs.fetch_mut::<AlarmQueue>()
+        .raise(BREAKER, "A breaker trips".to_string());
+    common::run_system(&mut ecs, &mut NetworkSystem {}, 1);
+
+    {
+        let terminals = ecs.read_storage::<Terminal>();
+        let alarms = &terminals.get(ship.terminal).unwrap().alarms;
+        assert_eq!(alarms.len(), 1);
+        assert!(alarms[0].contains("A breaker trips"));
+        assert!(alarms[0].starts_with('#'));
+    }
+
+    //Switched off it hears nothing and lists nothing
+    ecs.write_storage::<PowerSwitch>()
+        .get_mut(ship.terminal)
+        .unwrap()
+        .toggle();
+    ecs.write_storage::<PowerNode>()
+        .get_mut(ship.terminal)
+        .unwrap()
+        .dirty = true;
+    common::run_system(&mut ecs, &mut PowerSystem {}, 1);
+
+    ecs.fetch_mut::<AlarmQueue>()
+        .raise(BREAKER, "A breaker trips".to_string());
+    common::run_system(&mut ecs, &mut NetworkSystem {}, 1);
+
+    assert_eq!(
+        ecs.read_storage::<Terminal>()
+            .get(ship.terminal)
+            .unwrap()
+            .alarms
+            .len(),
+        1
+    );
+    assert!(get_devices_on_data_network(&ecs, ship.terminal).is_empty());
+}
+
+#[test]
+fn door_opens_from_the_terminal() {
+    let mut ecs = common::test_world();
+    let ship = networked_ship(&mut ecs);
+    let position = TERMINAL + Vector3i::new(0, 1, 0);
+    let player = spawn_named(&mut ecs, "player", position).unwrap();
+    common::set_player(&mut ecs, player, position);
+
+    let interaction = get_devices_on_data_network(&ecs, ship.terminal)
+        .into_iter()
+        .find(|interaction| interaction.entity_id == ship.door.id())
+        .unwrap();
+    let _ = ecs.write_storage::<InteractIntent>().insert(
+        player,
+        InteractIntent::new(
+            player,
+            ship.door,
+            interaction.id,
+            interaction.description,
+            interaction.cost,
+        ),
+    );
+    common::run_system(&mut ecs, &mut EventSystem {}, 2);
+
+    assert!(ecs.read_storage::<Door>().get(ship.door).unwrap().open);
+}
@@ -0,0 +1,531 @@
+mod common;
+
+use rltk::RGB;
+use specs::prelude::*;
+use weirdark::{
+    entities::{
+        components::Illuminant,
+        intents::Interactable,
+        atmospherics::Atmosphere,
+        power_components::{
+            Battery, ControlPanel, ElectronicHeater, PowerNode, PowerSource, PowerSwitch,
+            PoweredState, Wire, WireGauge, BREAKER_TRIP_TICKS, HEATER_HYSTERESIS,
+        },
+        power_graph::PowerGraph,
+    },
+    map::Map,
+    spawner,
+    systems::{power_system::PowerSystem, thermal_system::ThermalSystem},
+    vectors::Vector3i,
+};
+
+const LAMP_X: i32 = 3;
+const HEATER_TARGET: f32 = 293.15;
+
+fn lay_gauged_wire(ecs: &mut World, from: i32, to: i32, color_name: &str, gauge: WireGauge) {
+    let positions: Vec<Vector3i> = (from..=to).map(|x| Vector3i::new(x, 0, 0)).collect();
+    common::lay_wire_at(ecs, &positions, color_name, gauge, false);
+}
+
+fn powered_lamp(ecs: &mut World) -> Entity {
+    common::set_map(ecs, Map::new());
+
+    spawner::power_source(ecs, Vector3i::new(0, 0, 0), true, 1000.0);
+    common::lay_wire(ecs, 0, LAMP_X, false);
+
+    let lamp = spawner::ceiling_lamp(
+        ecs,
+        Vector3i::new(LAMP_X, 0, 0),
+        1.0,
+        RGB::named(rltk::WHITE).to_rgba(1.0),
+        true,
+    );
+
+    common::run_system(ecs, &mut PowerSystem {}, 1);
+
+    lamp
+}
+
+#[test]
+fn wired_lamp_is_powered() {
+    let mut ecs = common::test_world();
+    let lamp = powered_lamp(&mut ecs);
+
+    let power_states = ecs.read_storage::<PoweredState>();
+    let illuminants = ecs.read_storage::<Illuminant>();
+
+    assert!(power_states.get(lamp).unwrap().available_wattage > 0.0);
+    assert!(illuminants.get(lamp).unwrap().on);
+}
+
+#[test]
+fn lamp_loses_power_when_switched_off() {
+    let mut ecs = common::test_world();
+    let lamp = powered_lamp(&mut ecs);
+
+    //Same as an interaction through the event system: toggle and mark the node dirty
+    ecs.write_storage::<PowerSwitch>()
+        .get_mut(lamp)
+        .unwrap()
+        .interact();
+    ecs.write_storage::<PowerNode>().get_mut(lamp).unwrap().dirty = true;
+
+    common::run_system(&mut ecs, &mut PowerSystem {}, 1);
+
+    let power_states = ecs.read_storage::<PoweredState>();
+    let illuminants = ecs.read_storage::<Illuminant>();
+
+    assert!(!power_states.get(lamp).unwrap().on);
+    assert!(!illuminants.get(lamp).unwrap().on);
+}
+
+//Single sealed tile with a heater wired to a source next to it
+fn heated_tile(ecs: &mut World, temperature: f32) -> Entity {
+    let map = common::corridor_map(LAMP_X + 1, |_| {
+        let mut atmosphere = Atmosphere::new_stp();
+        atmosphere.update_temperature(temperature - atmosphere.temperature);
+        atmosphere
+    });
+    common::set_map(ecs, map);
+
+    spawner::power_source(ecs, Vector3i::new(0, 0, 0), true, 1000.0);
+    common::lay_wire(ecs, 0, LAMP_X, false);
+
+    spawner::heater(ecs, Vector3i::new(LAMP_X, 0, 0), HEATER_TARGET, true)
+}
+
+fn heater_tile_temperature(ecs: &World) -> f32 {
+    ecs.fetch::<Map>()
+        .tiles
+        .get(&Vector3i::new(LAMP_X, 0, 0))
+        .unwrap()
+        .atmosphere
+        .temperature
+}
+
+#[test]
+fn cold_room_turns_heater_on_and_warms_tile() {
+    let mut ecs = common::test_world();
+    let heater = heated_tile(&mut ecs, 280.0);
+
+    common::run_system(&mut ecs, &mut PowerSystem {}, 3);
+
+    assert!(ecs.read_storage::<PoweredState>().get(heater).unwrap().on);
+    assert!(ecs.read_storage::<ElectronicHeater>().get(heater).unwrap().on);
+    assert!(heater_tile_temperature(&ecs) > 280.0);
+}
+
+#[test]
+fn warm_room_turns_heater_off() {
+    let mut ecs = common::test_world();
+    let heater = heated_tile(&mut ecs, HEATER_TARGET + 5.0);
+
+    common::run_system(&mut ecs, &mut PowerSystem {}, 3);
+
+    assert!(!ecs.read_storage::<PoweredState>().get(heater).unwrap().on);
+    assert!(!ecs.read_storage::<ElectronicHeater>().get(heater).unwrap().on);
+    assert_eq!(heater_tile_temperature(&ecs), HEATER_TARGET + 5.0);
+}
+
+#[test]
+fn heater_stops_warming_the_tick_its_power_goes() {
+    let mut ecs = common::test_world();
+    heated_tile(&mut ecs, 280.0);
+    common::run_system(&mut ecs, &mut PowerSystem {}, 1);
+    assert!(heater_tile_temperature(&ecs) > 280.0);
+
+    let source = (&ecs.entities(), &ecs.read_storage::<PowerSource>())
+        .join()
+        .map(|(entity, _)| entity)
+        .next()
+        .unwrap();
+    ecs.write_storage::<PowerSwitch>()
+        .get_mut(source)
+        .unwrap()
+        .interact();
+    ecs.write_storage::<PowerNode>()
+        .get_mut(source)
+        .unwrap()
+        .dirty = true;
+    let temperature = heater_tile_temperature(&ecs);
+    common::run_system(&mut ecs, &mut PowerSystem {}, 1);
+
+    assert_eq!(heater_tile_temperature(&ecs), temperature);
+}
+
+#[test]
+fn placed_heater_warms_the_room() {
+    let mut ecs = common::test_world();
+    heated_tile(&mut ecs, 280.0);
+
+    for _ in 0..500 {
+        common::run_system(&mut ecs, &mut PowerSystem {}, 1);
+        common::run_system(&mut ecs, &mut ThermalSystem {}, 1);
+    }
+
+    //The heater holds its own tile at the target and the air around it comes up from 280 K too
+    let map = ecs.fetch::<Map>();
+    for x in 0..=LAMP_X {
+        let temperature = map.tiles.get(&Vector3i::new(x, 0, 0)).unwrap().temperature();
+        assert!(temperature > 290.0);
+    }
+    assert!((heater_tile_temperature(&ecs) - HEATER_TARGET).abs() <= HEATER_HYSTERESIS);
+}
+
+//Generator and battery at the start of the wire, a lamp at the end
+fn battery_backed_lamp(ecs: &mut World) -> (Entity, Entity) {
+    let lamp = powered_lamp(ecs);
+    let battery = spawner::battery(ecs, Vector3i::new(1, 0, 0));
+
+    common::run_system(ecs, &mut PowerSystem {}, 1);
+
+    (lamp, battery)
+}
+
+fn fail_generator(ecs: &mut World) {
+    let generator = (
+        &ecs.entities(),
+        &ecs.read_storage::<PowerSource>(),
+        !&ecs.read_storage::<Battery>(),
+    )
+        .join()
+        .map(|(entity, ..)| entity)
+        .next()
+        .unwrap();
+
+    ecs.write_storage::<PowerSwitch>()
+        .get_mut(generator)
+        .unwrap()
+        .interact();
+    ecs.write_storage::<PowerNode>()
+        .get_mut(generator)
+        .unwrap()
+        .dirty = true;
+}
+
+#[test]
+fn battery_keeps_lamp_lit_until_drained() {
+    let mut ecs = common::test_world();
+    let (lamp, battery) = battery_backed_lamp(&mut ecs);
+    let capacity = ecs.read_storage::<Battery>().get(battery).unwrap().capacity;
+
+    fail_generator(&mut ecs);
+    common::run_system(&mut ecs, &mut PowerSystem {}, 1);
+
+    assert!(ecs.read_storage::<Illuminant>().get(lamp).unwrap().on);
+    assert!(ecs.read_storage::<Battery>().get(battery).unwrap().charge < capacity);
+    //It only gives what the lamp draws, so nothing is left over
+    assert_eq!(
+        ecs.read_storage::<PowerSource>()
+            .get(battery)
+            .unwrap()
+            .available_wattage,
+        0.0
+    );
+
+    //Enough left for a tick and a half of the lamp
+    ecs.write_storage::<Battery>()
+        .get_mut(battery)
+        .unwrap()
+        .charge = 15.0;
+    common::run_system(&mut ecs, &mut PowerSystem {}, 3);
+
+    assert_eq!(ecs.read_storage::<Battery>().get(battery).unwrap().charge, 0.0);
+    assert!(!ecs.read_storage::<Illuminant>().get(lamp).unwrap().on);
+}
+
+#[test]
+fn battery_covers_a_newly_wired_lamp_straight_away() {
+    let mut ecs = common::test_world();
+    common::set_map(&mut ecs, Map::new());
+
+    //Nothing but a battery on the wire, the circuits it has to go by are only laid out this tick
+    let battery = spawner::battery(&mut ecs, Vector3i::new(0, 0, 0));
+    common::lay_wire(&mut ecs, 0, LAMP_X, false);
+    let lamp = spawner::ceiling_lamp(
+        &mut ecs,
+        Vector3i::new(LAMP_X, 0, 0),
+        1.0,
+        RGB::named(rltk::WHITE).to_rgba(1.0),
+        true,
+    );
+    common::run_system(&mut ecs, &mut PowerSystem {}, 1);
+
+    assert!(ecs.read_storage::<Illuminant>().get(lamp).unwrap().on);
+    assert_eq!(ecs.read_storage::<Battery>().get(battery).unwrap().flow, -10.0);
+}
+
+#[test]
+fn battery_charges_from_surplus() {
+    let mut ecs = common::test_world();
+    let (_, battery) = battery_backed_lamp(&mut ecs);
+    ecs.write_storage::<Battery>()
+        .get_mut(battery)
+        .unwrap()
+        .charge = 0.0;
+
+    common::run_system(&mut ecs, &mut PowerSystem {}, 1);
+
+    //The lamp takes ten of the generator's thousand watts, the rest goes into the battery
+    assert!(!ecs.read_storage::<PowerSource>().get(battery).unwrap().on);
+    let batteries = ecs.read_storage::<Battery>();
+    let battery = batteries.get(battery).unwrap();
+    assert_eq!(battery.charge, 990.0);
+    assert!(battery.state_description().ends_with("charging"));
+}
+
+#[test]
+fn breaker_trips_when_its_circuit_overloads() {
+    let mut ecs = common::test_world();
+    common::set_map(&mut ecs, Map::new());
+
+    //Trunk up to the breaker, a second colour from the breaker to the lamp
+    spawner::power_source(&mut ecs, Vector3i::new(0, 0, 0), true, 1000.0);
+    lay_gauged_wire(&mut ecs, 0, 2, "RED", WireGauge::Standard);
+    spawner::breaker_box(&mut ecs, Vector3i::new(2, 0, 0));
+    lay_gauged_wire(&mut ecs, 2, 4, "BLUE", WireGauge::Standard);
+    let lamp = spawner::ceiling_lamp(
+        &mut ecs,
+        Vector3i::new(4, 0, 0),
+        1.0,
+        RGB::named(rltk::WHITE).to_rgba(1.0),
+        true,
+    );
+
+    let breaker = (&ecs.entities(), &ecs.read_storage::<ControlPanel>())
+        .join()
+        .map(|(entity, _)| entity)
+        .next()
+        .unwrap();
+    ecs.write_storage::<ControlPanel>()
+        .get_mut(breaker)
+        .unwrap()
+        .rated_wattage = 5.0;
+
+    common::run_system(&mut ecs, &mut PowerSystem {}, 1);
+    assert!(ecs.read_storage::<Illuminant>().get(lamp).unwrap().on);
+    assert_eq!(ecs.read_storage::<ControlPanel>().get(breaker).unwrap().load, 10.0);
+
+    common::run_system(&mut ecs, &mut PowerSystem {}, BREAKER_TRIP_TICKS as usize);
+    assert!(!ecs.read_storage::<PowerSwitch>().get(breaker).unwrap().on);
+    assert!(common::log_mentions(&ecs, "A breaker trips"));
+
+    common::run_system(&mut ecs, &mut PowerSystem {}, 1);
+    assert!(!ecs.read_storage::<Illuminant>().get(lamp).unwrap().on);
+}
+
+#[test]
+fn overloaded_light_wire_burns_out() {
+    let mut ecs = common::test_world();
+    common::set_map(&mut ecs, common::corridor_map(4, |_| Atmosphere::new_stp()));
+
+    spawner::power_source(&mut ecs, Vector3i::new(0, 0, 0), true, 5000.0);
+    lay_gauged_wire(&mut ecs, 0, 1, "RED", WireGauge::Standard);
+    lay_gauged_wire(&mut ecs, 2, 3, "RED", WireGauge::Light);
+    let lamp = spawner::ceiling_lamp(
+        &mut ecs,
+        Vector3i::new(3, 0, 0),
+        1.0,
+        RGB::named(rltk::WHITE).to_rgba(1.0),
+        true,
+    );
+    //Twice what the light wire is rated for
+    ecs.write_storage::<PoweredState>()
+        .get_mut(lamp)
+        .unwrap()
+        .wattage = 1000.0;
+
+    common::run_system(&mut ecs, &mut PowerSystem {}, 12);
+
+    let gauges: Vec<WireGauge> = ecs
+        .read_storage::<Wire>()
+        .join()
+        .map(|wire| wire.gauge)
+        .collect();
+    assert_eq!(gauges, vec![WireGauge::Standard, WireGauge::Standard]);
+    assert!(common::log_mentions(&ecs, "A wire burns out"));
+    assert!(!ecs.read_storage::<Illuminant>().get(lamp).unwrap().on);
+
+    //Hot enough to light paper or flammable gas
+    let temperature = ecs
+        .fetch::<Map>()
+        .tiles
+        .get(&Vector3i::new(2, 0, 0))
+        .unwrap()
+        .atmosphere
+        .temperature;
+    assert!(temperature > 573.15);
+}
+
+#[test]
+fn open_breaker_splits_the_circuit() {
+    let mut ecs = common::test_world();
+    common::set_map(&mut ecs, Map::new());
+
+    spawner::power_source(&mut ecs, Vector3i::new(0, 0, 0), true, 1000.0);
+    lay_gauged_wire(&mut ecs, 0, 2, "RED", WireGauge::Standard);
+    spawner::breaker_box(&mut ecs, Vector3i::new(2, 0, 0));
+    lay_gauged_wire(&mut ecs, 2, 4, "BLUE", WireGauge::Standard);
+    let lamp = spawner::ceiling_lamp(
+        &mut ecs,
+        Vector3i::new(4, 0, 0),
+        1.0,
+        RGB::named(rltk::WHITE).to_rgba(1.0),
+        true,
+    );
+    let breaker = (&ecs.entities(), &ecs.read_storage::<ControlPanel>())
+        .join()
+        .map(|(entity, _)| entity)
+        .next()
+        .unwrap();
+    let network_id = |ecs: &World, entity: Entity| {
+        ecs.read_storage::<PowerNode>()
+            .get(entity)
+            .unwrap()
+            .network_id
+    };
+    let source = (&ecs.entities(), &ecs.read_storage::<PowerSource>())
+        .join()
+        .map(|(entity, _)| entity)
+        .next()
+        .unwrap();
+
+    common::run_system(&mut ecs, &mut PowerSystem {}, 1);
+    assert_eq!(network_id(&ecs, lamp), network_id(&ecs, source));
+
+    ecs.write_storage::<PowerSwitch>()
+        .get_mut(breaker)
+        .unwrap()
+        .interact();
+    ecs.write_storage::<PowerNode>()
+        .get_mut(breaker)
+        .unwrap()
+        .dirty = true;
+    common::run_system(&mut ecs, &mut PowerSystem {}, 1);
+
+    assert_ne!(network_id(&ecs, lamp), network_id(&ecs, source));
+    assert!(!ecs.read_storage::<Illuminant>().get(lamp).unwrap().on);
+}
+
+#[test]
+fn graph_follows_wires_laid_and_cut_later() {
+    let mut ecs = common::test_world();
+    common::set_map(&mut ecs, Map::new());
+
+    //A gap in the wire leaves the lamp dark until it is bridged
+    spawner::power_source(&mut ecs, Vector3i::new(0, 0, 0), true, 1000.0);
+    common::lay_wire(&mut ecs, 0, 1, false);
+    common::lay_wire(&mut ecs, 3, LAMP_X, false);
+    let lamp = spawner::ceiling_lamp(
+        &mut ecs,
+        Vector3i::new(LAMP_X, 0, 0),
+        1.0,
+        RGB::named(rltk::WHITE).to_rgba(1.0),
+        true,
+    );
+    common::run_system(&mut ecs, &mut PowerSystem {}, 1);
+    assert!(!ecs.read_storage::<Illuminant>().get(lamp).unwrap().on);
+
+    common::lay_wire(&mut ecs, 2, 2, false);
+    common::run_system(&mut ecs, &mut PowerSystem {}, 1);
+    assert!(ecs.read_storage::<Illuminant>().get(lamp).unwrap().on);
+    assert_eq!(ecs.fetch::<PowerGraph>().wire_count(), 4);
+
+    //Cutting it again, the graph drops the wire on its own
+    let bridge = (
+        &ecs.entities(),
+        &ecs.read_storage::<Wire>(),
+        &ecs.read_storage::<Vector3i>(),
+    )
+        .join()
+        .find(|(_, _, position)| **position == Vector3i::new(2, 0, 0))
+        .map(|(entity, ..)| entity)
+        .unwrap();
+    ecs.delete_entity(bridge).unwrap();
+    common::run_system(&mut ecs, &mut PowerSystem {}, 1);
+
+    assert!(!ecs.read_storage::<Illuminant>().get(lamp).unwrap().on);
+    assert_eq!(ecs.fetch::<PowerGraph>().wire_count(), 3);
+}
+
+#[test]
+fn sources_on_one_tile_share_its_load() {
+    let mut ecs = common::test_world();
+    let lamp = powered_lamp(&mut ecs);
+    spawner::power_source(&mut ecs, Vector3i::new(0, 0, 0), true, 1000.0);
+    common::run_system(&mut ecs, &mut PowerSystem {}, 1);
+
+    //The lamp is only drawn from once however many sources stand at the root of its wire
+    let lamp_wattage = ecs.read_storage::<PoweredState>().get(lamp).unwrap().wattage;
+    let available: Vec<f32> = ecs
+        .read_storage::<PowerSource>()
+        .join()
+        .map(|power_source| power_source.available_wattage)
+        .collect();
+    assert_eq!(available, vec![1000.0 - lamp_wattage / 2.0; 2]);
+}
+
+#[test]
+fn switching_one_circuit_leaves_the_others_alone() {
+    let mut ecs = common::test_world();
+    let lamp = powered_lamp(&mut ecs);
+
+    //A second circuit further down the ship with a lamp of its own
+    let positions: Vec<Vector3i> = (0..=LAMP_X).map(|x| Vector3i::new(x, 5, 0)).collect();
+    spawner::power_source(&mut ecs, positions[0], true, 1000.0);
+    common::lay_wire_at(&mut ecs, &positions, "RED", WireGauge::Standard, false);
+    let other_lamp = spawner::ceiling_lamp(
+        &mut ecs,
+        positions[LAMP_X as usize],
+        1.0,
+        RGB::named(rltk::WHITE).to_rgba(1.0),
+        true,
+    );
+    common::run_system(&mut ecs, &mut PowerSystem {}, 1);
+
+    let at = |ecs: &World, position: Vector3i| {
+        (&ecs.entities(), &ecs.read_storage::<Vector3i>())
+            .join()
+            .filter(|(_, at)| **at == position)
+            .map(|(entity, _)| entity)
+            .collect::<Vec<Entity>>()
+    };
+    let other_source = at(&ecs, positions[0])
+        .into_iter()
+        .find(|entity| ecs.read_storage::<PowerSource>().get(*entity).is_some())
+        .unwrap();
+
+    //Marked so a solve of the first circuit would show
+    let first_wire = at(&ecs, Vector3i::new(0, 0, 0))
+        .into_iter()
+        .find(|entity| ecs.read_storage::<Wire>().get(*entity).is_some())
+        .unwrap();
+    ecs.write_storage::<Wire>()
+        .get_mut(first_wire)
+        .unwrap()
+        .power_load = 1.0;
+
+    ecs.write_storage::<PowerSwitch>()
+        .get_mut(other_lamp)
+        .unwrap()
+        .interact();
+    ecs.write_storage::<PowerNode>()
+        .get_mut(other_lamp)
+        .unwrap()
+        .dirty = true;
+    common::run_system(&mut ecs, &mut PowerSystem {}, 1);
+
+    assert!(!ecs.read_storage::<Illuminant>().get(other_lamp).unwrap().on);
+    assert_eq!(
+        ecs.read_storage::<PowerSource>()
+            .get(other_source)
+            .unwrap()
+            .available_wattage,
+        1000.0
+    );
+    assert!(ecs.read_storage::<Illuminant>().get(lamp).unwrap().on);
+    assert_eq!(
+        ecs.read_storage::<Wire>().get(first_wire).unwrap().power_load,
+        1.0
+    );
+}
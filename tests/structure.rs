@@ -0,0 +1,174 @@
+mod common;
+
+use std::collections::HashMap;
+
+use specs::prelude::*;
+use weirdark::{
+    entities::{
+        atmospherics::{Atmosphere, Gas},
+        components::Viewshed,
+        crew::CrewAi,
+    },
+    map::{
+        structure::{DamageQueue, DamageType, Material},
+        tile_blueprints,
+        zones::AtmosphereZones,
+        Map,
+    },
+    systems::{
+        fire_system::FireSystem,
+        structural_system::{replace_tile, StructuralSystem},
+    },
+    vectors::Vector3i,
+};
+
+const HULL: Vector3i = Vector3i { x: -1, y: 0, z: 0 };
+
+fn damage(ecs: &mut World, position: Vector3i, damage_type: DamageType, amount: f32) {
+    ecs.fetch_mut::<DamageQueue>()
+        .damage(position, damage_type, amount);
+    common::run_system(ecs, &mut StructuralSystem {}, 1);
+}
+
+fn tile_name(ecs: &World, position: Vector3i) -> String {
+    ecs.fetch::<Map>()
+        .tiles
+        .get(&position)
+        .unwrap()
+        .name
+        .clone()
+}
+
+#[test]
+fn destroyed_hull_becomes_debris_and_opens_the_room() {
+    let mut ecs = common::test_world();
+    common::set_map(&mut ecs, common::corridor_map(4, |_| Atmosphere::new_stp()));
+    ecs.fetch_mut::<AtmosphereZones>().dirty = false;
+    let watcher = ecs
+        .create_entity()
+        .with(Vector3i::new(3, 0, 0))
+        .with(Viewshed::new(10, 1, 1.0))
+        .build();
+    ecs.write_storage::<Viewshed>()
+        .get_mut(watcher)
+        .unwrap()
+        .dirty = false;
+
+    //Steel shrugs off half of an impact, this leaves it standing at 50
+    damage(&mut ecs, HULL, DamageType::Impact, 100.0);
+    {
+        let map = ecs.fetch::<Map>();
+        let hull = map.tiles.get(&HULL).unwrap();
+        assert!(!hull.passable);
+        assert!((hull.structure.condition() - 0.5).abs() < 0.01);
+    }
+    assert!(!ecs.fetch::<AtmosphereZones>().dirty);
+
+    damage(&mut ecs, HULL, DamageType::Explosion, 60.0);
+
+    let debris = tile_blueprints::get_tile("debris").unwrap();
+    assert_eq!(tile_name(&ecs, HULL), debris.name);
+    {
+        let map = ecs.fetch::<Map>();
+        let tile = map.tiles.get(&HULL).unwrap();
+        assert!(tile.passable && !tile.airtight && !tile.opaque);
+        assert_eq!(tile.structure.material, Material::Debris);
+    }
+    assert!(ecs.fetch::<AtmosphereZones>().dirty);
+    assert!(ecs.read_storage::<Viewshed>().get(watcher).unwrap().dirty);
+}
+
+#[test]
+fn crew_walking_through_destroyed_tile_finds_a_new_path() {
+    let mut ecs = common::test_world();
+    let mut map = common::corridor_map(4, |_| Atmosphere::new_stp());
+    let debris_position = Vector3i::new(1, 0, 0);
+    map.tiles
+        .insert(debris_position, tile_blueprints::get_tile("debris").unwrap());
+    common::set_map(&mut ecs, map);
+
+    let mut crew_ai = CrewAi::new();
+    crew_ai.path = vec![debris_position, Vector3i::new(2, 0, 0), Vector3i::new(3, 0, 0)];
+    let crew = ecs
+        .create_entity()
+        .with(Vector3i::new(0, 0, 0))
+        .with(crew_ai)
+        .build();
+
+    damage(&mut ecs, debris_position, DamageType::Explosion, 1000.0);
+
+    assert_eq!(tile_name(&ecs, debris_position), "Vacuume");
+    //Only the destination is left, the crew system paths to it again
+    assert_eq!(
+        ecs.read_storage::<CrewAi>().get(crew).unwrap().path,
+        vec![Vector3i::new(3, 0, 0)]
+    );
+}
+
+#[test]
+fn gas_walled_in_with_nowhere_to_go_is_kept() {
+    let mut ecs = common::test_world();
+    common::set_map(&mut ecs, common::corridor_map(1, |_| Atmosphere::new_stp()));
+    let origin = Vector3i::new(0, 0, 0);
+    let mols = common::total_mols(&ecs.fetch::<Map>());
+
+    //The only open tile is walled in
+    {
+        let mut map = ecs.fetch_mut::<Map>();
+        replace_tile(&mut map, origin, tile_blueprints::get_tile("hull").unwrap());
+    }
+    assert!((common::total_mols(&ecs.fetch::<Map>()) - mols).abs() < 0.01);
+
+    //And comes out again once the wall gives way
+    damage(&mut ecs, origin, DamageType::Explosion, 1000.0);
+
+    let map = ecs.fetch::<Map>();
+    let debris = map.tiles.get(&origin).unwrap();
+    assert!(debris.passable);
+    assert!((debris.atmosphere.get_total_mols() - mols).abs() < 0.01);
+}
+
+#[test]
+fn explosions_shatter_glass_before_steel() {
+    let mut ecs = common::test_world();
+    let mut map = common::corridor_map(3, |_| Atmosphere::new_stp());
+    map.tiles.insert(
+        Vector3i::new(3, 0, 0),
+        tile_blueprints::get_tile("glass_hull").unwrap(),
+    );
+    common::set_map(&mut ecs, map);
+
+    ecs.fetch_mut::<DamageQueue>()
+        .explode(Vector3i::new(1, 0, 0), 2, 90.0);
+    common::run_system(&mut ecs, &mut StructuralSystem {}, 1);
+
+    //Glass went straight to open space, the steel end held
+    let vacuume = tile_blueprints::get_tile("vacuume").unwrap();
+    assert_eq!(tile_name(&ecs, Vector3i::new(3, 0, 0)), vacuume.name);
+    assert!(!ecs.fetch::<Map>().tiles.get(&HULL).unwrap().passable);
+    assert!(ecs.fetch::<DamageQueue>().pending.is_empty());
+}
+
+#[test]
+fn fire_weakens_surrounding_walls() {
+    let mut ecs = common::test_world();
+    let map = common::corridor_map(2, |x| {
+        let mut atmosphere = Atmosphere::new_stp();
+
+        if x == 0 {
+            atmosphere.update_gas(&HashMap::from([(Gas::Hydrogen, 2.0)]), 288.15);
+            let temperature = atmosphere.temperature;
+            atmosphere.update_temperature(700.0 - temperature);
+        }
+
+        atmosphere
+    });
+    common::set_map(&mut ecs, map);
+
+    common::run_system(&mut ecs, &mut FireSystem {}, 1);
+    common::run_system(&mut ecs, &mut StructuralSystem {}, 1);
+
+    let map = ecs.fetch::<Map>();
+    let hull = map.tiles.get(&HULL).unwrap();
+    assert!(hull.structure.integrity < hull.structure.max_integrity);
+}
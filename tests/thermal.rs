@@ -0,0 +1,84 @@
+mod common;
+
+use weirdark::{
+    entities::atmospherics::Atmosphere,
+    map::{tile_blueprints, Map},
+    systems::thermal_system::ThermalSystem,
+    vectors::Vector3i,
+};
+
+fn temperature_at(map: &Map, x: i32) -> f32 {
+    map.tiles.get(&Vector3i::new(x, 0, 0)).unwrap().temperature()
+}
+
+fn heated_air(temperature: f32) -> Atmosphere {
+    let mut atmosphere = Atmosphere::new_stp();
+    atmosphere.update_temperature(temperature - atmosphere.temperature);
+    atmosphere
+}
+
+#[test]
+fn heat_conducts_through_walls() {
+    let mut ecs = common::test_world();
+    //Hot air, a hull section, cold air
+    let mut map = common::corridor_map(3, |x| {
+        if x == 0 {
+            heated_air(350.0)
+        } else {
+            heated_air(250.0)
+        }
+    });
+    let mut hull = tile_blueprints::get_tile("hull").unwrap();
+    hull.atmosphere.temperature = 300.0;
+    map.tiles.insert(Vector3i::new(1, 0, 0), hull);
+    common::set_map(&mut ecs, map);
+
+    common::run_system(&mut ecs, &mut ThermalSystem {}, 50);
+
+    let map = ecs.fetch::<Map>();
+    assert!(temperature_at(&map, 0) < 350.0);
+    assert!(temperature_at(&map, 2) > 250.0);
+    assert!(temperature_at(&map, 0) > temperature_at(&map, 2));
+}
+
+#[test]
+fn hull_open_to_space_radiates_and_room_cools() {
+    let mut ecs = common::test_world();
+    //The corridor ends in hull with nothing past it
+    let map = common::corridor_map(2, |_| Atmosphere::new_stp());
+    common::set_map(&mut ecs, map);
+    let start = temperature_at(&ecs.fetch::<Map>(), -1);
+
+    common::run_system(&mut ecs, &mut ThermalSystem {}, 200);
+
+    let map = ecs.fetch::<Map>();
+    assert!(temperature_at(&map, -1) < start);
+    assert!(temperature_at(&map, 0) < 288.15);
+    //Cooling flows inward, the wall is colder than the air it draws heat from
+    assert!(temperature_at(&map, -1) < temperature_at(&map, 0));
+}
+
+#[test]
+fn vacuum_does_not_conduct() {
+    //The far side ends up the same whether the near side is hot or not
+    let far_side = |near_temperature: f32| {
+        let mut ecs = common::test_world();
+        let map = common::corridor_map(3, |x| {
+            if x == 1 {
+                Atmosphere::new_vacuume()
+            } else if x == 0 {
+                heated_air(near_temperature)
+            } else {
+                heated_air(250.0)
+            }
+        });
+        common::set_map(&mut ecs, map);
+
+        common::run_system(&mut ecs, &mut ThermalSystem {}, 20);
+
+        let map = ecs.fetch::<Map>();
+        temperature_at(&map, 2)
+    };
+
+    assert_eq!(far_side(350.0), far_side(250.0));
+}
@@ -0,0 +1,118 @@
+mod common;
+
+use specs::prelude::*;
+use weirdark::{
+    entities::{
+        atmospherics::Atmosphere,
+        biology::{BodyTemperatureState, Breather, Thermoregulation},
+        components::{Health, Name},
+    },
+    gamelog::GameLog,
+    map::Map,
+    raws::spawn_named,
+    systems::thermoregulation_system::ThermoregulationSystem,
+    vectors::Vector3i,
+};
+
+fn air_at(temperature: f32) -> Atmosphere {
+    let mut atmosphere = Atmosphere::new_stp();
+    atmosphere.update_temperature(temperature - atmosphere.temperature);
+    atmosphere
+}
+
+fn set_air_temperature(ecs: &mut World, temperature: f32) {
+    let mut map = ecs.fetch_mut::<Map>();
+    let tile = map.tiles.get_mut(&Vector3i::new(0, 0, 0)).unwrap();
+    tile.atmosphere.update_temperature(temperature - tile.atmosphere.temperature);
+}
+
+fn air_temperature(ecs: &World) -> f32 {
+    let map = ecs.fetch::<Map>();
+    map.tiles.get(&Vector3i::new(0, 0, 0)).unwrap().atmosphere.temperature
+}
+
+fn body_state(ecs: &World, entity: Entity) -> BodyTemperatureState {
+    ecs.read_storage::<Thermoregulation>().get(entity).unwrap().state
+}
+
+fn health_of(ecs: &World, entity: Entity) -> f32 {
+    ecs.read_storage::<Health>().get(entity).unwrap().current
+}
+
+//Runs with the room held at a fixed temperature, as if the walls soaked up the body's heat
+fn run_held_at(ecs: &mut World, temperature: f32, ticks: usize) {
+    for _ in 0..ticks {
+        set_air_temperature(ecs, temperature);
+        common::run_system(ecs, &mut ThermoregulationSystem {}, 1);
+    }
+}
+
+#[test]
+fn freezing_room_causes_hypothermia() {
+    let mut ecs = common::test_world();
+    common::set_map(&mut ecs, common::corridor_map(1, |_| air_at(150.0)));
+    let player = spawn_named(&mut ecs, "player", Vector3i::new(0, 0, 0)).unwrap();
+
+    run_held_at(&mut ecs, 150.0, 1000);
+
+    assert_eq!(body_state(&ecs, player), BodyTemperatureState::Hypothermic);
+    assert!(health_of(&ecs, player) < 100.0);
+    assert!(common::logged(&ecs, "Player shivers"));
+    assert!(common::logged(&ecs, "Player is freezing"));
+}
+
+#[test]
+fn hot_room_causes_hyperthermia() {
+    let mut ecs = common::test_world();
+    common::set_map(&mut ecs, common::corridor_map(1, |_| air_at(400.0)));
+    let player = spawn_named(&mut ecs, "player", Vector3i::new(0, 0, 0)).unwrap();
+
+    run_held_at(&mut ecs, 400.0, 200);
+
+    assert_eq!(body_state(&ecs, player), BodyTemperatureState::Hyperthermic);
+    assert!(health_of(&ecs, player) < 100.0);
+    assert!(common::logged(&ecs, "Player is overheating"));
+
+    //Exhaled air follows the body
+    let breather_temperature = ecs.read_storage::<Breather>().get(player).unwrap().temperature;
+    assert!(breather_temperature > 310.15);
+}
+
+#[test]
+fn body_heat_warms_sealed_room() {
+    let mut ecs = common::test_world();
+    common::set_map(&mut ecs, common::corridor_map(1, |_| Atmosphere::new_stp()));
+    let player = spawn_named(&mut ecs, "player", Vector3i::new(0, 0, 0)).unwrap();
+    let start = air_temperature(&ecs);
+
+    common::run_system(&mut ecs, &mut ThermoregulationSystem {}, 50);
+
+    assert!(air_temperature(&ecs) > start);
+    assert_eq!(body_state(&ecs, player), BodyTemperatureState::Normal);
+}
+
+#[test]
+fn body_without_vitals_dies_once() {
+    let mut ecs = common::test_world();
+    common::set_map(&mut ecs, common::corridor_map(1, |_| air_at(150.0)));
+    let mut body = Thermoregulation::new_humanlike();
+    body.temperature = 250.0;
+    ecs.create_entity()
+        .with(Vector3i::new(0, 0, 0))
+        .with(Name {
+            name: "Dummy".to_string(),
+        })
+        .with(body)
+        .with(Health::new(1.0))
+        .build();
+
+    run_held_at(&mut ecs, 150.0, 10);
+
+    let deaths = ecs
+        .fetch::<GameLog>()
+        .entries
+        .iter()
+        .filter(|entry| *entry == "Dummy dies")
+        .count();
+    assert_eq!(deaths, 1);
+}